@@ -0,0 +1,159 @@
+//! Feeds arbitrary operations through the high-level `Lcd` API and checks the bytes written to
+//! the port expander.
+//!
+//! The display runs headless, so nothing reaches a bus, but the observer still sees every
+//! write. For each one it checks that:
+//!
+//! * an enable pulse is always released by the next write, with the other pins unchanged,
+//! * the RS pin stays the same across the six writes that send one command or data byte,
+//!   whether those are separate transactions or part of a batched row,
+//! * the RW pin is never raised, since readback is off.
+//!
+//! Run with `cargo fuzz run encoder` from the `lcd_i2c_rs` directory.
+
+#![no_main]
+
+use core::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use esp_idf_hal::sys::{EspError, ESP_FAIL};
+use lcd_i2c_rs::{Lcd, Observer};
+use libfuzzer_sys::fuzz_target;
+
+const RS: u8 = 0x01;
+const RW: u8 = 0x02;
+const EN: u8 = 0x04;
+
+/// The number of expander writes that send one byte: two nibbles of data, EN high, EN low.
+const WRITES_PER_BYTE: u8 = 6;
+
+#[derive(Default)]
+struct Checker {
+    /// The last write, if it raised EN.
+    enabled: Cell<Option<u8>>,
+    /// The RS level of each byte announced but not yet fully written.
+    bytes: RefCell<VecDeque<bool>>,
+    /// How many writes of the first announced byte have been seen.
+    written: Cell<u8>,
+}
+
+impl Checker {
+    fn start_byte(&self, rs: bool) {
+        assert!(self.enabled.get().is_none(), "byte started inside an EN pulse");
+        self.bytes.borrow_mut().push_back(rs);
+    }
+
+    fn finish(&self) {
+        assert!(self.enabled.get().is_none(), "EN left high");
+        assert!(self.bytes.borrow().is_empty(), "byte cut short");
+    }
+}
+
+impl Observer for Checker {
+    fn on_command(&self, _byte: u8) {
+        self.start_byte(false);
+    }
+
+    fn on_data(&self, _byte: u8) {
+        self.start_byte(true);
+    }
+
+    fn on_i2c_write(&self, bytes: &[u8]) {
+        let [0, pins @ ..] = bytes else {
+            panic!("unexpected expander write {:02x?}", bytes);
+        };
+        assert!(!pins.is_empty(), "empty expander write");
+        for &pins in pins {
+            assert_eq!(pins & RW, 0, "RW raised without readback");
+            match self.enabled.take() {
+                Some(high) => assert_eq!(pins, high & !EN, "EN pulse not released cleanly"),
+                None if pins & EN != 0 => self.enabled.set(Some(pins)),
+                None => {}
+            }
+            let mut queue = self.bytes.borrow_mut();
+            if let Some(&rs) = queue.front() {
+                assert_eq!(pins & RS != 0, rs, "RS changed within a byte");
+                self.written.set(self.written.get() + 1);
+                if self.written.get() == WRITES_PER_BYTE {
+                    queue.pop_front();
+                    self.written.set(0);
+                }
+            }
+        }
+    }
+}
+
+/// Reads the fuzz input a byte at a time, yielding zeros once it runs out.
+struct Input<'d>(&'d [u8]);
+
+impl Input<'_> {
+    fn byte(&mut self) -> u8 {
+        let (&first, rest) = self.0.split_first().unwrap_or((&0, &[]));
+        self.0 = rest;
+        first
+    }
+
+    fn bytes(&mut self, max: usize) -> &[u8] {
+        let len = (self.byte() as usize).min(max).min(self.0.len());
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        taken
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let checker = Checker::default();
+    let mut input = Input(data);
+    let cols = [8, 16, 20, 24, 40][input.byte() as usize % 5];
+    let rows = input.byte() % 4 + 1;
+
+    let mut lcd = Lcd::new(Err(EspError::from_infallible::<ESP_FAIL>()), cols, rows);
+    lcd.probe();
+    lcd.set_observer(&checker);
+    let _ = lcd.init();
+
+    while !input.0.is_empty() {
+        // Errors are fine, panics and broken pulses are not.
+        let _ = match input.byte() % 21 {
+            0 => lcd.clear(),
+            1 => lcd.home(),
+            2 => {
+                let (col, row) = (input.byte(), input.byte());
+                lcd.set_cursor(col, row)
+            }
+            3 => lcd.write_byte(input.byte()),
+            4 => {
+                let text = String::from_utf8_lossy(input.bytes(64)).into_owned();
+                lcd.print_str(&text)
+            }
+            5 => {
+                let text = String::from_utf8_lossy(input.bytes(128)).into_owned();
+                lcd.print_long_str(&text)
+            }
+            6 => {
+                let location = input.byte();
+                let bitmap = input.bytes(64).to_vec();
+                lcd.create_custom_chars(location, &bitmap)
+            }
+            7 => lcd.cursor(input.byte() & 1 != 0),
+            8 => lcd.blink(input.byte() & 1 != 0),
+            9 => lcd.autoscroll(input.byte() & 1 != 0),
+            10 => lcd.scroll_left(),
+            11 => lcd.scroll_right(),
+            12 => lcd.left_to_right(),
+            13 => lcd.right_to_left(),
+            14 => lcd.backlight_on(),
+            15 => lcd.backlight_off(),
+            16 => lcd.display_on(),
+            17 => lcd.display_off(),
+            18 => lcd.newline(),
+            19 => {
+                let row = input.byte();
+                let codes = input.bytes(48).to_vec();
+                lcd.write_row(row, &codes)
+            }
+            _ => lcd.advance(),
+        };
+    }
+    checker.finish();
+});
@@ -0,0 +1,145 @@
+use crate::charset::DisplayWidth;
+use crate::{AnimClock, Frame, Moment, RefreshScheduler};
+use std::time::Duration;
+
+/// How an alert draws attention to itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Blink {
+    /// The alert is shown steadily.
+    #[default]
+    Off,
+    /// The alert text blinks.
+    Text,
+    /// The alert is shown steadily and the backlight flashes.
+    Backlight,
+}
+
+/// An alert taking over the display from a [`RefreshScheduler`].
+pub(crate) struct Alert {
+    message: Frame,
+    blank: Frame,
+    started: Moment,
+    duration: Duration,
+    blink: Blink,
+    on: bool,
+    backlight_was_on: bool,
+}
+
+impl Alert {
+    /// Returns the frame to show in the current blink phase.
+    pub(crate) fn frame(&self) -> &Frame {
+        if self.on || self.blink != Blink::Text {
+            &self.message
+        } else {
+            &self.blank
+        }
+    }
+}
+
+impl RefreshScheduler<'_> {
+    /// Shows a message over the whole display for a while, then restores the frame it
+    /// replaced.
+    ///
+    /// The message is centered, one line per `\n`. While the alert is up the application keeps
+    /// drawing and presenting as usual; its frames are held back and the latest one is shown
+    /// when the alert ends. Raising an alert while another is shown replaces it.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The message.
+    /// * `duration` - How long the alert is shown.
+    /// * `blink` - How the alert draws attention.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the alert is raised.
+    /// * `Err(anyhow::Error)` - If a character cannot be shown and the fallback policy is [`Fallback::Error`](crate::charset::Fallback::Error), or there is an error while switching the backlight.
+    pub fn alert(&mut self, text: &str, duration: Duration, blink: Blink) -> anyhow::Result<()> {
+        let (cols, rows) = (self.front.cols(), self.front.rows());
+        let charmap = *self.lcd.charmap();
+        let mut message = Frame::new(cols, rows);
+        let lines = text.lines().count().min(rows as usize) as u8;
+        let top = (rows - lines) / 2;
+        for (i, line) in text.lines().take(lines as usize).enumerate() {
+            let width = line.display_width(&charmap).min(cols as usize) as u8;
+            message.print((cols - width) / 2, top + i as u8, line, &charmap)?;
+        }
+
+        let backlight_was_on = match &self.alert {
+            Some(alert) => alert.backlight_was_on,
+            None => self.lcd.is_backlight_on(),
+        };
+        if blink == Blink::Backlight {
+            self.set_backlight(true)?;
+        } else if self
+            .alert
+            .as_ref()
+            .is_some_and(|alert| alert.blink == Blink::Backlight)
+        {
+            self.set_backlight(backlight_was_on)?;
+        }
+        self.alert = Some(Alert {
+            message,
+            blank: Frame::new(cols, rows),
+            started: AnimClock::now(),
+            duration,
+            blink,
+            on: true,
+            backlight_was_on,
+        });
+        self.shown = false;
+        Ok(())
+    }
+
+    /// Ends the alert early, restoring the application's latest frame.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the alert is dismissed, or none was shown.
+    /// * `Err(anyhow::Error)` - If there is an error while switching the backlight back.
+    pub fn dismiss_alert(&mut self) -> anyhow::Result<()> {
+        if let Some(alert) = self.alert.take() {
+            if alert.blink == Blink::Backlight {
+                self.set_backlight(alert.backlight_was_on)?;
+            }
+            self.shown = *self.source() == self.front;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` while an alert is shown.
+    pub fn is_alerting(&self) -> bool {
+        self.alert.is_some()
+    }
+
+    /// Advances the blinking of the alert and ends it once it expires.
+    pub(crate) fn update_alert(&mut self) -> anyhow::Result<()> {
+        let Some(alert) = &mut self.alert else {
+            return Ok(());
+        };
+        let elapsed = alert.started.elapsed();
+        if elapsed >= alert.duration {
+            return self.dismiss_alert();
+        }
+
+        let on = alert.blink == Blink::Off || AnimClock::blink_on();
+        if on == alert.on {
+            return Ok(());
+        }
+        alert.on = on;
+        match alert.blink {
+            Blink::Off => {}
+            Blink::Text => self.shown = false,
+            Blink::Backlight => self.set_backlight(on)?,
+        }
+        Ok(())
+    }
+
+    fn set_backlight(&mut self, on: bool) -> anyhow::Result<()> {
+        if on {
+            self.lcd.backlight_on()
+        } else {
+            self.lcd.backlight_off()
+        }
+    }
+}
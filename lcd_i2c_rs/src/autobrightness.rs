@@ -0,0 +1,132 @@
+use crate::Lcd;
+use std::time::{Duration, Instant};
+
+/// Sets the backlight brightness from an ambient light sensor.
+///
+/// Every interval the callback is asked for a sample, in lux or raw ADC counts, which the curve
+/// maps to a brightness level for [`Lcd::set_brightness`]. To keep the backlight from
+/// flickering when the light hovers around a point of the curve, a new level is only applied
+/// once the sample has moved by more than the hysteresis since the level was last set.
+///
+/// # Example
+///
+/// ```ignore
+/// const CURVE: [(f32, u8); 3] = [(0.0, 8), (50.0, 96), (400.0, 255)];
+/// let mut auto = AutoBrightness::new(|| read_lux(&mut sensor), &CURVE);
+/// loop {
+///     auto.tick(&mut lcd)?;
+/// }
+/// ```
+pub struct AutoBrightness<'c, F> {
+    sample: F,
+    curve: &'c [(f32, u8)],
+    hysteresis: f32,
+    interval: Duration,
+    last_sample: Option<Instant>,
+    anchor: Option<f32>,
+}
+
+impl<'c, F> AutoBrightness<'c, F>
+where
+    F: FnMut() -> anyhow::Result<f32>,
+{
+    /// Creates a controller sampling once a second with 20% hysteresis.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample` - Returns the current sensor reading.
+    /// * `curve` - Points of `(reading, brightness)` sorted by reading. Readings between two
+    ///   points are interpolated, readings outside the curve take the nearest end.
+    pub fn new(sample: F, curve: &'c [(f32, u8)]) -> Self {
+        Self {
+            sample,
+            curve,
+            hysteresis: 0.2,
+            interval: Duration::from_secs(1),
+            last_sample: None,
+            anchor: None,
+        }
+    }
+
+    /// Sets how much the reading has to move before the brightness follows.
+    ///
+    /// # Arguments
+    ///
+    /// * `fraction` - The change relative to the reading the current level was set for, e.g.
+    ///   0.2 for 20%. Readings below 1 are compared as if they were 1.
+    pub fn with_hysteresis(mut self, fraction: f32) -> Self {
+        self.hysteresis = fraction.max(0.0);
+        self
+    }
+
+    /// Sets how often the sensor is read.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The time between two samples.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the brightness the curve gives for a reading.
+    ///
+    /// # Arguments
+    ///
+    /// * `reading` - The sensor reading.
+    pub fn level_for(&self, reading: f32) -> u8 {
+        let Some(&(first_at, first_level)) = self.curve.first() else {
+            return u8::MAX;
+        };
+        if reading <= first_at {
+            return first_level;
+        }
+        for pair in self.curve.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            if reading <= x1 {
+                let t = if x1 > x0 {
+                    (reading - x0) / (x1 - x0)
+                } else {
+                    1.0
+                };
+                return (y0 as f32 + (y1 as f32 - y0 as f32) * t).round() as u8;
+            }
+        }
+        self.curve[self.curve.len() - 1].1
+    }
+
+    /// Reads the sensor if the interval has passed and adjusts the brightness.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `true` if the brightness was changed.
+    /// * `Err(anyhow::Error)` - If the sensor cannot be read or there is an error while setting the brightness.
+    pub fn tick(&mut self, lcd: &mut Lcd) -> anyhow::Result<bool> {
+        let now = Instant::now();
+        if let Some(last) = self.last_sample {
+            if now.duration_since(last) < self.interval {
+                return Ok(false);
+            }
+        }
+        self.last_sample = Some(now);
+
+        let reading = (self.sample)()?;
+        if let Some(anchor) = self.anchor {
+            if (reading - anchor).abs() <= anchor.max(1.0) * self.hysteresis {
+                return Ok(false);
+            }
+        }
+        self.anchor = Some(reading);
+
+        let level = self.level_for(reading);
+        if level == lcd.brightness() {
+            return Ok(false);
+        }
+        lcd.set_brightness(level)?;
+        Ok(true)
+    }
+}
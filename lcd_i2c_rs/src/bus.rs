@@ -0,0 +1,104 @@
+use crate::Lcd;
+use core::cell::RefCell;
+use esp_idf_hal::delay::BLOCK;
+use esp_idf_hal::i2c::I2cDriver;
+use esp_idf_hal::sys::EspError;
+
+/// The addresses a PCF8574 (0x20-0x27) or PCF8574A (0x38-0x3f) backpack can be strapped to.
+const EXPANDER_ADDRESSES: [core::ops::RangeInclusive<u8>; 2] = [0x20..=0x27, 0x38..=0x3f];
+
+/// The I2C bus a driver talks on: its own, or one shared with other displays.
+pub(crate) enum Bus<'a> {
+    Owned(Result<I2cDriver<'a>, EspError>),
+    Shared(&'a dyn SharedI2c),
+}
+
+impl Bus<'_> {
+    /// Returns `true` if there is a driver to talk through.
+    pub(crate) fn is_ok(&self) -> bool {
+        match self {
+            Bus::Owned(i2c) => i2c.is_ok(),
+            Bus::Shared(_) => true,
+        }
+    }
+
+    pub(crate) fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), EspError> {
+        match self {
+            Bus::Owned(Ok(i2c)) => i2c.write(address, bytes, BLOCK),
+            // The driver could not be created; every transaction fails the way creating it did.
+            Bus::Owned(Err(err)) => Err(*err),
+            Bus::Shared(bus) => bus.write(address, bytes),
+        }
+    }
+
+    pub(crate) fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), EspError> {
+        match self {
+            Bus::Owned(Ok(i2c)) => i2c.read(address, buffer, BLOCK),
+            Bus::Owned(Err(err)) => Err(*err),
+            Bus::Shared(bus) => bus.read(address, buffer),
+        }
+    }
+}
+
+/// An I2C driver borrowed by several displays, one transaction at a time.
+pub(crate) trait SharedI2c {
+    fn write(&self, address: u8, bytes: &[u8]) -> Result<(), EspError>;
+    fn read(&self, address: u8, buffer: &mut [u8]) -> Result<(), EspError>;
+}
+
+impl SharedI2c for RefCell<I2cDriver<'_>> {
+    fn write(&self, address: u8, bytes: &[u8]) -> Result<(), EspError> {
+        self.borrow_mut().write(address, bytes, BLOCK)
+    }
+
+    fn read(&self, address: u8, buffer: &mut [u8]) -> Result<(), EspError> {
+        self.borrow_mut().read(address, buffer, BLOCK)
+    }
+}
+
+impl<'a> Lcd<'a> {
+    /// Finds every display on a bus and returns them initialized, in address order.
+    ///
+    /// Scans the addresses a PCF8574 (0x20-0x27) or PCF8574A (0x38-0x3f) backpack can be
+    /// strapped to, so up to 16 displays can share one bus. Every expander that answers is
+    /// taken for a display, so other port expanders on the bus, such as one for a keypad, must
+    /// sit at addresses outside these ranges.
+    ///
+    /// # Arguments
+    ///
+    /// * `bus` - The I2C driver, shared by the displays found.
+    /// * `cols` - The number of columns of every display.
+    /// * `rows` - The number of rows of every display.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok([Option<Lcd>; N])` - The displays found, from the first element on; the rest are `None`, as are any found beyond `N`.
+    /// * `Err(anyhow::Error)` - If a display that answered fails to initialize.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let bus = RefCell::new(I2cDriver::new(peripherals.i2c0, sda, scl, &config)?);
+    /// let [kitchen, hall, ..] = Lcd::enumerate::<4>(&bus, 16, 2)?;
+    /// for (lcd, name) in [kitchen, hall].iter_mut().flatten().zip(["Kitchen", "Hall"]) {
+    ///     lcd.print_str(name)?;
+    /// }
+    /// ```
+    pub fn enumerate<const N: usize>(
+        bus: &'a RefCell<I2cDriver<'_>>,
+        cols: u8,
+        rows: u8,
+    ) -> anyhow::Result<[Option<Lcd<'a>>; N]> {
+        let mut found = [(); N].map(|_| None);
+        let addresses = EXPANDER_ADDRESSES.into_iter().flatten();
+        for (slot, mut lcd) in found.iter_mut().zip(
+            addresses
+                .map(|address| Lcd::shared(bus, address, cols, rows))
+                .filter_map(|mut lcd| lcd.probe().then_some(lcd)),
+        ) {
+            lcd.init()?;
+            *slot = Some(lcd);
+        }
+        Ok(found)
+    }
+}
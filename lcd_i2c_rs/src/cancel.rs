@@ -0,0 +1,93 @@
+use crate::Lcd;
+use core::sync::atomic::{AtomicBool, Ordering};
+use esp_idf_hal::delay::Ets;
+
+/// The longest a cancelable delay sleeps before checking its token again.
+const POLL_MS: u32 = 10;
+
+/// A flag that stops long-running display operations early.
+///
+/// Register a token with [`Lcd::set_cancel_token`]. Printing methods check it between
+/// characters, and the character set demo and self-test between steps and during their
+/// delays; once it is cancelled they stop and return an error. Cancelling takes `&self`, so
+/// a `static` token can be cancelled from a button handler or another task while a long
+/// print runs. The token stays cancelled until [`reset`](Self::reset).
+///
+/// Animations driven by `tick` methods, such as transitions, screensavers and scrolling
+/// widgets, never block, so they are preempted simply by not ticking them.
+///
+/// # Example
+///
+/// ```ignore
+/// static CANCEL: CancelToken = CancelToken::new();
+///
+/// button.on_press(|| CANCEL.cancel());
+/// lcd.set_cancel_token(&CANCEL);
+/// if lcd.print_long_str(manual).is_err() && CANCEL.is_cancelled() {
+///     CANCEL.reset();
+///     show_menu(&mut lcd)?;
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct CancelToken(AtomicBool);
+
+impl CancelToken {
+    /// Creates a token that is not cancelled.
+    pub const fn new() -> Self {
+        Self(AtomicBool::new(false))
+    }
+
+    /// Asks the operations checking this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Returns `true` once [`cancel`](Self::cancel) has been called, until the next
+    /// [`reset`](Self::reset).
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Clears the cancellation, so operations run to completion again.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl<'a> Lcd<'a> {
+    /// Sets a token that stops long prints, the character set demo and the self-test when
+    /// cancelled, see [`CancelToken`].
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token to check.
+    pub fn set_cancel_token(&mut self, token: &'a CancelToken) {
+        self.cancel = Some(token);
+    }
+
+    /// Removes the cancel token.
+    pub fn clear_cancel_token(&mut self) {
+        self.cancel = None;
+    }
+
+    /// Returns an error if the cancel token has been cancelled.
+    pub(crate) fn check_cancel(&self) -> anyhow::Result<()> {
+        match self.cancel {
+            Some(token) if token.is_cancelled() => Err(anyhow::anyhow!("Cancelled")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Waits for `ms` milliseconds, returning early with an error if the cancel token is
+    /// cancelled meanwhile.
+    pub(crate) fn delay_ms(&self, ms: u32) -> anyhow::Result<()> {
+        let mut left = ms;
+        while left > 0 {
+            self.check_cancel()?;
+            let step = left.min(POLL_MS);
+            Ets::delay_ms(step);
+            left -= step;
+        }
+        self.check_cancel()
+    }
+}
@@ -0,0 +1,149 @@
+//! Mapping of Rust characters to the display's character codes.
+
+/// What to do with a character that has no code on the display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fallback {
+    /// Leave the character out.
+    Skip,
+    /// Print the given character code instead.
+    Replace(u8),
+    /// Fail with an error.
+    Error,
+}
+
+impl Default for Fallback {
+    fn default() -> Self {
+        Fallback::Replace(b'?')
+    }
+}
+
+/// Common typographic characters mapped to their closest ASCII codes.
+///
+/// Pass it to [`Lcd::set_substitutions`](crate::Lcd::set_substitutions) or use it as a starting
+/// point for your own table.
+pub const TYPOGRAPHIC_SUBSTITUTIONS: &[(char, u8)] = &[
+    ('\u{2018}', b'\''),
+    ('\u{2019}', b'\''),
+    ('\u{201c}', b'"'),
+    ('\u{201d}', b'"'),
+    ('\u{2013}', b'-'),
+    ('\u{2014}', b'-'),
+    ('\u{2026}', b'.'),
+    ('\u{00a0}', b' '),
+];
+
+/// Encodes characters for the display using a substitution table and a [`Fallback`].
+///
+/// Characters found in the substitution table are mapped first, so a table can also override
+/// ASCII characters that the ROM draws differently (such as `\` showing as `¥` on A00 ROMs).
+/// Other ASCII characters and the custom character codes 0-7 are passed through unchanged.
+/// Everything else goes to the fallback policy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CharMap<'a> {
+    substitutions: &'a [(char, u8)],
+    fallback: Fallback,
+}
+
+impl<'a> CharMap<'a> {
+    /// Creates a map with the given substitutions and fallback policy.
+    pub const fn new(substitutions: &'a [(char, u8)], fallback: Fallback) -> Self {
+        Self {
+            substitutions,
+            fallback,
+        }
+    }
+
+    /// Returns the substitution table.
+    pub fn substitutions(&self) -> &'a [(char, u8)] {
+        self.substitutions
+    }
+
+    /// Replaces the substitution table.
+    pub fn set_substitutions(&mut self, substitutions: &'a [(char, u8)]) {
+        self.substitutions = substitutions;
+    }
+
+    /// Returns the fallback policy.
+    pub fn fallback(&self) -> Fallback {
+        self.fallback
+    }
+
+    /// Replaces the fallback policy.
+    pub fn set_fallback(&mut self, fallback: Fallback) {
+        self.fallback = fallback;
+    }
+
+    /// Returns the number of display cells `ch` occupies: 0 if it is skipped, 1 otherwise.
+    pub fn width(&self, ch: char) -> usize {
+        usize::from(!matches!(self.encode(ch), Ok(None)))
+    }
+
+    /// Returns the longest prefix of `text` that fits in `cells` display cells, and its width.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to truncate.
+    /// * `cells` - The number of display cells available.
+    pub fn truncate<'s>(&self, text: &'s str, cells: usize) -> (&'s str, usize) {
+        let mut used = 0;
+        for (i, ch) in text.char_indices() {
+            let width = self.width(ch);
+            if used + width > cells {
+                return (&text[..i], used);
+            }
+            used += width;
+        }
+        (text, used)
+    }
+
+    /// Returns the code to print for `ch`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(u8))` - The character code to print.
+    /// * `Ok(None)` - If the character should be skipped.
+    /// * `Err(anyhow::Error)` - If the character has no code and the policy is [`Fallback::Error`].
+    pub fn encode(&self, ch: char) -> anyhow::Result<Option<u8>> {
+        if let Some(&(_, code)) = self.substitutions.iter().find(|(from, _)| *from == ch) {
+            return Ok(Some(code));
+        }
+        if ch.is_ascii() {
+            return Ok(Some(ch as u8));
+        }
+        match self.fallback {
+            Fallback::Skip => Ok(None),
+            Fallback::Replace(code) => Ok(Some(code)),
+            Fallback::Error => Err(anyhow::anyhow!(
+                "Character {:?} cannot be shown on the display",
+                ch
+            )),
+        }
+    }
+}
+
+/// Measures text in display cells rather than bytes or `char`s.
+///
+/// Most characters take one cell, whatever their UTF-8 length, and characters skipped under
+/// [`Fallback::Skip`] take none. Widgets use this to truncate and pad text to field widths.
+///
+/// # Example
+///
+/// ```ignore
+/// let cells = "Café".display_width(lcd.charmap()); // 4, although the string is 5 bytes
+/// ```
+pub trait DisplayWidth {
+    /// Returns the number of display cells the text occupies under `charmap`.
+    fn display_width(&self, charmap: &CharMap<'_>) -> usize;
+}
+
+impl DisplayWidth for char {
+    fn display_width(&self, charmap: &CharMap<'_>) -> usize {
+        charmap.width(*self)
+    }
+}
+
+impl DisplayWidth for str {
+    fn display_width(&self, charmap: &CharMap<'_>) -> usize {
+        self.chars().map(|ch| charmap.width(ch)).sum()
+    }
+}
@@ -0,0 +1,206 @@
+//! The time animations run on.
+
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+#[cfg(feature = "virtual-time")]
+use std::cell::Cell;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Whether the time is fed by the application instead of read from the system.
+static FED: AtomicBool = AtomicBool::new(false);
+/// The fed time, in milliseconds.
+static MILLIS: AtomicU32 = AtomicU32::new(0);
+/// When the system time started counting, on its first reading.
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+/// How long blinking elements stay shown, and hidden, in milliseconds.
+static BLINK_MS: AtomicU32 = AtomicU32::new(500);
+
+#[cfg(feature = "virtual-time")]
+std::thread_local! {
+    /// The virtual time of the calling thread, in milliseconds, while it runs on one.
+    static VIRTUAL: Cell<Option<u32>> = const { Cell::new(None) };
+}
+
+/// The clock every animation of the crate runs on: blinking cursors and alerts, fades, status
+/// light pulses, screensaver steps, alternating values and timed transitions.
+///
+/// Animation speeds are given in milliseconds and worked out from this clock, never from how
+/// often the main loop runs, so a cursor blinks at the same rate whether the loop takes 1 ms or
+/// 80 ms. By default the clock follows the system time. An application with its own time
+/// base, such as embassy, can [`feed`](Self::feed) it instead, once per pass of the main loop;
+/// every animation then sees the same time throughout the pass.
+///
+/// Widgets with a `tick(elapsed_ms)` method, such as the [`Reader`](crate::widgets::Reader),
+/// take their time from a [`Moment`] kept by the application, see [`Moment::lap`].
+///
+/// Everything that blinks, such as edit cursors, alerts, dialog options and the digits of a
+/// pairing code, takes its phase from [`blink_on`](Self::blink_on), so they all blink in unison
+/// at the rate set with [`set_blink_rate`](Self::set_blink_rate).
+///
+/// The time is shared by the whole program and counts milliseconds in a `u32`, which wraps
+/// after about 49 days; durations between moments stay right across the wrap.
+///
+/// With the `virtual-time` feature, a host test can [`start_virtual`](Self::start_virtual)
+/// time on its own thread and [`step`](Self::step) it by hand. Refresh rate caps, compositor
+/// intervals, toast and alert expiry, screensaver activation and every animation then happen
+/// at exact, repeatable ticks, whatever the machine running the test. Tests run in parallel
+/// threads each keep their own virtual time.
+///
+/// # Example
+///
+/// ```ignore
+/// loop {
+///     AnimClock::feed(embassy_time::Instant::now().as_millis() as u32);
+///     compositor.tick()?;
+///     light.tick()?;
+/// }
+/// ```
+#[derive(Debug)]
+pub enum AnimClock {}
+
+impl AnimClock {
+    /// Returns the current time.
+    pub fn now() -> Moment {
+        #[cfg(feature = "virtual-time")]
+        if let Some(millis) = VIRTUAL.with(Cell::get) {
+            return Moment(millis);
+        }
+        if FED.load(Ordering::Acquire) {
+            return Moment(MILLIS.load(Ordering::Acquire));
+        }
+        let epoch = EPOCH.get_or_init(Instant::now);
+        Moment(epoch.elapsed().as_millis() as u32)
+    }
+
+    /// Sets the time from the application's clock. From the first call on, the clock only
+    /// moves when fed.
+    ///
+    /// # Arguments
+    ///
+    /// * `millis` - The application's time in milliseconds, from any starting point.
+    pub fn feed(millis: u32) {
+        MILLIS.store(millis, Ordering::Release);
+        FED.store(true, Ordering::Release);
+    }
+
+    /// Moves a fed clock on by `by`, or starts feeding it from the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `by` - The time that passed, counted in whole milliseconds.
+    pub fn advance(by: Duration) {
+        let now = Self::now().0;
+        Self::feed(now.wrapping_add(by.as_millis() as u32));
+    }
+
+    /// Makes the clock follow the system time again after being fed.
+    pub fn follow_system() {
+        FED.store(false, Ordering::Release);
+    }
+
+    /// Sets how long blinking elements stay shown, and then hidden, 500 ms by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `half` - Half of the blink period, counted in whole milliseconds and at least 1.
+    pub fn set_blink_rate(half: Duration) {
+        BLINK_MS.store((half.as_millis() as u32).max(1), Ordering::Release);
+    }
+
+    /// Returns how long blinking elements stay shown, and then hidden.
+    pub fn blink_rate() -> Duration {
+        Duration::from_millis(BLINK_MS.load(Ordering::Acquire).into())
+    }
+
+    /// Returns `true` in the shown half of the blink cycle every blinking element shares.
+    ///
+    /// The cycle is counted from time 0 of the clock, not from when an element started
+    /// blinking, so two elements never blink out of step.
+    pub fn blink_on() -> bool {
+        (Self::now().0 / BLINK_MS.load(Ordering::Acquire)) % 2 == 0
+    }
+
+    /// Stops time at 0 on the calling thread until it is [`step`](Self::step)ped, for host
+    /// tests that need exact frames at exact ticks (`virtual-time` feature).
+    ///
+    /// Start virtual time before creating the scheduler, widgets and screens under test, so
+    /// the moments they keep are on the virtual clock.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// AnimClock::start_virtual();
+    /// let mut scheduler = RefreshScheduler::new(lcd);
+    /// scheduler.toast("Saved", Duration::from_secs(2));
+    /// scheduler.tick()?;
+    /// AnimClock::step(Duration::from_millis(1999));
+    /// scheduler.tick()?;
+    /// assert_eq!(scheduler.lcd().screenshot().row(1), b"     Saved      ");
+    /// AnimClock::step(Duration::from_millis(1));
+    /// scheduler.tick()?;
+    /// ```
+    #[cfg(feature = "virtual-time")]
+    pub fn start_virtual() {
+        VIRTUAL.with(|time| time.set(Some(0)));
+    }
+
+    /// Moves the virtual time of the calling thread on by `by`, starting it at 0 if it is not
+    /// running yet (`virtual-time` feature).
+    ///
+    /// # Arguments
+    ///
+    /// * `by` - The time that passes, counted in whole milliseconds.
+    #[cfg(feature = "virtual-time")]
+    pub fn step(by: Duration) {
+        VIRTUAL.with(|time| {
+            let now = time.get().unwrap_or(0);
+            time.set(Some(now.wrapping_add(by.as_millis() as u32)));
+        });
+    }
+
+    /// Returns the calling thread to the shared clock (`virtual-time` feature).
+    #[cfg(feature = "virtual-time")]
+    pub fn stop_virtual() {
+        VIRTUAL.with(|time| time.set(None));
+    }
+}
+
+/// A point in time on the [`AnimClock`], used like [`Instant`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Moment(u32);
+
+impl Moment {
+    /// Returns the time since this moment.
+    pub fn elapsed(&self) -> Duration {
+        AnimClock::now().duration_since(*self)
+    }
+
+    /// Returns the time from `earlier` to this moment.
+    ///
+    /// # Arguments
+    ///
+    /// * `earlier` - A moment before this one.
+    pub fn duration_since(&self, earlier: Moment) -> Duration {
+        Duration::from_millis(self.0.wrapping_sub(earlier.0).into())
+    }
+
+    /// Returns the milliseconds since this moment and moves it to now, for feeding widgets
+    /// that count time themselves.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut last = AnimClock::now();
+    /// loop {
+    ///     if reader.tick(last.lap()) {
+    ///         reader.render(&mut lcd)?;
+    ///     }
+    /// }
+    /// ```
+    pub fn lap(&mut self) -> u32 {
+        let now = AnimClock::now();
+        let elapsed = now.0.wrapping_sub(self.0);
+        *self = now;
+        elapsed
+    }
+}
@@ -0,0 +1,107 @@
+use crate::consts::*;
+
+/// An HD44780 instruction, for sending with [`Lcd::raw_command`](crate::Lcd::raw_command).
+///
+/// These are the instructions of the standard instruction set, with named fields instead of
+/// bit masks. The interface width is not among them: the I2C backpack only wires four data
+/// lines, so [`FunctionSet`](Command::FunctionSet) always selects the 4-bit interface.
+///
+/// # Example
+///
+/// ```ignore
+/// // Shift the whole display one position to the left, keeping DDRAM as it is.
+/// lcd.raw_command(Command::Shift { display: true, right: false })?;
+/// assert_eq!(Command::SetDdram(0x40).encode(), 0xc0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Clears DDRAM and returns the cursor home.
+    Clear,
+    /// Returns the cursor and any display shift home, leaving DDRAM as it is.
+    Home,
+    /// Sets how the address counter moves after each character.
+    EntryMode {
+        /// Move the cursor right (`true`) or left (`false`).
+        increment: bool,
+        /// Shift the display instead of the cursor.
+        shift: bool,
+    },
+    /// Turns the display, the underline cursor and the blinking block on or off.
+    DisplayControl {
+        /// Show DDRAM.
+        display: bool,
+        /// Show the underline cursor.
+        cursor: bool,
+        /// Blink the character at the cursor.
+        blink: bool,
+    },
+    /// Moves the cursor or shifts the display by one position without writing.
+    Shift {
+        /// Shift the display (`true`) or move the cursor (`false`).
+        display: bool,
+        /// Move to the right (`true`) or left (`false`).
+        right: bool,
+    },
+    /// Sets the number of display lines and the font, always in 4-bit mode.
+    FunctionSet {
+        /// Use two lines (also used by four-row displays) instead of one.
+        two_line: bool,
+        /// Use the 5x10 font, only available with one line.
+        tall_font: bool,
+    },
+    /// Sets the CGRAM address, 0 to 63, for writing custom character rows.
+    SetCgram(u8),
+    /// Sets the DDRAM address, 0 to 127, moving the cursor there.
+    SetDdram(u8),
+}
+
+impl Command {
+    /// Returns the instruction byte sent to the controller.
+    pub const fn encode(self) -> u8 {
+        const fn bit(on: bool, mask: u8) -> u8 {
+            if on {
+                mask
+            } else {
+                0
+            }
+        }
+        match self {
+            Command::Clear => LCD_CLEARDISPLAY,
+            Command::Home => LCD_RETURNHOME,
+            Command::EntryMode { increment, shift } => {
+                LCD_ENTRYMODESET
+                    | bit(increment, LCD_ENTRYLEFT)
+                    | bit(shift, LCD_ENTRYSHIFTINCREMENT)
+            }
+            Command::DisplayControl {
+                display,
+                cursor,
+                blink,
+            } => {
+                LCD_DISPLAYCONTROL
+                    | bit(display, LCD_DISPLAYON)
+                    | bit(cursor, LCD_CURSORON)
+                    | bit(blink, LCD_BLINKON)
+            }
+            Command::Shift { display, right } => {
+                LCD_CURSORSHIFT | bit(display, LCD_DISPLAYMOVE) | bit(right, LCD_MOVERIGHT)
+            }
+            Command::FunctionSet {
+                two_line,
+                tall_font,
+            } => {
+                LCD_FUNCTIONSET
+                    | LCD_4BITMODE
+                    | bit(two_line, LCD_2LINE)
+                    | bit(tall_font, LCD_5X10DOTS)
+            }
+            Command::SetCgram(address) => LCD_SETCGRAMADDR | (address & 0x3f),
+            Command::SetDdram(address) => LCD_SETDDRAMADDR | (address & 0x7f),
+        }
+    }
+
+    /// Returns `true` for the instructions that take the long clear/home execution time.
+    pub const fn is_slow(self) -> bool {
+        matches!(self, Command::Clear | Command::Home)
+    }
+}
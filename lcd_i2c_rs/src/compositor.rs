@@ -0,0 +1,577 @@
+use crate::charset::CharMap;
+use crate::i18n::Catalog;
+#[cfg(feature = "input")]
+use crate::input::{InputEvent, InputSource};
+use crate::units::Units;
+use crate::{AnimClock, Col, Frame, Lcd, Moment, Rect, RefreshScheduler, Region, Row};
+use std::time::Duration;
+
+/// How the software cursor of a [`Compositor`] is drawn.
+///
+/// The cursor swaps the character under it for a ROM glyph in the shown half of the blink
+/// cycle, see [`AnimClock::blink_on`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CursorStyle {
+    /// An underscore.
+    #[default]
+    Underline,
+    /// A solid block, like the controller's blinking cursor.
+    Block,
+}
+
+impl CursorStyle {
+    /// Returns the character code drawn in place of the cell under the cursor.
+    const fn code(self) -> u8 {
+        match self {
+            CursorStyle::Underline => b'_',
+            CursorStyle::Block => 0xff,
+        }
+    }
+}
+
+/// Where a widget asked for the cursor, in frame coordinates.
+type Cursor = (u8, u8, CursorStyle);
+
+/// A view of one rectangle of a [`Frame`], handed to [`Widget::render`].
+///
+/// Positions are relative to the rectangle and writes outside it are clipped.
+pub struct Canvas<'f> {
+    frame: &'f mut Frame,
+    rect: Rect,
+    charmap: CharMap<'f>,
+    cursor: Option<&'f mut Option<Cursor>>,
+}
+
+impl<'f> Canvas<'f> {
+    /// Creates a view of a rectangle of a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The frame to draw into.
+    /// * `rect` - The rectangle the view covers.
+    /// * `charmap` - How characters map to character codes, usually [`Lcd::charmap`].
+    pub fn new(frame: &'f mut Frame, rect: Rect, charmap: CharMap<'f>) -> Self {
+        Self {
+            frame,
+            rect,
+            charmap,
+            cursor: None,
+        }
+    }
+
+    /// Keeps the cursor a widget asks for in `cursor`.
+    fn with_cursor(mut self, cursor: &'f mut Option<Cursor>) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Returns the number of columns of the view.
+    pub fn width(&self) -> u8 {
+        self.rect.width
+    }
+
+    /// Returns the number of rows of the view.
+    pub fn height(&self) -> u8 {
+        self.rect.height
+    }
+
+    /// Returns a view of part of this view, e.g. to let a widget draw a nested one.
+    ///
+    /// # Arguments
+    ///
+    /// * `rect` - The part, relative to this view and clipped to it.
+    pub fn sub(&mut self, rect: Rect) -> Canvas<'_> {
+        let col = rect.col.min(self.rect.width);
+        let row = rect.row.min(self.rect.height);
+        let rect = Rect::new(
+            self.rect.col + col,
+            self.rect.row + row,
+            rect.width.min(self.rect.width - col),
+            rect.height.min(self.rect.height - row),
+        );
+        Canvas {
+            frame: self.frame,
+            rect,
+            charmap: self.charmap,
+            cursor: self.cursor.as_deref_mut(),
+        }
+    }
+
+    /// Fills the view with spaces.
+    pub fn clear(&mut self) {
+        for row in 0..self.rect.height {
+            for col in 0..self.rect.width {
+                self.set(col, row, b' ');
+            }
+        }
+    }
+
+    /// Shows the software cursor of the [`Compositor`] on a cell, until the widget is drawn
+    /// again without it.
+    ///
+    /// The display's own cursor shows wherever the last write of a refresh happened to land,
+    /// which with buffered, out-of-order updates is rarely the cell being edited. The
+    /// compositor draws this one into the frame instead, blinking with every other blinking
+    /// element, so keep the hardware cursor off. With several widgets asking, the one with the
+    /// input focus wins, or else the first added. Canvases not made by a compositor ignore it.
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - The column position within the view (0-indexed).
+    /// * `row` - The row position within the view (0-indexed).
+    /// * `style` - How the cursor is drawn.
+    pub fn set_cursor(&mut self, col: impl Into<Col>, row: impl Into<Row>, style: CursorStyle) {
+        let (col, row) = (col.into().0, row.into().0);
+        if let Some(cursor) = &mut self.cursor {
+            if col < self.rect.width && row < self.rect.height {
+                **cursor = Some((self.rect.col + col, self.rect.row + row, style));
+            }
+        }
+    }
+
+    /// Sets the character code of a cell. Cells outside the view are ignored.
+    pub fn set(&mut self, col: impl Into<Col>, row: impl Into<Row>, code: u8) {
+        let (col, row) = (col.into().0, row.into().0);
+        if col < self.rect.width && row < self.rect.height {
+            self.frame
+                .set(self.rect.col + col, self.rect.row + row, code);
+        }
+    }
+
+    /// Writes raw character codes from a position, clipped at the edge of the view.
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - The column position within the view (0-indexed).
+    /// * `row` - The row position within the view (0-indexed).
+    /// * `codes` - The character codes to write.
+    pub fn write_bytes(&mut self, col: impl Into<Col>, row: impl Into<Row>, codes: &[u8]) {
+        let (col, row) = (col.into().0, row.into().0);
+        for (i, &code) in codes.iter().enumerate() {
+            let Some(col) = col.checked_add(i as u8) else {
+                break;
+            };
+            self.set(col, row, code);
+        }
+    }
+
+    /// Prints text from a position, clipped at the edge of the view.
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - The column position within the view (0-indexed).
+    /// * `row` - The row position within the view (0-indexed).
+    /// * `text` - The text to print.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the text is successfully printed.
+    /// * `Err(anyhow::Error)` - If a character cannot be shown and the fallback policy is [`Fallback::Error`](crate::charset::Fallback::Error).
+    pub fn print(
+        &mut self,
+        col: impl Into<Col>,
+        row: impl Into<Row>,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let (col, row) = (col.into().0, row.into().0);
+        let mut col = col;
+        for ch in text.chars() {
+            if col >= self.rect.width {
+                break;
+            }
+            if let Some(code) = self.charmap.encode(ch)? {
+                self.set(col, row, code);
+                col += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Something that draws itself into a rectangle of the display, managed by a [`Compositor`].
+pub trait Widget {
+    /// Prepares the display once, when the widget is added, e.g. by loading custom characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the display is successfully prepared.
+    /// * `Err(anyhow::Error)` - If there is an error while sending data.
+    fn setup(&mut self, _lcd: &mut Lcd) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Draws the widget. The canvas has been cleared beforehand.
+    ///
+    /// # Arguments
+    ///
+    /// * `canvas` - The widget's rectangle of the frame.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the widget is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the widget cannot be drawn.
+    fn render(&mut self, canvas: &mut Canvas) -> anyhow::Result<()>;
+
+    /// Returns `true` if the widget should be redrawn on the next tick before its interval is
+    /// up, e.g. because an [`Observable`](crate::Observable) it is bound to changed. Widgets
+    /// drawn only at their interval keep the default.
+    fn needs_redraw(&self) -> bool {
+        false
+    }
+
+    /// Returns `true` while part of the widget blinks with [`AnimClock::blink_on`]; it is then
+    /// also redrawn whenever the blink phase flips, so it blinks in unison with every other
+    /// blinking element whatever its interval.
+    fn blinks(&self) -> bool {
+        false
+    }
+
+    /// Reacts to an input event. Widgets that take no input keep the default, which ignores
+    /// every event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the widget used the event; it is then redrawn on the next tick.
+    #[cfg(feature = "input")]
+    fn handle(&mut self, _event: InputEvent) -> bool {
+        false
+    }
+}
+
+struct Slot<'w> {
+    widget: &'w mut dyn Widget,
+    region: Region,
+    interval: Duration,
+    last: Option<Moment>,
+    cursor: Option<Cursor>,
+}
+
+/// Lays out up to `N` widgets on one display and redraws each at its own interval.
+///
+/// Each widget owns a rectangle, checked out from the scheduler as a [`Region`]; overlapping
+/// rectangles are rejected when the widget is added.
+/// [`tick`](Self::tick) renders the widgets that are due into the frame of a
+/// [`RefreshScheduler`], which then sends only the cells that changed.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut compositor: Compositor<3> = Compositor::new(RefreshScheduler::new(lcd));
+/// compositor.add(Rect::new(0, 0, 11, 1), &mut marquee, Duration::from_millis(300))?;
+/// compositor.add(Rect::new(11, 0, 5, 1), &mut clock, Duration::from_secs(1))?;
+/// compositor.add(Rect::new(0, 1, 16, 1), &mut spark, Duration::from_secs(5))?;
+/// loop {
+///     compositor.tick()?;
+/// }
+/// ```
+pub struct Compositor<'a, 'w, const N: usize> {
+    scheduler: RefreshScheduler<'a>,
+    slots: [Option<Slot<'w>>; N],
+    catalog: Option<(&'w Catalog<'w>, u32)>,
+    units: Option<(&'w Units, u32)>,
+    /// The blink phase of the last tick.
+    blink: bool,
+    /// The software cursor as last presented, if it was showing.
+    cursor: Option<Cursor>,
+    /// How often the frame moves by a cell against burn-in, if it does.
+    burn_in: Option<Duration>,
+    /// Whether the frame is shown one cell off, and since when it has been in this position.
+    shifted: (bool, Moment),
+    #[cfg(feature = "input")]
+    focus: Option<usize>,
+}
+
+/// Moves every row one cell towards the edge whose column is blank, to the right if both or
+/// neither are.
+fn shift(frame: &mut Frame) {
+    let (cols, rows) = (frame.cols(), frame.rows());
+    if cols == 0 {
+        return;
+    }
+    let blank = |frame: &Frame, col: u8| (0..rows).all(|row| frame.get(col, row) == Some(b' '));
+    let right = blank(frame, cols - 1) || !blank(frame, 0);
+    for row in 0..rows {
+        if right {
+            for col in (1..cols).rev() {
+                frame.set(col, row, frame.get(col - 1, row).unwrap_or(b' '));
+            }
+            frame.set(0, row, b' ');
+        } else {
+            for col in 0..cols - 1 {
+                frame.set(col, row, frame.get(col + 1, row).unwrap_or(b' '));
+            }
+            frame.set(cols - 1, row, b' ');
+        }
+    }
+}
+
+impl<'a, 'w, const N: usize> Compositor<'a, 'w, N> {
+    /// Creates a compositor without widgets.
+    ///
+    /// # Arguments
+    ///
+    /// * `scheduler` - The scheduler refreshing the display.
+    pub fn new(scheduler: RefreshScheduler<'a>) -> Self {
+        Self {
+            scheduler,
+            slots: core::array::from_fn(|_| None),
+            catalog: None,
+            units: None,
+            blink: AnimClock::blink_on(),
+            cursor: None,
+            burn_in: None,
+            shifted: (false, AnimClock::now()),
+            #[cfg(feature = "input")]
+            focus: None,
+        }
+    }
+
+    /// Moves everything shown one cell sideways every `period`, and back after the next, to
+    /// spread the wear of static content on OLED modules such as the WS0010 that stay on day
+    /// and night.
+    ///
+    /// The frame moves towards the edge whose column is blank, to the right if both or
+    /// neither are, so a layout using every column loses its last one while moved. Widgets
+    /// keep drawing in their own rectangles, and a move alone does not count as activity for
+    /// a [`Screensaver`](crate::Screensaver).
+    pub fn with_burn_in_shift(mut self, period: Duration) -> Self {
+        self.burn_in = Some(period);
+        self
+    }
+
+    /// Sets how long blinking elements stay shown, and then hidden, see
+    /// [`AnimClock::set_blink_rate`]. The rate is shared by every blinking element of the
+    /// program, inside the compositor or not.
+    pub fn with_blink_rate(self, half: Duration) -> Self {
+        AnimClock::set_blink_rate(half);
+        self
+    }
+
+    /// Redraws every widget on the next tick after the language of `catalog` changes, so
+    /// widgets showing its labels follow [`Catalog::set_language`].
+    pub fn with_catalog(mut self, catalog: &'w Catalog<'w>) -> Self {
+        self.catalog = Some((catalog, catalog.generation()));
+        self
+    }
+
+    /// Redraws every widget on the next tick after a unit of `units` changes, so widgets such
+    /// as [`Readout`](crate::widgets::Readout) follow [`Units::set`].
+    pub fn with_units(mut self, units: &'w Units) -> Self {
+        self.units = Some((units, units.generation()));
+        self
+    }
+
+    /// Redraws every widget on the next tick, whether it is due or not.
+    pub fn invalidate(&mut self) {
+        for slot in self.slots.iter_mut().flatten() {
+            slot.last = None;
+        }
+    }
+
+    /// Adds a widget and runs its [`setup`](Widget::setup).
+    ///
+    /// # Arguments
+    ///
+    /// * `rect` - The rectangle the widget draws into.
+    /// * `widget` - The widget.
+    /// * `interval` - How often the widget is redrawn.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the widget is successfully added.
+    /// * `Err(anyhow::Error)` - If the rectangle is off the display or overlaps another widget or checked-out region, all `N` places are taken, or the setup fails.
+    pub fn add(
+        &mut self,
+        rect: Rect,
+        widget: &'w mut dyn Widget,
+        interval: Duration,
+    ) -> anyhow::Result<()> {
+        let free = self
+            .slots
+            .iter()
+            .position(Option::is_none)
+            .ok_or_else(|| anyhow::anyhow!("No room for another widget"))?;
+        let region = self.scheduler.checkout(rect)?;
+        if let Err(err) = widget.setup(self.scheduler.lcd_mut()) {
+            self.scheduler.release(region);
+            return Err(err);
+        }
+        self.slots[free] = Some(Slot {
+            widget,
+            region,
+            interval,
+            last: None,
+            cursor: None,
+        });
+        Ok(())
+    }
+
+    /// Redraws the widgets that are due and lets the scheduler refresh the display.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `true` if anything was sent to the display.
+    /// * `Err(anyhow::Error)` - If a widget fails to render or there is an error while sending data.
+    pub fn tick(&mut self) -> anyhow::Result<bool> {
+        let mut changed = false;
+        if let Some((catalog, seen)) = &mut self.catalog {
+            changed |= core::mem::replace(seen, catalog.generation()) != *seen;
+        }
+        if let Some((units, seen)) = &mut self.units {
+            changed |= core::mem::replace(seen, units.generation()) != *seen;
+        }
+        if changed {
+            self.invalidate();
+        }
+        let now = AnimClock::now();
+        let blink = AnimClock::blink_on();
+        let flipped = core::mem::replace(&mut self.blink, blink) != blink;
+        let mut drawn = false;
+        for slot in self.slots.iter_mut().flatten() {
+            if let Some(last) = slot.last {
+                let blinked = flipped && slot.widget.blinks();
+                if now.duration_since(last) < slot.interval
+                    && !slot.widget.needs_redraw()
+                    && !blinked
+                {
+                    continue;
+                }
+            }
+            slot.last = Some(now);
+            slot.cursor = None;
+            let mut canvas = self
+                .scheduler
+                .canvas(&slot.region)
+                .with_cursor(&mut slot.cursor);
+            canvas.clear();
+            slot.widget.render(&mut canvas)?;
+            drawn = true;
+        }
+        let cursor = self.cursor().filter(|_| blink);
+        let (shifted, since) = self.shifted;
+        let moved = self
+            .burn_in
+            .is_some_and(|period| now.duration_since(since) >= period);
+        if moved {
+            self.shifted = (!shifted, now);
+        }
+        if drawn || moved || cursor != self.cursor {
+            self.present(cursor, drawn);
+        }
+        self.scheduler.tick()
+    }
+
+    /// Returns the cursor of the focused widget, or else of the first widget showing one.
+    fn cursor(&self) -> Option<Cursor> {
+        #[cfg(feature = "input")]
+        if let Some(Some(slot)) = self.focus.and_then(|focus| self.slots.get(focus)) {
+            return slot.cursor;
+        }
+        self.slots.iter().flatten().find_map(|slot| slot.cursor)
+    }
+
+    /// Presents the frame with the cursor glyph swapped in and moved by the burn-in shift,
+    /// keeping the frame being drawn as the widgets left it. Unless a widget was redrawn,
+    /// the frame does not count as activity.
+    fn present(&mut self, cursor: Option<Cursor>, drawn: bool) {
+        let original = self.scheduler.frame().clone();
+        let frame = self.scheduler.frame_mut();
+        if let Some((col, row, style)) = cursor {
+            frame.set(col, row, style.code());
+        }
+        if self.shifted.0 {
+            shift(frame);
+        }
+        if drawn {
+            self.scheduler.present();
+        } else {
+            self.scheduler.present_quietly();
+        }
+        *self.scheduler.frame_mut() = original;
+        self.cursor = cursor;
+    }
+
+    /// Gives one widget the input focus, or with `None` offers events to every widget.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The widget, counted in the order they were added from 0.
+    #[cfg(feature = "input")]
+    pub fn set_focus(&mut self, index: Option<usize>) {
+        self.focus = index;
+    }
+
+    /// Returns the widget with the input focus, if any.
+    #[cfg(feature = "input")]
+    pub fn focus(&self) -> Option<usize> {
+        self.focus
+    }
+
+    /// Hands an input event to the widgets.
+    ///
+    /// The widget with the focus gets the event; without a focus it is offered to each widget
+    /// in the order they were added until one uses it. A widget that uses the event is redrawn
+    /// on the next [`tick`](Self::tick).
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a widget used the event.
+    #[cfg(feature = "input")]
+    pub fn dispatch(&mut self, event: InputEvent) -> bool {
+        let focus = self.focus;
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            let Some(slot) = slot else {
+                continue;
+            };
+            if focus.is_some_and(|focus| focus != index) {
+                continue;
+            }
+            if slot.widget.handle(event) {
+                slot.last = None;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Dispatches every pending event of an input adapter.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The adapter, e.g. a [`MatrixKeypad`](crate::input::MatrixKeypad).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `true` if a widget used any of the events.
+    /// * `Err(anyhow::Error)` - If the input cannot be read.
+    #[cfg(feature = "input")]
+    pub fn poll_input(&mut self, source: &mut dyn InputSource) -> anyhow::Result<bool> {
+        let mut used = false;
+        while let Some(event) = source.next_event()? {
+            used |= self.dispatch(event);
+        }
+        Ok(used)
+    }
+
+    /// Returns the scheduler, e.g. to read its dropped frame count.
+    pub fn scheduler(&self) -> &RefreshScheduler<'a> {
+        &self.scheduler
+    }
+
+    /// Returns the scheduler, for access to the display.
+    pub fn scheduler_mut(&mut self) -> &mut RefreshScheduler<'a> {
+        &mut self.scheduler
+    }
+}
@@ -21,8 +21,11 @@ pub const LCD_BLINKON: u8 = 0x01;
 pub const LCD_BLINKOFF: u8 = 0x00;
 pub const LCD_4BITMODE: u8 = 0x00;
 pub const LCD_5X8DOTS: u8 = 0x00;
+pub const LCD_5X10DOTS: u8 = 0x04;
 pub const LCD_ADDRESS: u8 = 0x27;
 pub const LCD_1LINE: u8 = 0x00;
 pub const LCD_2LINE: u8 = 0x08;
 pub const EN: u8 = 0x04;
-pub const RS: u8 = 0x01;
\ No newline at end of file
+pub const RS: u8 = 0x01;
+pub const RW: u8 = 0x02;/// DDRAM start address of each row, shared by all supported geometries.
+pub const ROW_OFFSETS: [u8; 4] = [0x00, 0x40, 0x14, 0x54];
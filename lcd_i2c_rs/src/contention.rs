@@ -0,0 +1,51 @@
+use core::fmt;
+
+/// The port expander's pins did not hold what was written to them, several writes in a row.
+///
+/// Returned by every write once the port check is on (see
+/// [`Lcd::set_port_check`](crate::Lcd::set_port_check)) and the pins read back wrong too often
+/// to be noise. The usual causes are another device holding SDA low, a second device answering
+/// on the same address, or a pin shorted to ground, such as the backlight pin behind a failed
+/// transistor. Text sent while this goes on would arrive garbled, so it is better to stop and
+/// report. Retrieve it with [`anyhow::Error::downcast_ref`].
+///
+/// # Example
+///
+/// ```ignore
+/// if let Err(e) = lcd.print_str("Ready") {
+///     if let Some(contention) = e.downcast_ref::<BusContention>() {
+///         log::error!("{contention}, stuck pins {:08b}", contention.stuck_pins());
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusContention {
+    /// The byte last written to the port.
+    pub written: u8,
+    /// The byte the port read back.
+    pub read: u8,
+}
+
+impl BusContention {
+    /// Creates a report.
+    pub const fn new(written: u8, read: u8) -> Self {
+        Self { written, read }
+    }
+
+    /// Returns a mask of the pins that read back different from what was written.
+    pub const fn stuck_pins(&self) -> u8 {
+        self.written ^ self.read
+    }
+}
+
+impl fmt::Display for BusContention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Bus contention: expander port reads {:#04x} after writing {:#04x}",
+            self.read, self.written
+        )
+    }
+}
+
+impl std::error::Error for BusContention {}
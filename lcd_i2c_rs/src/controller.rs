@@ -0,0 +1,347 @@
+//! Controller profiles describing how HD44780-compatible chips differ in setup and features.
+
+/// One step of a controller's start-up sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitStep {
+    /// Waits the given number of milliseconds.
+    DelayMs(u16),
+    /// Waits the given number of microseconds.
+    DelayUs(u16),
+    /// Writes the backlight state to the expander with all other pins low.
+    Idle,
+    /// Clocks in a single 4-bit nibble, used while the bus width is not yet known.
+    Nibble(u8),
+    /// Sends the function set command for the geometry and font table.
+    FunctionSet,
+    /// Sends a raw command byte.
+    Command(u8),
+    /// Sends the bias, contrast, power and follower settings of the extended instruction set
+    /// and waits for the follower to settle.
+    Extended,
+}
+
+/// The character set used by controllers with selectable fonts, such as the WS0010.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FontTable {
+    /// ASCII with half-width katakana, like the HD44780 A00 ROM.
+    #[default]
+    EnglishJapanese,
+    /// ASCII with Western European accented letters.
+    WesternEuropean1,
+    /// ASCII with Cyrillic letters, see [`CYRILLIC_WS0010`](crate::cyrillic::CYRILLIC_WS0010).
+    EnglishRussian,
+    /// ASCII with a second set of Western European letters and symbols.
+    WesternEuropean2,
+}
+
+impl FontTable {
+    /// Returns the FT1 and FT0 bits of the function set command.
+    pub(crate) fn bits(self) -> u8 {
+        match self {
+            FontTable::EnglishJapanese => 0b00,
+            FontTable::WesternEuropean1 => 0b01,
+            FontTable::EnglishRussian => 0b10,
+            FontTable::WesternEuropean2 => 0b11,
+        }
+    }
+}
+
+/// A fixed icon printed on the glass of some modules and driven by icon RAM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconSegment {
+    /// Antenna or signal strength.
+    Antenna,
+    /// Telephone handset.
+    Phone,
+    /// Speaker.
+    Sound,
+    /// Input or play marker.
+    Input,
+    /// Up arrow.
+    Up,
+    /// Down arrow.
+    Down,
+    /// Padlock.
+    Lock,
+    /// Crossed-out speaker.
+    Mute,
+    /// Battery outline.
+    BatteryFrame,
+    /// First (lowest) battery level bar.
+    Battery1,
+    /// Second battery level bar.
+    Battery2,
+    /// Third (highest) battery level bar.
+    Battery3,
+    /// Alarm or other module-specific symbol.
+    Alarm,
+}
+
+/// The icon RAM location of one [`IconSegment`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IconBit {
+    /// The icon.
+    pub segment: IconSegment,
+    /// The icon RAM address, from 0 to 15.
+    pub address: u8,
+    /// The bit within the address, from 0 to 4.
+    pub bit: u8,
+}
+
+/// The icon layout common to ST7032 modules with an icon row, such as the AQM1602.
+const ST7032_ICONS: &[IconBit] = &[
+    IconBit {
+        segment: IconSegment::Antenna,
+        address: 0x00,
+        bit: 4,
+    },
+    IconBit {
+        segment: IconSegment::Phone,
+        address: 0x02,
+        bit: 4,
+    },
+    IconBit {
+        segment: IconSegment::Sound,
+        address: 0x04,
+        bit: 4,
+    },
+    IconBit {
+        segment: IconSegment::Input,
+        address: 0x06,
+        bit: 4,
+    },
+    IconBit {
+        segment: IconSegment::Up,
+        address: 0x07,
+        bit: 4,
+    },
+    IconBit {
+        segment: IconSegment::Down,
+        address: 0x07,
+        bit: 3,
+    },
+    IconBit {
+        segment: IconSegment::Lock,
+        address: 0x09,
+        bit: 4,
+    },
+    IconBit {
+        segment: IconSegment::Mute,
+        address: 0x0b,
+        bit: 4,
+    },
+    IconBit {
+        segment: IconSegment::Battery1,
+        address: 0x0d,
+        bit: 4,
+    },
+    IconBit {
+        segment: IconSegment::Battery2,
+        address: 0x0d,
+        bit: 3,
+    },
+    IconBit {
+        segment: IconSegment::Battery3,
+        address: 0x0d,
+        bit: 2,
+    },
+    IconBit {
+        segment: IconSegment::BatteryFrame,
+        address: 0x0d,
+        bit: 1,
+    },
+    IconBit {
+        segment: IconSegment::Alarm,
+        address: 0x0f,
+        bit: 4,
+    },
+];
+
+/// Describes a controller chip: its start-up sequence, timing and capabilities.
+///
+/// Pass one to [`Lcd::with_profile`](crate::Lcd::with_profile). The built-in profiles cover the
+/// common chips; a custom one can be built for clones with other quirks.
+///
+/// After the `init` steps, [`Lcd::init`](crate::Lcd::init) turns the display on, clears it,
+/// sets left-to-right entry mode and returns home, which every profile has in common.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControllerProfile {
+    /// The name of the chip, for logs.
+    pub name: &'static str,
+    /// The controller-specific part of the start-up sequence, ending in 4-bit mode.
+    pub init: &'static [InitStep],
+    /// How long clear and return home take, in microseconds.
+    pub clear_us: u16,
+    /// How long any other command or data write takes, in microseconds.
+    pub command_us: u16,
+    /// Whether the busy flag and address counter can be read back.
+    pub busy_readable: bool,
+    /// Whether the function set command selects one of several [`FontTable`]s.
+    pub font_tables: bool,
+    /// Whether the chip has an extended instruction set.
+    pub extended_instructions: bool,
+    /// Whether the module has a backlight driven by the expander.
+    pub backlight: bool,
+    /// Where the module's fixed icons sit in icon RAM, empty if it has none.
+    pub icons: &'static [IconBit],
+}
+
+/// The classic reset-by-instruction sequence from the HD44780 datasheet.
+const HD44780_INIT: &[InitStep] = &[
+    InitStep::DelayMs(50),
+    InitStep::Idle,
+    InitStep::DelayMs(1000),
+    InitStep::Nibble(0x03),
+    InitStep::DelayUs(4500),
+    InitStep::Nibble(0x03),
+    InitStep::DelayUs(4500),
+    InitStep::Nibble(0x03),
+    InitStep::DelayUs(4500),
+    InitStep::Nibble(0x02),
+    InitStep::FunctionSet,
+];
+
+/// The KS0066 expects the 4-bit switch directly, followed by function set.
+const KS0066_INIT: &[InitStep] = &[
+    InitStep::DelayMs(50),
+    InitStep::Idle,
+    InitStep::DelayMs(50),
+    InitStep::Nibble(0x02),
+    InitStep::FunctionSet,
+    InitStep::DelayUs(100),
+];
+
+/// The WS0010 also needs character mode selected and its internal power supply turned on.
+const WS0010_INIT: &[InitStep] = &[
+    InitStep::DelayMs(50),
+    InitStep::Idle,
+    InitStep::Nibble(0x03),
+    InitStep::DelayMs(5),
+    InitStep::Nibble(0x02),
+    InitStep::DelayMs(5),
+    InitStep::FunctionSet,
+    InitStep::DelayMs(5),
+    InitStep::Command(0x08),
+    InitStep::Command(0x17),
+];
+
+/// The ST7036 and ST7032 datasheet sequence, with the analog setup from the extended
+/// instruction set.
+const ST7036_INIT: &[InitStep] = &[
+    InitStep::DelayMs(50),
+    InitStep::Idle,
+    InitStep::Nibble(0x03),
+    InitStep::DelayMs(5),
+    InitStep::Nibble(0x03),
+    InitStep::DelayUs(200),
+    InitStep::Nibble(0x03),
+    InitStep::DelayUs(200),
+    InitStep::Nibble(0x02),
+    InitStep::FunctionSet,
+    InitStep::Extended,
+];
+
+impl ControllerProfile {
+    /// Hitachi HD44780 and the many modules that follow its datasheet.
+    pub const HD44780: ControllerProfile = ControllerProfile {
+        name: "HD44780",
+        init: HD44780_INIT,
+        clear_us: 2000,
+        command_us: 50,
+        busy_readable: true,
+        font_tables: false,
+        extended_instructions: false,
+        backlight: true,
+        icons: &[],
+    };
+
+    /// Samsung KS0066, common on low-cost modules.
+    pub const KS0066: ControllerProfile = ControllerProfile {
+        name: "KS0066",
+        init: KS0066_INIT,
+        clear_us: 2000,
+        command_us: 50,
+        busy_readable: true,
+        font_tables: false,
+        extended_instructions: false,
+        backlight: true,
+        icons: &[],
+    };
+
+    /// Sitronix ST7066, an HD44780 clone with the same start-up sequence.
+    pub const ST7066: ControllerProfile = ControllerProfile {
+        name: "ST7066",
+        init: HD44780_INIT,
+        clear_us: 2000,
+        command_us: 50,
+        busy_readable: true,
+        font_tables: false,
+        extended_instructions: false,
+        backlight: true,
+        icons: &[],
+    };
+
+    /// Sunplus SPLC780, an HD44780 clone with a slightly slower clear.
+    pub const SPLC780: ControllerProfile = ControllerProfile {
+        name: "SPLC780",
+        init: HD44780_INIT,
+        clear_us: 2500,
+        command_us: 50,
+        busy_readable: true,
+        font_tables: false,
+        extended_instructions: false,
+        backlight: true,
+        icons: &[],
+    };
+
+    /// Sitronix ST7036, used on EA DOG-M modules, with software contrast, icon RAM and double
+    /// height through its extended instruction set.
+    pub const ST7036: ControllerProfile = ControllerProfile {
+        name: "ST7036",
+        init: ST7036_INIT,
+        clear_us: 2000,
+        command_us: 50,
+        busy_readable: true,
+        font_tables: false,
+        extended_instructions: true,
+        backlight: true,
+        icons: &[],
+    };
+
+    /// Sitronix ST7032, a smaller relative of the ST7036 with the same contrast and icon
+    /// commands. Double height is limited to merging the two lines of a two-line panel.
+    ///
+    /// The icon map is the common one of modules with an icon row, such as the AQM1602.
+    pub const ST7032: ControllerProfile = ControllerProfile {
+        name: "ST7032",
+        init: ST7036_INIT,
+        clear_us: 2000,
+        command_us: 50,
+        busy_readable: true,
+        font_tables: false,
+        extended_instructions: true,
+        backlight: true,
+        icons: ST7032_ICONS,
+    };
+
+    /// Winstar WS0010, used on WEH OLED character modules.
+    ///
+    /// These have no backlight and offer four font tables, see [`FontTable`].
+    pub const WS0010: ControllerProfile = ControllerProfile {
+        name: "WS0010",
+        init: WS0010_INIT,
+        clear_us: 6200,
+        command_us: 50,
+        busy_readable: true,
+        font_tables: true,
+        extended_instructions: false,
+        backlight: false,
+        icons: &[],
+    };
+}
+
+impl Default for ControllerProfile {
+    fn default() -> Self {
+        ControllerProfile::HD44780
+    }
+}
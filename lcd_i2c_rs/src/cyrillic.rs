@@ -0,0 +1,197 @@
+//! Russian text on modules with a Cyrillic-capable character ROM.
+//!
+//! The tables here plug into [`Lcd::set_substitutions`](crate::Lcd::set_substitutions), so the
+//! regular print methods handle Cyrillic once the table matching the module's ROM is selected.
+//!
+//! # Example
+//!
+//! ```ignore
+//! lcd.set_substitutions(CYRILLIC_A02);
+//! lcd.load_cyrillic_glyphs()?;
+//! lcd.print_str("Привет")?;
+//! ```
+
+#[cfg(feature = "driver")]
+use crate::Lcd;
+
+/// The first CGRAM slot used for the letters missing from the A02 ROM.
+pub const A02_GLYPH_SLOT: u8 = 5;
+
+/// Bitmaps of Ф, Ю and Я, the letters missing from the A02 ROM.
+pub const A02_GLYPHS: [[u8; 8]; 3] = [
+    [
+        0b00100, 0b01110, 0b10101, 0b10101, 0b10101, 0b01110, 0b00100, 0b00000,
+    ],
+    [
+        0b10010, 0b10101, 0b10101, 0b11101, 0b10101, 0b10101, 0b10010, 0b00000,
+    ],
+    [
+        0b01111, 0b10001, 0b10001, 0b01111, 0b00101, 0b01001, 0b10001, 0b00000,
+    ],
+];
+
+/// Cyrillic letters on the English/Russian font table of WS0010 OLED modules and on
+/// HD44780 clones with the same Cyrillic ROM.
+///
+/// Every Russian letter has a code, using Latin letters where they look the same.
+pub const CYRILLIC_WS0010: &[(char, u8)] = &[
+    ('А', b'A'),
+    ('Б', 0xa0),
+    ('В', b'B'),
+    ('Г', 0xa1),
+    ('Д', 0xe0),
+    ('Е', b'E'),
+    ('Ж', 0xa3),
+    ('З', 0xa4),
+    ('И', 0xa5),
+    ('Й', 0xa6),
+    ('К', b'K'),
+    ('Л', 0xa7),
+    ('М', b'M'),
+    ('Н', b'H'),
+    ('О', b'O'),
+    ('П', 0xa8),
+    ('Р', b'P'),
+    ('С', b'C'),
+    ('Т', b'T'),
+    ('У', 0xa9),
+    ('Ф', 0xaa),
+    ('Х', b'X'),
+    ('Ц', 0xe1),
+    ('Ч', 0xab),
+    ('Ш', 0xac),
+    ('Щ', 0xe2),
+    ('Ъ', 0xad),
+    ('Ы', 0xae),
+    ('Ь', b'b'),
+    ('Э', 0xaf),
+    ('Ю', 0xb0),
+    ('Я', 0xb1),
+    ('Ё', 0xa2),
+    ('а', b'a'),
+    ('б', 0xb2),
+    ('в', 0xb3),
+    ('г', 0xb4),
+    ('д', 0xe3),
+    ('е', b'e'),
+    ('ж', 0xb6),
+    ('з', 0xb7),
+    ('и', 0xb8),
+    ('й', 0xb9),
+    ('к', 0xba),
+    ('л', 0xbb),
+    ('м', 0xbc),
+    ('н', 0xbd),
+    ('о', b'o'),
+    ('п', 0xbe),
+    ('р', b'p'),
+    ('с', b'c'),
+    ('т', 0xbf),
+    ('у', b'y'),
+    ('ф', 0xe4),
+    ('х', b'x'),
+    ('ц', 0xe5),
+    ('ч', 0xc0),
+    ('ш', 0xc1),
+    ('щ', 0xe6),
+    ('ъ', 0xc2),
+    ('ы', 0xc3),
+    ('ь', 0xc4),
+    ('э', 0xc5),
+    ('ю', 0xc6),
+    ('я', 0xc7),
+    ('ё', 0xb5),
+];
+
+/// Cyrillic letters on HD44780 modules with the A02 (European) ROM.
+///
+/// The A02 ROM only has the capitals that differ from Latin and Greek letters, so lowercase
+/// letters are shown as small capitals. Ф, Ю and Я are missing and map to custom characters
+/// [`A02_GLYPH_SLOT`] to `A02_GLYPH_SLOT + 2`; load them with
+/// [`Lcd::load_cyrillic_glyphs`](crate::Lcd::load_cyrillic_glyphs).
+pub const CYRILLIC_A02: &[(char, u8)] = &[
+    ('А', b'A'),
+    ('Б', 0x80),
+    ('В', b'B'),
+    ('Г', 0x92),
+    ('Д', 0x81),
+    ('Е', b'E'),
+    ('Ж', 0x82),
+    ('З', 0x83),
+    ('И', 0x84),
+    ('Й', 0x85),
+    ('К', b'K'),
+    ('Л', 0x86),
+    ('М', b'M'),
+    ('Н', b'H'),
+    ('О', b'O'),
+    ('П', 0x87),
+    ('Р', b'P'),
+    ('С', b'C'),
+    ('Т', b'T'),
+    ('У', 0x88),
+    ('Ф', A02_GLYPH_SLOT),
+    ('Х', b'X'),
+    ('Ц', 0x89),
+    ('Ч', 0x8a),
+    ('Ш', 0x8b),
+    ('Щ', 0x8c),
+    ('Ъ', 0x8d),
+    ('Ы', 0x8e),
+    ('Ь', b'b'),
+    ('Э', 0x8f),
+    ('Ю', A02_GLYPH_SLOT + 1),
+    ('Я', A02_GLYPH_SLOT + 2),
+    ('Ё', 0xcb),
+    ('а', b'a'),
+    ('б', 0x80),
+    ('в', b'B'),
+    ('г', 0x92),
+    ('д', 0x81),
+    ('е', b'e'),
+    ('ж', 0x82),
+    ('з', 0x83),
+    ('и', 0x84),
+    ('й', 0x85),
+    ('к', b'K'),
+    ('л', 0x86),
+    ('м', b'M'),
+    ('н', b'H'),
+    ('о', b'o'),
+    ('п', 0x87),
+    ('р', b'p'),
+    ('с', b'c'),
+    ('т', b'T'),
+    ('у', b'y'),
+    ('ф', A02_GLYPH_SLOT),
+    ('х', b'x'),
+    ('ц', 0x89),
+    ('ч', 0x8a),
+    ('ш', 0x8b),
+    ('щ', 0x8c),
+    ('ъ', 0x8d),
+    ('ы', 0x8e),
+    ('ь', b'b'),
+    ('э', 0x8f),
+    ('ю', A02_GLYPH_SLOT + 1),
+    ('я', A02_GLYPH_SLOT + 2),
+    ('ё', 0xeb),
+];
+
+#[cfg(feature = "driver")]
+impl Lcd<'_> {
+    /// Loads the custom characters used by [`CYRILLIC_A02`] for Ф, Ю and Я.
+    ///
+    /// This overwrites CGRAM slots [`A02_GLYPH_SLOT`] to `A02_GLYPH_SLOT + 2`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the characters are successfully created.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the data.
+    pub fn load_cyrillic_glyphs(&mut self) -> anyhow::Result<()> {
+        for (offset, glyph) in A02_GLYPHS.iter().enumerate() {
+            self.create_custom_chars(A02_GLYPH_SLOT + offset as u8, glyph)?;
+        }
+        Ok(())
+    }
+}
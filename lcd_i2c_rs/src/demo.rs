@@ -0,0 +1,53 @@
+use crate::fmt::fmt_buf;
+use crate::Lcd;
+
+/// First character code shown by the demo; 0x00-0x0f address CGRAM.
+const FIRST_CODE: u16 = 0x10;
+
+impl Lcd<'_> {
+    /// Pages through the controller's ROM character set, labeling each row with its code.
+    ///
+    /// Each row shows the hexadecimal code of its first character followed by the characters
+    /// themselves, 8 per row on 16-column panels and 16 per row on 20-column panels. Codes
+    /// 0x10 to 0xff are shown, so the ROM variant is easy to identify: A00 (Japanese) modules
+    /// show katakana from 0xa0 on, A02 (European) modules show accented Latin and Cyrillic
+    /// letters there and symbols at 0x10-0x1f.
+    ///
+    /// # Arguments
+    ///
+    /// * `page_delay_ms` - How long each page stays on screen, in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every page was shown.
+    /// * `Err(anyhow::Error)` - If the display is too narrow, there is an error while sending data, or the [`CancelToken`](crate::CancelToken) is cancelled.
+    pub fn demo_charset(&mut self, page_delay_ms: u32) -> anyhow::Result<()> {
+        let per_row: u16 = match self.cols() {
+            c if c >= 19 => 16,
+            c if c >= 11 => 8,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Display too narrow for the character set demo"
+                ))
+            }
+        };
+
+        let mut code = FIRST_CODE;
+        while code <= 0xff {
+            self.clear()?;
+            for row in 0..self.rows() {
+                if code > 0xff {
+                    break;
+                }
+                self.set_cursor(0, row)?;
+                self.print_str(fmt_buf!(4, "{:02X} ", code).as_str())?;
+                for offset in 0..per_row {
+                    self.write_byte((code + offset) as u8)?;
+                }
+                code += per_row;
+            }
+            self.delay_ms(page_delay_ms)?;
+        }
+        Ok(())
+    }
+}
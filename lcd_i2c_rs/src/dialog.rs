@@ -0,0 +1,205 @@
+use crate::charset::DisplayWidth;
+use crate::input::InputEvent;
+use crate::wrap::{WrapMode, WrappedLines};
+use crate::{AnimClock, Frame, RefreshScheduler};
+
+/// A modal question with a few answers, such as Yes/No/Cancel, shown over the whole display.
+///
+/// The message is word-wrapped over all rows but the last, which holds the options centered
+/// side by side. The selected option is framed by `[` `]` and its text blinks, since the
+/// controller has no inverse video. [`InputEvent::Left`] and [`Right`](InputEvent::Right) or an
+/// [`Encoder`](InputEvent::Encoder) move between the options, a [`Char`](InputEvent::Char)
+/// picks the option starting with that letter, and [`Up`](InputEvent::Up) and
+/// [`Down`](InputEvent::Down) scroll a message longer than the display.
+/// [`Select`](InputEvent::Select) answers with the selected option and
+/// [`Back`](InputEvent::Back) with the cancel option, the last one unless set otherwise.
+///
+/// The dialog is drawn as an overlay of a [`RefreshScheduler`], like a
+/// [`Screensaver`](crate::Screensaver): the application keeps drawing and presenting as usual
+/// while it is open, and its latest frame is restored once the dialog is answered.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut dialog = Dialog::yes_no_cancel("Save changes before leaving?");
+/// let answer = loop {
+///     if let Some(event) = keypad.next_event()? {
+///         dialog.handle(event);
+///     }
+///     dialog.tick(&mut scheduler)?;
+///     scheduler.tick()?;
+///     if let Some(answer) = dialog.answer() {
+///         break answer;
+///     }
+/// };
+/// ```
+pub struct Dialog<'d> {
+    message: &'d str,
+    options: &'d [&'d str],
+    selected: usize,
+    cancel: usize,
+    scroll: usize,
+    answer: Option<usize>,
+    open: bool,
+    drawn: Option<(usize, usize, bool)>,
+}
+
+impl<'d> Dialog<'d> {
+    /// Creates a dialog with the first option selected and the last one answering Back.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The question; `\n` starts a new line.
+    /// * `options` - The answers, shown side by side on the bottom row.
+    pub fn new(message: &'d str, options: &'d [&'d str]) -> Self {
+        Self {
+            message,
+            options,
+            selected: 0,
+            cancel: options.len().saturating_sub(1),
+            scroll: 0,
+            answer: None,
+            open: false,
+            drawn: None,
+        }
+    }
+
+    /// Creates a dialog answered with Yes (0) or No (1).
+    pub fn yes_no(message: &'d str) -> Self {
+        Self::new(message, &["Yes", "No"])
+    }
+
+    /// Creates a dialog answered with Yes (0), No (1) or Cancel (2).
+    pub fn yes_no_cancel(message: &'d str) -> Self {
+        Self::new(message, &["Yes", "No", "Cancel"])
+    }
+
+    /// Selects an option to start from, e.g. No for a destructive action.
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.selected = index.min(self.options.len().saturating_sub(1));
+        self
+    }
+
+    /// Sets the option [`InputEvent::Back`] answers with.
+    pub fn with_cancel(mut self, index: usize) -> Self {
+        self.cancel = index.min(self.options.len().saturating_sub(1));
+        self
+    }
+
+    /// Returns the index of the selected option.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Returns the index of the option answered, once the dialog is answered.
+    pub fn answer(&self) -> Option<usize> {
+        self.answer
+    }
+
+    /// Reacts to an input event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the event was used. Events are ignored once the dialog is answered.
+    pub fn handle(&mut self, event: InputEvent) -> bool {
+        if self.answer.is_some() || self.options.is_empty() {
+            return false;
+        }
+        let last = self.options.len() - 1;
+        match event {
+            InputEvent::Left => self.selected = self.selected.saturating_sub(1),
+            InputEvent::Right => self.selected = (self.selected + 1).min(last),
+            InputEvent::Encoder(detents) => {
+                let target = self.selected as isize + detents as isize;
+                self.selected = target.clamp(0, last as isize) as usize;
+            }
+            InputEvent::Up => self.scroll = self.scroll.saturating_sub(1),
+            // Clamped to the message when drawn, since the wrapping depends on the width.
+            InputEvent::Down => self.scroll += 1,
+            InputEvent::Char(letter) => {
+                let starts = |option: &&str| {
+                    option
+                        .chars()
+                        .next()
+                        .is_some_and(|first| first.to_lowercase().eq(letter.to_lowercase()))
+                };
+                match self.options.iter().position(starts) {
+                    Some(index) => self.selected = index,
+                    None => return false,
+                }
+            }
+            InputEvent::Select => self.answer = Some(self.selected),
+            InputEvent::Back => self.answer = Some(self.cancel),
+        }
+        true
+    }
+
+    /// Shows the dialog over the scheduler's frames, advances the blinking of the selected
+    /// option, and restores the application's latest frame once the dialog is answered.
+    ///
+    /// # Arguments
+    ///
+    /// * `scheduler` - The scheduler refreshing the display.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `true` if the dialog was drawn or dismissed.
+    /// * `Err(anyhow::Error)` - If a character cannot be shown and the fallback policy is [`Fallback::Error`](crate::charset::Fallback::Error).
+    pub fn tick(&mut self, scheduler: &mut RefreshScheduler) -> anyhow::Result<bool> {
+        if self.answer.is_some() {
+            if self.open {
+                self.open = false;
+                self.drawn = None;
+                scheduler.set_overlay(None);
+                return Ok(true);
+            }
+            return Ok(false);
+        }
+        let (cols, rows) = (scheduler.frame().cols(), scheduler.frame().rows());
+        let lit = AnimClock::blink_on();
+        let charmap = *scheduler.lcd().charmap();
+        let lines = || WrappedLines::new(self.message, cols, WrapMode::Word).with_charmap(charmap);
+        let message_rows = rows.saturating_sub(1) as usize;
+        self.scroll = self
+            .scroll
+            .min(lines().count().saturating_sub(message_rows));
+        if self.open && self.drawn == Some((self.selected, self.scroll, lit)) {
+            return Ok(false);
+        }
+
+        let mut frame = Frame::new(cols, rows);
+        for (row, line) in lines().skip(self.scroll).take(message_rows).enumerate() {
+            frame.print(0, row as u8, line.text, &charmap)?;
+        }
+        // Neighbouring options share the cell between them, which holds the brackets of
+        // whichever is selected.
+        let row = rows.saturating_sub(1);
+        let total = self.options.len()
+            + 1
+            + self
+                .options
+                .iter()
+                .map(|option| option.display_width(&charmap))
+                .sum::<usize>();
+        let mut col = (cols as usize).saturating_sub(total) / 2;
+        for (index, option) in self.options.iter().enumerate() {
+            let end = col + 1 + option.display_width(&charmap);
+            if index == self.selected {
+                frame.print(col as u8, row, "[", &charmap)?;
+                frame.print(end as u8, row, "]", &charmap)?;
+            }
+            if index != self.selected || lit {
+                frame.print(col as u8 + 1, row, option, &charmap)?;
+            }
+            col = end;
+        }
+        scheduler.set_overlay(Some(frame));
+        self.open = true;
+        self.drawn = Some((self.selected, self.scroll, lit));
+        Ok(true)
+    }
+}
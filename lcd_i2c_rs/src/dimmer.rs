@@ -0,0 +1,68 @@
+//! Hooks for dimming the backlight beyond the expander's on/off bit.
+
+/// Sets the backlight brightness, usually through a PWM output.
+///
+/// The PCF8574 backpack can only switch the backlight on or off. For dimming, remove the
+/// backlight jumper and drive the LED from a PWM pin (e.g. an `LedcDriver`), then hand the
+/// driver a `Dimmer` with [`Lcd::set_dimmer`](crate::Lcd::set_dimmer).
+///
+/// # Example
+///
+/// ```ignore
+/// struct Ledc<'d>(LedcDriver<'d>);
+///
+/// impl Dimmer for Ledc<'_> {
+///     fn set_level(&mut self, level: u8) -> anyhow::Result<()> {
+///         let max = self.0.get_max_duty();
+///         self.0.set_duty(max * level as u32 / 255)?;
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait Dimmer {
+    /// Sets the brightness, from 0 (off) to 255 (full).
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The brightness level.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the brightness is successfully set.
+    /// * `Err(anyhow::Error)` - If the output cannot be set.
+    fn set_level(&mut self, level: u8) -> anyhow::Result<()>;
+}
+
+/// Sets the levels of a backlight with several LED channels, such as a red/green or RGB
+/// module with each color on its own pin.
+///
+/// Drive it through a [`StatusLight`](crate::StatusLight).
+///
+/// # Example
+///
+/// ```ignore
+/// struct BiColor<'d>(LedcDriver<'d>, LedcDriver<'d>);
+///
+/// impl ColorDimmer for BiColor<'_> {
+///     fn set_levels(&mut self, levels: [u8; 3]) -> anyhow::Result<()> {
+///         let max = self.0.get_max_duty();
+///         self.0.set_duty(max * levels[0] as u32 / 255)?;
+///         self.1.set_duty(max * levels[1] as u32 / 255)?;
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait ColorDimmer {
+    /// Sets the level of each channel, from 0 (off) to 255 (full), in the order red, green,
+    /// blue. Modules with fewer channels ignore the rest.
+    ///
+    /// # Arguments
+    ///
+    /// * `levels` - The channel levels.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the levels are successfully set.
+    /// * `Err(anyhow::Error)` - If an output cannot be set.
+    fn set_levels(&mut self, levels: [u8; 3]) -> anyhow::Result<()>;
+}
@@ -0,0 +1,1697 @@
+use crate::bus::Bus;
+use crate::charset::{CharMap, Fallback};
+use crate::command::Command;
+use crate::consts::*;
+use crate::controller::{ControllerProfile, FontTable, InitStep};
+use crate::extended::{DoubleHeight, Extended, LCD_DOUBLEHEIGHT};
+use crate::fade::{Fade, FadeCurve};
+use crate::flash::Flash;
+#[cfg(feature = "buffer")]
+use crate::shadow::{Screenshot, Shadow};
+use crate::wrap::{WrapMode, WrappedLines};
+use crate::{BusContention, CancelToken, Col, Dimmer, Observer, ProgressReport, Row, Stats};
+use core::cell::RefCell;
+use esp_idf_hal::delay::Ets;
+use esp_idf_hal::i2c::*;
+use esp_idf_hal::sys::EspError;
+use std::time::Instant;
+
+/// Represents an LCD display connected via I2C.
+///
+/// The `Lcd` struct encapsulates the state and functionality for controlling an LCD display.
+/// It provides methods for initializing the display, controlling the backlight, setting the cursor,
+/// printing characters and strings, and more.
+///
+/// # Fields
+///
+/// * `i2c` - The I2C driver, owned or shared with other displays.
+/// * `i2c_address` - The bus address of the port expander.
+/// * `cols` - The number of columns in the LCD.
+/// * `rows` - The number of rows in the LCD.
+/// * `display_mode` - The display mode settings.
+/// * `display_control` - The display control settings.
+/// * `backlight` - The backlight state.
+/// * `current_line` - The current line position of the cursor.
+/// * `current_col` - The current column position of the cursor, `cols` once a row is full.
+/// * `observer` - An optional observer notified of the low-level traffic.
+/// * `stats` - Counters of the I2C traffic sent so far.
+/// * `max_retries` - How many times a failed I2C write is repeated.
+/// * `shadow` - A software copy of the display memory (`buffer` feature).
+/// * `headless` - Whether bus traffic is skipped because no display is connected.
+/// * `readback` - Whether the backpack wires the RW pin so the display can be read.
+/// * `verify` - Whether printed characters are read back and rewritten on mismatch.
+/// * `port_check` - Whether the expander port is read back after each write.
+/// * `port_mismatches` - How many writes in a row the port read back wrong.
+/// * `charmap` - How characters are mapped to the display's character codes.
+/// * `profile` - The init sequence, timing and capabilities of the controller chip.
+/// * `font_table` - The selected font table, on controllers that have several.
+/// * `extended` - Settings of the extended instruction set, on controllers that have one.
+/// * `address` - The DDRAM address counter, if known, used to skip redundant cursor moves.
+/// * `dimmer` - An optional output that dims the backlight.
+/// * `brightness` - The brightness last set with `set_brightness`.
+/// * `flash` - The backlight flash pattern being played, if any.
+/// * `fade` - The brightness fade being played, if any.
+/// * `fade_curve` - The curve brightness fades follow.
+/// * `cgram` - A copy of each custom character defined, for reloading after a re-init.
+/// * `cgram_synced` - A bit per CGRAM location whose copy is known to match the controller.
+/// * `sentinel` - Whether the audit sentinel is in place in DDRAM.
+/// * `cancel` - An optional token that stops long operations early.
+pub struct Lcd<'a> {
+    i2c: Bus<'a>,
+    i2c_address: u8,
+    cols: u8,
+    rows: u8,
+    display_mode: u8,
+    display_control: u8,
+    backlight: u8,
+    current_line: u8,
+    current_col: u8,
+    observer: Option<&'a dyn Observer>,
+    stats: Stats,
+    max_retries: u8,
+    #[cfg(feature = "buffer")]
+    pub(crate) shadow: Shadow,
+    headless: bool,
+    readback: bool,
+    verify: bool,
+    port_check: bool,
+    port_mismatches: u8,
+    charmap: CharMap<'a>,
+    profile: ControllerProfile,
+    font_table: FontTable,
+    pub(crate) extended: Extended,
+    address: Option<u8>,
+    dimmer: Option<&'a mut dyn Dimmer>,
+    brightness: u8,
+    pub(crate) flash: Option<Flash>,
+    pub(crate) fade: Option<Fade>,
+    pub(crate) fade_curve: FadeCurve,
+    cgram: [Option<[u8; 8]>; 8],
+    cgram_synced: u8,
+    sentinel: bool,
+    pub(crate) cancel: Option<&'a CancelToken>,
+}
+
+impl<'a> Lcd<'a> {
+    /// Creates a new `Lcd` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - A result containing an `I2cDriver` or an `EspError`.
+    /// * `cols` - The number of columns in the LCD.
+    /// * `rows` - The number of rows in the LCD.
+    ///
+    /// # Returns
+    ///
+    /// A new `Lcd` instance.
+    pub fn new(i2c: Result<I2cDriver<'a>, EspError>, cols: u8, rows: u8) -> Self {
+        Self::with_profile(i2c, cols, rows, ControllerProfile::HD44780)
+    }
+
+    /// Creates a new `Lcd` instance for a specific controller chip.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` - A result containing an `I2cDriver` or an `EspError`.
+    /// * `cols` - The number of columns in the LCD.
+    /// * `rows` - The number of rows in the LCD.
+    /// * `profile` - The controller chip, e.g. [`ControllerProfile::WS0010`] for OLED modules.
+    ///
+    /// # Returns
+    ///
+    /// A new `Lcd` instance.
+    pub fn with_profile(
+        i2c: Result<I2cDriver<'a>, EspError>,
+        cols: u8,
+        rows: u8,
+        profile: ControllerProfile,
+    ) -> Self {
+        Self::on_bus(Bus::Owned(i2c), cols, rows, profile)
+    }
+
+    fn on_bus(i2c: Bus<'a>, cols: u8, rows: u8, profile: ControllerProfile) -> Self {
+        Self {
+            i2c,
+            i2c_address: LCD_ADDRESS,
+            cols,
+            rows,
+            display_mode: LCD_ENTRYLEFT | LCD_ENTRYSHIFTDECREMENT,
+            display_control: LCD_DISPLAYON | LCD_CURSOROFF | LCD_BLINKOFF,
+            backlight: LCD_NOBACKLIGHT,
+            current_line: 0,
+            current_col: 0,
+            observer: None,
+            stats: Stats::default(),
+            max_retries: 0,
+            #[cfg(feature = "buffer")]
+            shadow: Shadow::new().with_extended(profile.extended_instructions),
+            headless: false,
+            readback: false,
+            verify: false,
+            port_check: false,
+            port_mismatches: 0,
+            charmap: CharMap::default(),
+            profile,
+            font_table: FontTable::EnglishJapanese,
+            extended: Extended::default(),
+            address: None,
+            dimmer: None,
+            brightness: u8::MAX,
+            flash: None,
+            fade: None,
+            fade_curve: FadeCurve::Cie1931,
+            cgram: [None; 8],
+            cgram_synced: 0,
+            sentinel: false,
+            cancel: None,
+        }
+    }
+
+    /// Creates a new `Lcd` instance on a bus shared with other displays.
+    ///
+    /// Each display on the bus needs its own address, set with the A0-A2 jumpers of its
+    /// backpack. See [`enumerate`](Self::enumerate) to find them all.
+    ///
+    /// # Arguments
+    ///
+    /// * `bus` - The I2C driver, borrowed by every display on the bus.
+    /// * `address` - The bus address of the display's port expander.
+    /// * `cols` - The number of columns in the LCD.
+    /// * `rows` - The number of rows in the LCD.
+    ///
+    /// # Returns
+    ///
+    /// A new `Lcd` instance.
+    pub fn shared(bus: &'a RefCell<I2cDriver<'_>>, address: u8, cols: u8, rows: u8) -> Self {
+        let mut lcd = Self::on_bus(Bus::Shared(bus), cols, rows, ControllerProfile::HD44780);
+        lcd.i2c_address = address;
+        lcd
+    }
+
+    /// Returns the profile of the controller chip the driver was created for.
+    pub fn profile(&self) -> &ControllerProfile {
+        &self.profile
+    }
+
+    /// Selects the character set on controllers with several font tables.
+    ///
+    /// Can be called before or after [`init`](Self::init).
+    ///
+    /// # Arguments
+    ///
+    /// * `font_table` - The font table to use.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the font table is successfully selected.
+    /// * `Err(anyhow::Error)` - If the controller has a single font, or there is an error while sending the command.
+    pub fn set_font_table(&mut self, font_table: FontTable) -> anyhow::Result<()> {
+        if !self.profile.font_tables {
+            return Err(anyhow::anyhow!("Controller has no selectable font tables"));
+        }
+        self.font_table = font_table;
+        self.send(self.function_set(), 0x0)?;
+        Ok(())
+    }
+
+    /// Checks whether a display answers on the bus.
+    ///
+    /// If the I2C driver could not be created or the port expander does not acknowledge its
+    /// address, the driver switches to headless mode: every method keeps working and keeps the
+    /// shadow buffer (`buffer` feature) up to date, but nothing is sent on the bus. An
+    /// [`Observer`] still sees the writes that would have been sent. This lets the same firmware run
+    /// with or without the display attached. Call it once at startup, before [`init`](Self::init).
+    ///
+    /// # Returns
+    ///
+    /// `true` if the display is present, `false` if the driver is now headless.
+    pub fn probe(&mut self) -> bool {
+        let present = self.i2c.is_ok() && self.i2c.read(self.i2c_address, &mut [0]).is_ok();
+        self.headless = !present;
+        present
+    }
+
+    /// Returns `true` if the driver is in headless mode, see [`probe`](Self::probe).
+    pub fn is_headless(&self) -> bool {
+        self.headless
+    }
+
+    /// Returns the characters currently shown, as tracked by the shadow buffer.
+    ///
+    /// Works in headless mode too, so the screen content can be logged instead.
+    #[cfg(feature = "buffer")]
+    pub fn screenshot(&self) -> Screenshot {
+        self.shadow.screenshot(self.cols, self.rows())
+    }
+
+    /// Sets an observer that is notified of every command, data byte and I2C write.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The observer to notify.
+    pub fn set_observer(&mut self, observer: &'a dyn Observer) {
+        self.observer = Some(observer);
+    }
+
+    /// Removes the observer, if any.
+    pub fn clear_observer(&mut self) {
+        self.observer = None;
+    }
+
+    /// Returns the I2C traffic counters accumulated since creation or the last
+    /// [`reset_stats`](Self::reset_stats).
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Counts a whole-frame flush of the [`RefreshScheduler`](crate::RefreshScheduler) in the
+    /// statistics.
+    #[cfg(feature = "buffer")]
+    pub(crate) fn record_flush(&mut self, elapsed: std::time::Duration) {
+        self.stats.record_flush(elapsed);
+    }
+
+    /// Resets all I2C traffic counters to zero.
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+    }
+
+    /// Returns the bus address of the display's port expander, 0x27 unless set otherwise.
+    pub fn i2c_address(&self) -> u8 {
+        self.i2c_address
+    }
+
+    /// Sets the bus address of the display's port expander, for backpacks with their A0-A2
+    /// jumpers set or a PCF8574A (0x38-0x3f) instead of a PCF8574 (0x20-0x27).
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The 7-bit bus address.
+    pub fn set_i2c_address(&mut self, address: u8) {
+        self.i2c_address = address;
+    }
+
+    /// Sets how many times a failed I2C write is repeated before its error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `retries` - The number of extra attempts, 0 by default.
+    pub fn set_max_retries(&mut self, retries: u8) {
+        self.max_retries = retries;
+    }
+
+    /// Changes the number of columns and rows the driver addresses.
+    ///
+    /// Use this after [`detect_geometry`](Self::detect_geometry) or when the panel size is only
+    /// known at runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `cols` - The number of columns in the LCD.
+    /// * `rows` - The number of rows in the LCD.
+    pub fn set_geometry(&mut self, cols: u8, rows: u8) {
+        self.cols = cols;
+        self.rows = rows;
+        self.current_line = self.current_line.min(rows.saturating_sub(1));
+        self.current_col = self.current_col.min(cols);
+    }
+
+    /// Returns the number of columns of the LCD.
+    pub fn cols(&self) -> u8 {
+        self.cols
+    }
+
+    /// Returns the number of rows of text the LCD shows.
+    ///
+    /// While two lines are merged by [`set_double_height`](Self::set_double_height) this is one
+    /// less than the panel has, so printing, wrapping and widgets lay text out on the rows that
+    /// are actually visible.
+    pub fn rows(&self) -> u8 {
+        if self.profile.extended_instructions && self.extended.double_height != DoubleHeight::Off {
+            self.rows - 1
+        } else {
+            self.rows
+        }
+    }
+
+    /// Returns the number of rows of the panel, whatever is merged.
+    pub(crate) fn panel_rows(&self) -> u8 {
+        self.rows
+    }
+
+    /// Initializes the LCD display.
+    ///
+    /// This function sets up the LCD display by configuring the display function,
+    /// writing initial commands, and setting the display mode and control settings.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the initialization is successful.
+    /// * `Err(anyhow::Error)` - If there is an error during initialization.
+    pub fn init(&mut self) -> anyhow::Result<()> {
+        // CGRAM holds random patterns after power-up, so nothing can be assumed to be there.
+        self.cgram_synced = 0;
+        // Mismatches seen before, e.g. while the bus was stuck, say nothing about the new start.
+        self.port_mismatches = 0;
+        for &step in self.profile.init {
+            match step {
+                InitStep::DelayMs(ms) => Ets::delay_ms(ms.into()),
+                InitStep::DelayUs(us) => Ets::delay_us(us.into()),
+                InitStep::Idle => self.expander_write(self.backlight)?,
+                InitStep::Nibble(nibble) => self.write4bits((nibble << 4) | self.backlight)?,
+                InitStep::FunctionSet => self.send(self.function_set(), 0x0)?,
+                InitStep::Command(cmd) => self.send(cmd, 0x0)?,
+                InitStep::Extended => self.apply_extended()?,
+            }
+        }
+
+        self.display_control = LCD_DISPLAYON | LCD_CURSOROFF | LCD_BLINKOFF;
+        self.display_on()?;
+        self.clear()?;
+
+        self.display_mode = LCD_ENTRYLEFT | LCD_ENTRYSHIFTDECREMENT;
+        self.send(LCD_ENTRYMODESET | self.display_mode, 0x0)?;
+
+        self.home()?;
+        Ok(())
+    }
+
+    /// Returns the function set command for the geometry, font table and double height mode.
+    pub(crate) fn function_set(&self) -> u8 {
+        let lines = if self.rows() > 1 {
+            LCD_2LINE
+        } else {
+            LCD_1LINE
+        };
+        let mut cmd = LCD_FUNCTIONSET | LCD_4BITMODE | lines | LCD_5X8DOTS;
+        if self.profile.font_tables {
+            cmd |= self.font_table.bits();
+        }
+        if self.profile.extended_instructions && self.extended.double_height != DoubleHeight::Off {
+            cmd |= LCD_DOUBLEHEIGHT;
+        }
+        cmd
+    }
+
+    /// Turns on the LCD display.
+    ///
+    /// This function sets the display control bit to turn on the display and sends the command to the LCD.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the display is successfully turned on.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn display_on(&mut self) -> anyhow::Result<()> {
+        self.display_control |= LCD_DISPLAYON;
+        let cmd = LCD_DISPLAYCONTROL | self.display_control;
+        self.send(cmd, 0x0)?;
+        Ok(())
+    }
+
+    /// Turns off the LCD display.
+    ///
+    /// This function clears the display control bit to turn off the display and sends the command to the LCD.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the display is successfully turned off.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn display_off(&mut self) -> anyhow::Result<()> {
+        self.display_control &= !LCD_DISPLAYON;
+        let cmd = LCD_DISPLAYCONTROL | self.display_control;
+        self.send(cmd, 0x0)?;
+        Ok(())
+    }
+
+    /// Returns `true` if the display is switched on, see [`display_on`](Self::display_on).
+    pub fn is_display_on(&self) -> bool {
+        self.display_control & LCD_DISPLAYON != 0
+    }
+
+    /// Turns on the LCD backlight.
+    ///
+    /// This function sets the backlight bit and writes the value to the expander. Modules
+    /// without a backlight, such as WS0010 OLEDs, ignore it.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if it fails to write to the expander.
+    pub fn backlight_on(&mut self) -> anyhow::Result<()> {
+        if !self.profile.backlight {
+            return Ok(());
+        }
+        self.backlight = LCD_BACKLIGHT;
+        self.expander_write(self.backlight)?;
+        Ok(())
+    }
+
+    /// Turns off the LCD backlight.
+    ///
+    /// This function clears the backlight bit and writes the value to the expander.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if it fails to write to the expander.
+    pub fn backlight_off(&mut self) -> anyhow::Result<()> {
+        self.backlight = LCD_NOBACKLIGHT;
+        self.expander_write(self.backlight)?;
+        Ok(())
+    }
+
+    /// Returns `true` if the backlight is switched on.
+    pub fn is_backlight_on(&self) -> bool {
+        self.backlight == LCD_BACKLIGHT
+    }
+
+    /// Sets an output that dims the backlight, see [`Dimmer`].
+    ///
+    /// # Arguments
+    ///
+    /// * `dimmer` - The output to drive.
+    pub fn set_dimmer(&mut self, dimmer: &'a mut dyn Dimmer) {
+        self.dimmer = Some(dimmer);
+    }
+
+    /// Sets the backlight brightness.
+    ///
+    /// With a [`Dimmer`] the level is passed on to it; without one the backlight can only be on
+    /// or off, so any level above 0 turns it on. Level 0 always turns the backlight off.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The brightness, from 0 (off) to 255 (full).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the brightness is successfully set.
+    /// * `Err(anyhow::Error)` - If the dimmer fails or there is an error while writing to the expander.
+    pub fn set_brightness(&mut self, level: u8) -> anyhow::Result<()> {
+        if let Some(dimmer) = self.dimmer.as_mut() {
+            dimmer.set_level(level)?;
+        }
+        if level == 0 {
+            self.backlight_off()?;
+        } else if self.brightness == 0 || self.backlight == LCD_NOBACKLIGHT {
+            self.backlight_on()?;
+        }
+        self.brightness = level;
+        Ok(())
+    }
+
+    /// Returns the brightness last set with [`set_brightness`](Self::set_brightness), 255 by
+    /// default.
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Clears the LCD display.
+    ///
+    /// This function sends the `LCD_CLEARDISPLAY` command to the LCD, waits for the command to complete,
+    /// and moves the cursor model to (0,0).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the display is successfully cleared.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn clear(&mut self) -> anyhow::Result<()> {
+        self.send(LCD_CLEARDISPLAY, 0x0)?;
+        self.sentinel = false;
+        Ets::delay_us(self.profile.clear_us.into());
+        self.current_line = 0;
+        self.current_col = 0;
+        Ok(())
+    }
+
+    /// Sets the cursor to a specific column and row on the LCD.
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - The column position (0-indexed).
+    /// * `row` - The row position (0-indexed).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the cursor is successfully set.
+    /// * `Err(anyhow::Error)` - If the row is out of bounds or the number of rows is invalid.
+    pub fn set_cursor(&mut self, col: impl Into<Col>, row: impl Into<Row>) -> anyhow::Result<()> {
+        let (col, row) = (col.into().0, row.into().0);
+        if row >= self.rows() {
+            return Err(anyhow::anyhow!("Row out of bounds"));
+        }
+
+        let row_offsets: &[u8] = match self.rows() {
+            1 => &[0x00],
+            2 => &[0x00, 0x40],
+            4 => &[0x00, 0x40, 0x14, 0x54],
+            _ => return Err(anyhow::anyhow!("Invalid number of rows")),
+        };
+
+        let address = col + row_offsets[row as usize];
+        // The address counter is often already there after printing the previous character.
+        if self.address != Some(address) {
+            self.send(LCD_SETDDRAMADDR | address, 0x0)?;
+        }
+        self.current_line = row;
+        self.current_col = col;
+        Ok(())
+    }
+
+    /// Controls the cursor visibility on the LCD.
+    ///
+    /// This function sets or clears the cursor control bit to turn the cursor on or off
+    /// and sends the command to the LCD.
+    ///
+    /// # Arguments
+    ///
+    /// * `on` - A boolean indicating whether to turn the cursor on (`true`) or off (`false`).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the cursor visibility is successfully changed.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn cursor(&mut self, on: bool) -> anyhow::Result<()> {
+        if on {
+            self.display_control |= LCD_CURSORON;
+        } else {
+            self.display_control &= !LCD_CURSORON;
+        }
+
+        let cmd = LCD_DISPLAYCONTROL | self.display_control;
+        self.send(cmd, 0x0)?;
+        Ok(())
+    }
+
+    /// Returns `true` if the underline cursor is shown, see [`cursor`](Self::cursor).
+    pub fn is_cursor_on(&self) -> bool {
+        self.display_control & LCD_CURSORON != 0
+    }
+
+    /// Controls the blinking of the cursor on the LCD.
+    ///
+    /// This function sets or clears the blink control bit to turn the cursor blinking on or off
+    /// and sends the command to the LCD.
+    ///
+    /// # Arguments
+    ///
+    /// * `on` - A boolean indicating whether to turn the cursor blinking on (`true`) or off (`false`).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the cursor blinking is successfully changed.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn blink(&mut self, on: bool) -> anyhow::Result<()> {
+        if on {
+            self.display_control |= LCD_BLINKON;
+        } else {
+            self.display_control &= !LCD_BLINKON;
+        }
+
+        let cmd = LCD_DISPLAYCONTROL | self.display_control;
+        self.send(cmd, 0x0)?;
+        Ok(())
+    }
+
+    /// Returns `true` if the cursor blinks, see [`blink`](Self::blink).
+    pub fn is_blink_on(&self) -> bool {
+        self.display_control & LCD_BLINKON != 0
+    }
+
+    /// Prints a single character to the LCD.
+    ///
+    /// The character is mapped to a character code by the substitution table and fallback
+    /// policy, see [`set_substitutions`](Self::set_substitutions). ASCII characters and the
+    /// custom character codes `'\0'` to `'\x07'` are printed as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `ch` - The character to print.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the character is successfully printed or skipped.
+    /// * `Err(anyhow::Error)` - If the character cannot be shown under [`Fallback::Error`] or
+    ///   there is an error while sending the character.
+    pub fn print(&mut self, ch: char) -> anyhow::Result<()> {
+        match self.charmap.encode(ch)? {
+            Some(code) => self.write_byte(code),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes a raw character code at the cursor, bypassing the substitution table.
+    ///
+    /// Use this for ROM characters outside ASCII, such as the degree sign or katakana.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The character code to write.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the code is successfully written.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the code.
+    pub fn write_byte(&mut self, code: u8) -> anyhow::Result<()> {
+        if self.verify {
+            self.write_verified(code)?;
+        } else {
+            self.send(code, RS)?;
+        }
+        self.track_column();
+        Ok(())
+    }
+
+    /// Writes a whole row of raw character codes, starting at the first column.
+    ///
+    /// The cursor is positioned once and the codes are streamed to the port expander in a
+    /// single I2C transaction, instead of one transaction per pin change. That makes this the
+    /// fastest way to redraw: a full 16x2 screen takes about 20 ms at 100 kHz, a quarter of
+    /// that at 400 kHz, where printing character by character takes several times longer. The
+    /// bus itself paces the controller, so the I2C clock must not exceed 400 kHz.
+    ///
+    /// With verification on (see [`set_verify`](Self::set_verify)) each code is written and
+    /// checked separately.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row to write (0-indexed).
+    /// * `codes` - The character codes, at most as many as the display has columns.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the row is successfully written.
+    /// * `Err(anyhow::Error)` - If the row is out of bounds, there are more codes than columns, or there is an error while sending data.
+    pub fn write_row(&mut self, row: impl Into<Row>, codes: &[u8]) -> anyhow::Result<()> {
+        let row = row.into().0;
+        if codes.len() > self.cols as usize {
+            return Err(anyhow::anyhow!("Row data wider than the display"));
+        }
+        self.write_cells(0, row, codes)
+    }
+
+    /// Writes consecutive cells of a row in as few I2C transactions as possible, see
+    /// [`write_row`](Self::write_row).
+    pub(crate) fn write_cells(&mut self, col: u8, row: u8, codes: &[u8]) -> anyhow::Result<()> {
+        /// Expander writes per character: each nibble is set up, then clocked with EN.
+        const WRITES_PER_BYTE: usize = 6;
+        const MAX_BATCH: usize = 40;
+
+        if codes.is_empty() {
+            return Ok(());
+        }
+        if self.verify {
+            self.set_cursor(col, row)?;
+            for &code in codes {
+                self.write_byte(code)?;
+            }
+            return Ok(());
+        }
+
+        // Right to left, the run is written backwards from its last cell.
+        let forward = self.display_mode & LCD_ENTRYLEFT != 0;
+        let first = if forward {
+            col
+        } else {
+            col.saturating_add(codes.len() as u8 - 1)
+        };
+        self.set_cursor(first, row)?;
+        let mut remaining = codes.len();
+        while remaining > 0 {
+            let batch = remaining.min(MAX_BATCH);
+            let mut bytes = [0u8; 1 + MAX_BATCH * WRITES_PER_BYTE];
+            let mut len = 1;
+            for i in 0..batch {
+                let sent = codes.len() - remaining + i;
+                let code = if forward {
+                    codes[sent]
+                } else {
+                    codes[codes.len() - 1 - sent]
+                };
+                if let Some(observer) = self.observer {
+                    observer.on_data(code);
+                }
+                #[cfg(feature = "buffer")]
+                self.shadow.data(code);
+                self.step_address();
+                self.track_column();
+                for nibble in [code & 0xf0, (code << 4) & 0xf0] {
+                    let pins = nibble | RS | self.backlight;
+                    bytes[len..len + 3].copy_from_slice(&[pins, pins | EN, pins]);
+                    len += 3;
+                }
+            }
+            self.i2c_write(&bytes[..len])?;
+            Ets::delay_us(self.profile.command_us.into());
+            remaining -= batch;
+        }
+        Ok(())
+    }
+
+    /// Sets the table of characters substituted before printing.
+    ///
+    /// Entries map a character to the code printed for it, e.g. `('é', b'e')`. The table is
+    /// consulted before anything else, so it can also remap ASCII characters or map characters
+    /// to custom character codes 0-7. [`TYPOGRAPHIC_SUBSTITUTIONS`](crate::charset::TYPOGRAPHIC_SUBSTITUTIONS)
+    /// covers common quotes and dashes.
+    ///
+    /// # Arguments
+    ///
+    /// * `substitutions` - Pairs of a character and its character code.
+    pub fn set_substitutions(&mut self, substitutions: &'a [(char, u8)]) {
+        self.charmap.set_substitutions(substitutions);
+    }
+
+    /// Sets what happens to characters that are not ASCII and not in the substitution table.
+    ///
+    /// The default is [`Fallback::Replace(b'?')`](Fallback::Replace).
+    ///
+    /// # Arguments
+    ///
+    /// * `fallback` - The policy for unmapped characters.
+    pub fn set_fallback(&mut self, fallback: Fallback) {
+        self.charmap.set_fallback(fallback);
+    }
+
+    /// Returns the character map used by the print methods.
+    pub fn charmap(&self) -> &CharMap<'a> {
+        &self.charmap
+    }
+
+    /// Prints a string to the LCD.
+    ///
+    /// # Arguments
+    ///
+    /// * `str` - The string to print.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the string is successfully printed.
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character, or the [`CancelToken`] is cancelled.
+    pub fn print_str(&mut self, str: &str) -> anyhow::Result<()> {
+        for ch in str.chars() {
+            self.check_cancel()?;
+            self.print(ch)?
+        }
+        Ok(())
+    }
+
+    /// Prints a long string to the LCD, wrapping text to the next line if necessary.
+    /// The text will be printed starting from the home position (0,0).
+    ///
+    /// # Arguments
+    ///
+    /// * `str` - The long string to print.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the string is successfully printed.
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character or setting the cursor, or the [`CancelToken`] is cancelled, with a [`ProgressReport`] of the characters printed.
+    pub fn print_long_str(&mut self, str: &str) -> anyhow::Result<()> {
+        self.set_cursor(0, 0)?;
+
+        let charmap = self.charmap;
+        let cells = str.chars().filter(|&ch| charmap.width(ch) > 0);
+        let total = cells.clone().count();
+        for (written, ch) in cells.enumerate() {
+            let wrapped = if self.current_col >= self.cols {
+                self.newline()
+            } else {
+                Ok(())
+            };
+            wrapped
+                .and_then(|_| self.check_cancel())
+                .and_then(|_| self.print(ch))
+                .map_err(|e| e.context(ProgressReport::new(written, total)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Prints text wrapped to the display width, starting from the home position (0,0).
+    ///
+    /// Unlike [`print_long_str`](Self::print_long_str), [`WrapMode::Word`] and
+    /// [`WrapMode::Hyphenate`] break lines at spaces and hyphens instead of in the middle of
+    /// words. Each row is padded with spaces to the display width, and text that does not fit
+    /// on the display is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `str` - The text to print.
+    /// * `mode` - How lines are broken.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the text is successfully printed.
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character or setting the cursor, or the [`CancelToken`] is cancelled.
+    pub fn print_wrapped(&mut self, str: &str, mode: WrapMode) -> anyhow::Result<()> {
+        let mut lines = WrappedLines::new(str, self.cols, mode).with_charmap(self.charmap);
+        for row in 0..self.rows() {
+            self.set_cursor(0, row)?;
+            let mut len = 0;
+            if let Some(line) = lines.next() {
+                for ch in line.chars() {
+                    self.check_cancel()?;
+                    self.print(ch)?;
+                }
+                len = line.len();
+            }
+            for _ in len..self.cols as usize {
+                self.print(' ')?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints text word-wrapped and centered on the display, both across and down.
+    ///
+    /// Each line is centered on its row, and the block of lines is centered between the top
+    /// and bottom rows, rounding up and to the left when it cannot be exact. Every other cell
+    /// is cleared, and text that does not fit on the display is dropped. Suits short messages
+    /// such as greetings and confirmations.
+    ///
+    /// # Arguments
+    ///
+    /// * `str` - The text to print.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the text is successfully printed.
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character or setting the cursor, or the [`CancelToken`] is cancelled.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// lcd.print_centered("Happy birthday, Ada!")?;
+    /// // "Happy birthday, "
+    /// // "      Ada!      "
+    /// ```
+    pub fn print_centered(&mut self, str: &str) -> anyhow::Result<()> {
+        let rows = self.rows();
+        let mut lines =
+            WrappedLines::new(str, self.cols, WrapMode::Word).with_charmap(self.charmap);
+        let count = lines.clone().take(rows.into()).count() as u8;
+        let top = (rows - count) / 2;
+        for row in 0..rows {
+            self.set_cursor(0, row)?;
+            let mut len = 0;
+            if (top..top + count).contains(&row) {
+                if let Some(line) = lines.next() {
+                    let pad = (self.cols as usize).saturating_sub(line.len()) / 2;
+                    for _ in 0..pad {
+                        self.print(' ')?;
+                    }
+                    for ch in line.chars() {
+                        self.check_cancel()?;
+                        self.print(ch)?;
+                    }
+                    len = pad + line.len();
+                }
+            }
+            for _ in len..self.cols as usize {
+                self.print(' ')?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Controls the autoscroll feature of the LCD.
+    ///
+    /// This function enables or disables the autoscroll feature, which causes the display to automatically
+    /// scroll the text when new characters are printed.
+    ///
+    /// # Arguments
+    ///
+    /// * `on` - A boolean indicating whether to enable (`true`) or disable (`false`) autoscroll.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the autoscroll setting is successfully changed.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn autoscroll(&mut self, on: bool) -> anyhow::Result<()> {
+        if on {
+            self.display_mode |= LCD_ENTRYSHIFTINCREMENT;
+        } else {
+            self.display_mode &= !LCD_ENTRYSHIFTINCREMENT;
+        }
+
+        let cmd = LCD_ENTRYMODESET | self.display_mode;
+        self.send(cmd, 0x0)?;
+        Ok(())
+    }
+
+    /// Returns `true` if autoscroll is enabled, see [`autoscroll`](Self::autoscroll).
+    pub fn is_autoscroll_on(&self) -> bool {
+        self.display_mode & LCD_ENTRYSHIFTINCREMENT != 0
+    }
+
+    /// Scrolls the display to the left.
+    ///
+    /// This function shifts the entire display to the left by one position.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the display is successfully scrolled.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn scroll_left(&mut self) -> anyhow::Result<()> {
+        let cmd = LCD_CURSORSHIFT | LCD_DISPLAYMOVE | LCD_MOVELEFT;
+        self.send(cmd, 0x0)?;
+        Ok(())
+    }
+
+    /// Scrolls the display to the right.
+    ///
+    /// This function shifts the entire display to the right by one position.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the display is successfully scrolled.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn scroll_right(&mut self) -> anyhow::Result<()> {
+        let cmd = LCD_CURSORSHIFT | LCD_DISPLAYMOVE | LCD_MOVERIGHT;
+        self.send(cmd, 0x0)?;
+        Ok(())
+    }
+
+    /// Sets the text direction to left-to-right.
+    ///
+    /// This function sets the display mode to left-to-right text entry and sends the command to the LCD.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the text direction is successfully set.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn left_to_right(&mut self) -> anyhow::Result<()> {
+        self.display_mode |= LCD_ENTRYLEFT;
+        let cmd = LCD_ENTRYMODESET | self.display_mode;
+        self.send(cmd, 0x0)?;
+        Ok(())
+    }
+
+    /// Sets the text direction to right-to-left.
+    ///
+    /// This function sets the display mode to right-to-left text entry and sends the command to the LCD.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the text direction is successfully set.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn right_to_left(&mut self) -> anyhow::Result<()> {
+        self.display_mode &= !LCD_ENTRYLEFT;
+        let cmd = LCD_ENTRYMODESET | self.display_mode;
+        self.send(cmd, 0x0)?;
+        Ok(())
+    }
+
+    /// Returns the direction the cursor moves after each character, see
+    /// [`left_to_right`](Self::left_to_right) and [`right_to_left`](Self::right_to_left).
+    pub fn entry_direction(&self) -> TextDirection {
+        if self.display_mode & LCD_ENTRYLEFT != 0 {
+            TextDirection::LeftToRight
+        } else {
+            TextDirection::RightToLeft
+        }
+    }
+
+    /// Returns the cursor to the home position (0,0).
+    ///
+    /// This function sends the `LCD_RETURNHOME` command to the LCD, which moves the cursor
+    /// to the home position (0,0) and waits for the command to complete.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the cursor is successfully moved to the home position.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn home(&mut self) -> anyhow::Result<()> {
+        self.send(LCD_RETURNHOME, 0x0)?;
+        Ets::delay_us(self.profile.clear_us.into());
+        self.current_line = 0;
+        self.current_col = 0;
+        Ok(())
+    }
+
+    /// Sends a single instruction to the controller.
+    ///
+    /// This gives access to controller features the higher-level methods do not wrap, such as
+    /// entry modes that shift the display. The driver's own copy of the entry mode and display
+    /// control settings, and its cursor model, follow the instruction, so the other methods
+    /// keep working afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The instruction to send.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the instruction is successfully sent.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn raw_command(&mut self, command: Command) -> anyhow::Result<()> {
+        self.send(command.encode(), 0x0)?;
+        if command.is_slow() {
+            Ets::delay_us(self.profile.clear_us.into());
+        }
+        match command {
+            Command::Clear | Command::Home => {
+                self.current_line = 0;
+                self.current_col = 0;
+            }
+            Command::EntryMode { .. } => {
+                self.display_mode = command.encode() & !LCD_ENTRYMODESET;
+            }
+            Command::DisplayControl { .. } => {
+                self.display_control = command.encode() & !LCD_DISPLAYCONTROL;
+            }
+            Command::SetDdram(address) => {
+                let cols = self.cols;
+                let visible = ROW_OFFSETS
+                    .iter()
+                    .take(self.rows() as usize)
+                    .position(|&offset| (offset..offset + cols).contains(&address));
+                if let Some(row) = visible {
+                    self.current_line = row as u8;
+                    self.current_col = address - ROW_OFFSETS[row];
+                }
+            }
+            Command::Shift { .. } | Command::FunctionSet { .. } | Command::SetCgram(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Returns the cursor position as `(col, row)`.
+    ///
+    /// The column equals [`cols`](Self::cols) once the last cell of a row has been written;
+    /// the next [`advance`](Self::advance) or [`newline`](Self::newline) moves on from there.
+    pub fn cursor_position(&self) -> (u8, u8) {
+        (self.current_col, self.current_line)
+    }
+
+    /// Moves the cursor one cell forward, continuing at the start of the next row after the
+    /// last column and at the top after the last row.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the cursor is successfully moved.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn advance(&mut self) -> anyhow::Result<()> {
+        if self.current_col + 1 >= self.cols {
+            self.newline()
+        } else {
+            self.set_cursor(self.current_col + 1, self.current_line)
+        }
+    }
+
+    /// Moves the cursor to the start of the next row, or of the first row after the last one.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the cursor is successfully moved.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn newline(&mut self) -> anyhow::Result<()> {
+        let row = (self.current_line + 1) % self.rows();
+        self.set_cursor(0, row)
+    }
+
+    /// Moves the cursor to the start of the current row.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the cursor is successfully moved.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the command.
+    pub fn carriage_return(&mut self) -> anyhow::Result<()> {
+        self.set_cursor(0, self.current_line)
+    }
+
+    /// Moves the cursor to the next line on the LCD.
+    ///
+    /// This function sets the cursor to the beginning of the next line, wrapping around to the
+    /// first row after the last one, like [`newline`](Self::newline). If the display has only
+    /// one row, it returns an error.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the cursor is successfully moved to the next line.
+    /// * `Err(anyhow::Error)` - If the display has only one row.
+    pub fn next_line(&mut self) -> anyhow::Result<()> {
+        if self.rows() == 1 {
+            return Err(anyhow::anyhow!("Next line not supported on 1 row display"));
+        }
+        self.newline()
+    }
+
+    /// Creates a custom character in the LCD's CGRAM (Character Generator RAM).
+    ///
+    /// When the location already holds a character created since [`init`](Self::init), only
+    /// the rows that differ from it are sent, each run of changed rows from its own CGRAM
+    /// address. Animation frames such as a spinner or a filling bar usually change a few rows,
+    /// so this cuts their bus traffic by half or more; an unchanged character sends nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location in CGRAM to store the custom character (0-7).
+    /// * `charmap` - A slice containing the character map (8 bytes).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the custom character is successfully created.
+    /// * `Err(anyhow::Error)` - If the location is out of bounds, the character map is shorter than 8 bytes or there is an error while sending the data.
+    pub fn create_custom_chars(&mut self, location: u8, charmap: &[u8]) -> anyhow::Result<()> {
+        if location > 7 {
+            return Err(anyhow::anyhow!("Custom character location out of bounds"));
+        }
+        let Some(&bitmap) = charmap.first_chunk::<8>() else {
+            return Err(anyhow::anyhow!("Custom character map must have 8 rows"));
+        };
+        let synced = self.cgram_synced & (1 << location) != 0;
+        let previous = self.cgram[location as usize].filter(|_| synced);
+        self.cgram[location as usize] = Some(bitmap);
+        // The bit is set again once every row is on the controller, so a failed upload is
+        // sent in full next time.
+        self.cgram_synced &= !(1 << location);
+        let mut next = None;
+        for (line, &row) in bitmap.iter().enumerate() {
+            let line = line as u8;
+            if previous.is_some_and(|previous| previous[line as usize] == row) {
+                continue;
+            }
+            if next != Some(line) {
+                self.send(LCD_SETCGRAMADDR | (location << 3) | line, 0x0)?;
+            }
+            self.send(row, RS)?;
+            next = Some(line + 1);
+        }
+        self.cgram_synced |= 1 << location;
+        Ok(())
+    }
+
+    /// Returns the custom character last created at `location`, if any.
+    pub fn custom_char(&self, location: u8) -> Option<[u8; 8]> {
+        self.cgram.get(location as usize).copied().flatten()
+    }
+
+    /// Writes every custom character created so far to CGRAM again.
+    ///
+    /// The controller loses CGRAM when it loses power, so after a brown-out the custom
+    /// characters show as random patterns until they are reloaded. [`reinit`](Self::reinit)
+    /// calls this.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the custom characters are reloaded.
+    /// * `Err(anyhow::Error)` - If there is an error while sending the data, with a [`ProgressReport`] of the characters reloaded.
+    pub fn reload_cgram(&mut self) -> anyhow::Result<()> {
+        self.cgram_synced = 0;
+        let total = self.cgram.iter().flatten().count();
+        let mut written = 0;
+        for location in 0..8 {
+            if let Some(bitmap) = self.cgram[location as usize] {
+                self.create_custom_chars(location, &bitmap)
+                    .map_err(|e| e.context(ProgressReport::new(written, total)))?;
+                written += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Enables reading from the display.
+    ///
+    /// Reading needs a backpack that connects the RW pin to the port expander (pin P1 on the
+    /// common PCF8574 boards). Many cheap modules tie RW to ground, so readback is off by default
+    /// and the read methods return an error until it is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `on` - A boolean indicating whether readback is available (`true`) or not (`false`).
+    pub fn set_readback(&mut self, on: bool) {
+        self.readback = on;
+    }
+
+    /// Returns `true` if readback has been enabled with [`set_readback`](Self::set_readback).
+    pub fn has_readback(&self) -> bool {
+        self.readback
+    }
+
+    /// Enables verification of every printed character.
+    ///
+    /// When enabled, each character is read back right after it is written and rewritten if the
+    /// code differs, up to three attempts. This protects against the occasional corrupted
+    /// character in electrically noisy installations, at the cost of roughly three times the
+    /// bus traffic. Requires readback, see [`set_readback`](Self::set_readback).
+    ///
+    /// # Arguments
+    ///
+    /// * `on` - A boolean indicating whether to verify writes (`true`) or not (`false`).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the setting is changed.
+    /// * `Err(anyhow::Error)` - If verification is requested but readback is not enabled.
+    pub fn set_verify(&mut self, on: bool) -> anyhow::Result<()> {
+        if on && !self.readback {
+            return Err(anyhow::anyhow!("Verify requires readback"));
+        }
+        self.verify = on;
+        Ok(())
+    }
+
+    /// Enables reading the port expander's pins back after every write.
+    ///
+    /// A PCF8574 reports the level of its pins when read, which matches what was written
+    /// unless something else drives them or the bus: another device holding SDA, a second
+    /// device answering on the same address, or a pin shorted to ground, such as the backlight
+    /// pin behind a failed transistor. When three writes in a row read back wrong, the write
+    /// fails with a [`BusContention`](crate::BusContention) error that says which pins are
+    /// affected, instead of the display silently showing garbage. A single mismatch is taken
+    /// for noise. This works on any backpack, without [`set_readback`](Self::set_readback),
+    /// and roughly doubles the bus traffic.
+    ///
+    /// # Arguments
+    ///
+    /// * `on` - A boolean indicating whether to check the port (`true`) or not (`false`).
+    pub fn set_port_check(&mut self, on: bool) {
+        self.port_check = on;
+        self.port_mismatches = 0;
+    }
+
+    /// Returns `true` if the port check is enabled, see [`set_port_check`](Self::set_port_check).
+    pub fn has_port_check(&self) -> bool {
+        self.port_check
+    }
+
+    /// Reads the character code stored at a position in DDRAM.
+    ///
+    /// This moves the cursor to the position after the one read.
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - The column position (0-indexed).
+    /// * `row` - The row position (0-indexed).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u8)` - The character code at the position.
+    /// * `Err(anyhow::Error)` - If readback is not enabled, the row is out of bounds, or there is an error on the bus.
+    pub fn read_ddram(&mut self, col: impl Into<Col>, row: impl Into<Row>) -> anyhow::Result<u8> {
+        let (col, row) = (col.into().0, row.into().0);
+        self.set_cursor(col, row)?;
+        self.read_data()
+    }
+
+    /// Reads the controller's busy flag and address counter.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((bool, u8))` - The busy flag and the 7-bit address counter.
+    /// * `Err(anyhow::Error)` - If readback is not enabled, the controller has no readable busy flag, or there is an error on the bus.
+    pub fn read_status(&mut self) -> anyhow::Result<(bool, u8)> {
+        if !self.profile.busy_readable {
+            return Err(anyhow::anyhow!("Controller has no readable busy flag"));
+        }
+        let status = self.receive(0x0)?;
+        Ok((status & 0x80 != 0, status & 0x7f))
+    }
+
+    /// Guesses what kind of panel is attached from how the controller maps DDRAM.
+    ///
+    /// This is a heuristic. A marker is written to the start of each DDRAM line and read back.
+    /// If the second line mirrors the first, the controller only implements one line, as on
+    /// some 16x1 modules. Otherwise it is treated as a multi-line panel. The controller has no notion of the glass it drives,
+    /// so 16x2 and 20x4 panels share the same two-line memory map and cannot be told apart
+    /// this way; pass the final size to [`set_geometry`](Self::set_geometry).
+    ///
+    /// The probed cells are restored afterwards and the cursor is returned home.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(DetectedGeometry)` - The kind of panel found.
+    /// * `Err(anyhow::Error)` - If readback is not enabled, the markers do not read back, or there is an error on the bus.
+    pub fn detect_geometry(&mut self) -> anyhow::Result<DetectedGeometry> {
+        const FIRST: u8 = 0x00;
+        const SECOND: u8 = 0x40;
+
+        let saved_first = self.read_at(FIRST)?;
+        let saved_second = self.read_at(SECOND)?;
+
+        self.write_at(FIRST, b'A')?;
+        self.write_at(SECOND, b'B')?;
+        let first = self.read_at(FIRST)?;
+        let second = self.read_at(SECOND)?;
+
+        self.write_at(FIRST, saved_first)?;
+        self.write_at(SECOND, saved_second)?;
+        self.home()?;
+
+        match (first, second) {
+            (b'A', b'B') => Ok(DetectedGeometry::MultiLine),
+            (b'B', b'B') => Ok(DetectedGeometry::SingleLine),
+            _ => Err(anyhow::anyhow!("DDRAM markers did not read back")),
+        }
+    }
+
+    /// Checks whether the controller has been reset, e.g. by a brown-out, and recovers if so.
+    ///
+    /// Battery-powered displays can lose power for a moment without the ESP32 noticing. The
+    /// controller then comes back blank and in 8-bit mode, and nothing sent afterwards makes
+    /// sense to it. Call this now and then to catch that: if the address counter does not
+    /// match the driver's model, or a sentinel written to a DDRAM cell outside the visible
+    /// area has gone, the display is re-initialized with [`reinit`](Self::reinit), which
+    /// redraws it from the shadow buffer (`buffer` feature).
+    ///
+    /// The first call only writes the sentinel, as does the first call after
+    /// [`clear`](Self::clear). Panels that show every DDRAM cell, such as 20x4 and 40x2, have
+    /// no room for it and rely on the address counter check alone. Text printed past the end
+    /// of a row can overwrite the sentinel, which causes one unneeded recovery.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `true` if a reset was detected and the display recovered.
+    /// * `Err(anyhow::Error)` - If readback is not enabled or there is an error on the bus.
+    pub fn audit(&mut self) -> anyhow::Result<bool> {
+        const SENTINEL: u8 = 0xa5;
+
+        let mut reset = false;
+        if self.profile.busy_readable {
+            let (busy, counter) = self.read_status()?;
+            reset = !busy && self.address.is_some_and(|address| address != counter);
+        }
+        if let Some(address) = self.sentinel_address() {
+            if !reset && self.sentinel {
+                reset = self.read_at(address)? != SENTINEL;
+            }
+            if reset {
+                self.reinit()?;
+            }
+            if !self.sentinel {
+                self.write_at(address, SENTINEL)?;
+                self.sentinel = true;
+            }
+            self.set_cursor(self.current_col, self.current_line)?;
+        } else if reset {
+            self.reinit()?;
+        }
+        Ok(reset)
+    }
+
+    /// Returns the last cell of a DDRAM line that the panel does not show, if there is one.
+    fn sentinel_address(&self) -> Option<u8> {
+        match self.panel_rows() {
+            1 => Some(0x4f),
+            2 if self.cols < 40 => Some(0x27),
+            4 if self.cols < 20 => Some(0x67),
+            _ => None,
+        }
+    }
+
+    fn write_verified(&mut self, value: u8) -> anyhow::Result<()> {
+        const ATTEMPTS: u8 = 3;
+
+        let (_, address) = self.read_status()?;
+        for attempt in 0..ATTEMPTS {
+            if attempt > 0 {
+                self.stats.retries = self.stats.retries.saturating_add(1);
+            }
+            self.write_at(address, value)?;
+            // Reading moves the address counter on, just as the write would have.
+            if self.read_at(address)? == value {
+                return Ok(());
+            }
+        }
+        Err(anyhow::anyhow!(
+            "Verify failed at DDRAM address {:#04x}",
+            address
+        ))
+    }
+
+    fn read_at(&mut self, address: u8) -> anyhow::Result<u8> {
+        self.send(LCD_SETDDRAMADDR | address, 0x0)?;
+        self.read_data()
+    }
+
+    fn write_at(&mut self, address: u8, value: u8) -> anyhow::Result<()> {
+        self.send(LCD_SETDDRAMADDR | address, 0x0)?;
+        self.send(value, RS)
+    }
+
+    fn read_data(&mut self) -> anyhow::Result<u8> {
+        let value = self.receive(RS)?;
+        self.step_address();
+        #[cfg(feature = "buffer")]
+        self.shadow.read();
+        Ok(value)
+    }
+
+    fn receive(&mut self, mode: u8) -> anyhow::Result<u8> {
+        if !self.readback {
+            return Err(anyhow::anyhow!("Readback not enabled"));
+        }
+        if self.headless {
+            return Err(anyhow::anyhow!("Display not connected"));
+        }
+        let high = self.read4bits(mode)?;
+        let low = self.read4bits(mode)?;
+        self.expander_write(mode | self.backlight)?;
+        Ok(high | (low >> 4))
+    }
+
+    fn read4bits(&mut self, mode: u8) -> anyhow::Result<u8> {
+        // Data pins are driven high so the quasi-bidirectional expander port can read them.
+        let idle = 0xf0 | RW | mode | self.backlight;
+        self.expander_write(idle)?;
+        self.expander_write(idle | EN)?;
+        Ets::delay_us(1);
+
+        let mut port = [0];
+        let started = Instant::now();
+        let result = self.i2c.read(self.i2c_address, &mut port);
+        self.stats.record_read(started.elapsed());
+        result?;
+
+        self.expander_write(idle)?;
+        Ok(port[0] & 0xf0)
+    }
+
+    fn expander_write(&mut self, data: u8) -> anyhow::Result<()> {
+        self.i2c_write(&[0, data])
+    }
+
+    /// Sends one I2C transaction to the port expander, which latches each byte after the
+    /// first onto its pins in turn.
+    fn i2c_write(&mut self, bytes: &[u8]) -> anyhow::Result<()> {
+        if let Some(observer) = self.observer {
+            observer.on_i2c_write(bytes);
+        }
+        if self.headless {
+            return Ok(());
+        }
+        let mut attempt = 0;
+        loop {
+            let started = Instant::now();
+            let result = self.i2c.write(self.i2c_address, bytes);
+            self.stats
+                .record_transaction(bytes.len(), started.elapsed());
+            match result {
+                Ok(()) => break,
+                Err(err) if attempt >= self.max_retries => return Err(anyhow::Error::from(err)),
+                Err(_) => {}
+            }
+            attempt += 1;
+            self.stats.retries = self.stats.retries.saturating_add(1);
+        }
+        match bytes.last() {
+            // While RW is high the display drives the data pins, so they differ on purpose.
+            Some(&written) if self.port_check && written & RW == 0 => self.check_port(written),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reads the port back after a write, failing once it has read wrong too often in a row.
+    fn check_port(&mut self, written: u8) -> anyhow::Result<()> {
+        const LIMIT: u8 = 3;
+
+        let mut port = [0];
+        let started = Instant::now();
+        let result = self.i2c.read(self.i2c_address, &mut port);
+        self.stats.record_read(started.elapsed());
+        result?;
+
+        if port[0] == written {
+            self.port_mismatches = 0;
+            return Ok(());
+        }
+        self.port_mismatches = self.port_mismatches.saturating_add(1);
+        if self.port_mismatches < LIMIT {
+            return Ok(());
+        }
+        Err(BusContention::new(written, port[0]).into())
+    }
+
+    fn pulse_enable(&mut self, data: u8) -> anyhow::Result<()> {
+        let pulse = (data | EN) | self.backlight;
+        self.expander_write(pulse)?;
+        Ets::delay_us(1);
+
+        let pulse = (data & !EN) | self.backlight;
+        self.expander_write(pulse)?;
+        Ets::delay_us(self.profile.command_us.into());
+        Ok(())
+    }
+
+    fn write4bits(&mut self, data: u8) -> anyhow::Result<()> {
+        self.expander_write(data)?;
+        self.pulse_enable(data)?;
+        Ok(())
+    }
+
+    /// Follows a command's effect on the DDRAM address counter.
+    ///
+    /// Anything that moves the counter in a way not modelled here, such as CGRAM access or
+    /// cursor shifts, makes the address unknown so the next cursor move is always sent.
+    fn track_address(&mut self, cmd: u8) {
+        if cmd & LCD_SETDDRAMADDR != 0 {
+            self.address = Some(cmd & 0x7f);
+        } else if cmd & LCD_SETCGRAMADDR != 0 {
+            self.address = None;
+        } else if cmd & LCD_FUNCTIONSET != 0 {
+            // Instruction table switches leave the address counter alone.
+        } else if cmd & LCD_CURSORSHIFT != 0 {
+            if cmd & LCD_DISPLAYMOVE == 0 {
+                self.address = None;
+            }
+        } else if cmd & (LCD_DISPLAYCONTROL | LCD_ENTRYMODESET) != 0 {
+            // Display, cursor and entry mode settings do not move the counter.
+        } else if cmd & (LCD_RETURNHOME | LCD_CLEARDISPLAY) != 0 {
+            self.address = Some(0);
+        }
+    }
+
+    /// Moves the cursor model on after a character is written, as the controller does.
+    fn track_column(&mut self) {
+        if self.display_mode & LCD_ENTRYLEFT != 0 {
+            self.current_col = self.current_col.saturating_add(1);
+        } else {
+            self.current_col = self.current_col.saturating_sub(1);
+        }
+    }
+
+    /// Moves the known DDRAM address on after a data byte is written or read.
+    fn step_address(&mut self) {
+        let two_line = self.rows() > 1;
+        let forward = self.display_mode & LCD_ENTRYLEFT != 0;
+        self.address = self
+            .address
+            .map(|address| next_ddram_address(address, two_line, forward));
+    }
+
+    pub(crate) fn send(&mut self, value: u8, mode: u8) -> anyhow::Result<()> {
+        if let Some(observer) = self.observer {
+            if mode & RS != 0 {
+                observer.on_data(value);
+            } else {
+                observer.on_command(value);
+            }
+        }
+        #[cfg(feature = "buffer")]
+        if mode & RS != 0 {
+            self.shadow.data(value);
+        } else {
+            self.shadow.command(value);
+        }
+        if mode & RS != 0 {
+            self.step_address();
+        } else {
+            self.track_address(value);
+        }
+
+        let high_nibble = value & 0xf0;
+        let low_nibble = (value << 4) & 0xf0;
+
+        let high_cmd = (high_nibble | mode) | self.backlight;
+        self.write4bits(high_cmd)?;
+
+        let low_cmd = (low_nibble | mode) | self.backlight;
+        self.write4bits(low_cmd)?;
+        Ok(())
+    }
+}
+
+/// The kind of panel reported by [`Lcd::detect_geometry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectedGeometry {
+    /// The second DDRAM line mirrors the first, as on 16x1 panels.
+    SingleLine,
+    /// The DDRAM lines are independent, as on 16x2, 20x2 and 20x4 panels.
+    MultiLine,
+}
+
+/// The direction text is entered in, returned by [`Lcd::entry_direction`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextDirection {
+    /// The cursor moves right after each character.
+    LeftToRight,
+    /// The cursor moves left after each character.
+    RightToLeft,
+}
+
+/// Returns the DDRAM address the controller moves to after a read or write at `address`.
+///
+/// In two-line mode the lines are 40 cells long and the address wraps from the end of one line
+/// to the start of the other; in one-line mode DDRAM is a single 80-cell line.
+pub(crate) fn next_ddram_address(address: u8, two_line: bool, forward: bool) -> u8 {
+    match (two_line, forward) {
+        (true, true) => match address {
+            0x27 => 0x40,
+            0x67 => 0x00,
+            a => a + 1,
+        },
+        (true, false) => match address {
+            0x40 => 0x27,
+            0x00 => 0x67,
+            a => a - 1,
+        },
+        (false, true) => (address + 1) % 0x50,
+        (false, false) => address.checked_sub(1).unwrap_or(0x4f),
+    }
+}
+
+#[cfg(all(test, feature = "buffer"))]
+mod tests {
+    use super::*;
+    use esp_idf_hal::sys::ESP_FAIL;
+
+    /// A 16x2 driver with no bus, drawing into the shadow buffer only.
+    fn headless() -> Lcd<'static> {
+        let mut lcd = Lcd::new(Err(EspError::from_infallible::<ESP_FAIL>()), 16, 2);
+        assert!(!lcd.probe());
+        lcd.init().unwrap();
+        lcd
+    }
+
+    #[test]
+    fn advance_wraps_from_the_last_column_to_the_next_row() {
+        let mut lcd = headless();
+        lcd.set_cursor(15, 0).unwrap();
+        lcd.advance().unwrap();
+        assert_eq!(lcd.cursor_position(), (0, 1));
+        lcd.print('A').unwrap();
+        assert_eq!(&lcd.screenshot().row(1)[..2], b"A ");
+    }
+
+    #[test]
+    fn newline_on_the_last_row_returns_to_the_top() {
+        let mut lcd = headless();
+        lcd.set_cursor(3, 1).unwrap();
+        lcd.newline().unwrap();
+        assert_eq!(lcd.cursor_position(), (0, 0));
+        lcd.print('B').unwrap();
+        assert_eq!(&lcd.screenshot().row(0)[..2], b"B ");
+    }
+
+    #[test]
+    fn carriage_return_overwrites_the_current_row() {
+        let mut lcd = headless();
+        lcd.set_cursor(0, 1).unwrap();
+        lcd.print_str("Hello").unwrap();
+        lcd.carriage_return().unwrap();
+        assert_eq!(lcd.cursor_position(), (0, 1));
+        lcd.print('J').unwrap();
+        assert_eq!(&lcd.screenshot().row(1)[..6], b"Jello ");
+        assert_eq!(&lcd.screenshot().row(0)[..6], b"      ");
+    }
+}
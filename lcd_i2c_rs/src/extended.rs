@@ -0,0 +1,308 @@
+//! Extended instruction set of the ST7036, used on EA DOG-M and similar modules.
+//!
+//! The ST7036 reuses the function set command to switch between instruction tables. Table 1
+//! holds the bias, icon RAM, power, follower and contrast commands. Every method here switches
+//! to the table it needs and back to the normal table 0.
+
+use crate::consts::*;
+use crate::controller::IconSegment;
+use crate::Lcd;
+use esp_idf_hal::delay::Ets;
+
+/// Instruction table 1: bias, icon RAM, power, follower and contrast.
+const TABLE_1: u8 = 0x01;
+
+const BIAS_SET: u8 = 0x14;
+const ICON_ADDRESS: u8 = 0x40;
+const POWER_ICON_CONTRAST: u8 = 0x50;
+const FOLLOWER_CONTROL: u8 = 0x60;
+const CONTRAST_SET: u8 = 0x70;
+
+const BIAS_ONE_FOURTH: u8 = 0x08;
+const ICON_ON: u8 = 0x08;
+const BOOSTER_ON: u8 = 0x04;
+const FOLLOWER_ON: u8 = 0x08;
+
+/// The DH bit of the function set command.
+pub(crate) const LCD_DOUBLEHEIGHT: u8 = 0x04;
+
+/// How long the voltage follower needs to settle after it is switched on.
+const FOLLOWER_SETTLE_MS: u32 = 200;
+
+/// The number of icon RAM addresses.
+pub(crate) const ICON_RAM_SIZE: u8 = 16;
+
+/// The LCD bias ratio, which depends on the module's glass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Bias {
+    /// 1/5 bias, used by most modules.
+    #[default]
+    OneFifth,
+    /// 1/4 bias.
+    OneFourth,
+}
+
+/// Whether the lines of a two-line panel are merged into one double-height line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DoubleHeight {
+    /// All lines are shown at normal height.
+    #[default]
+    Off,
+    /// The two lines are merged.
+    Top,
+}
+
+/// The settings held in the extended instruction tables, kept so that commands sharing a byte
+/// can be resent.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Extended {
+    contrast: u8,
+    booster: bool,
+    icons: bool,
+    bias: Bias,
+    amplifier_ratio: u8,
+    pub(crate) double_height: DoubleHeight,
+    icon_ram: [u8; ICON_RAM_SIZE as usize],
+}
+
+impl Default for Extended {
+    /// The values recommended for EA DOG-M modules running at 3.3V.
+    fn default() -> Self {
+        Self {
+            contrast: 0x28,
+            booster: true,
+            icons: false,
+            bias: Bias::OneFifth,
+            amplifier_ratio: 0x05,
+            double_height: DoubleHeight::Off,
+            icon_ram: [0; ICON_RAM_SIZE as usize],
+        }
+    }
+}
+
+impl Lcd<'_> {
+    /// Sets the contrast by software.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The contrast level from 0 to 63.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the contrast is successfully set.
+    /// * `Err(anyhow::Error)` - If the controller has no extended instruction set, the level is out of range, or there is an error while sending the command.
+    pub fn set_contrast(&mut self, level: u8) -> anyhow::Result<()> {
+        self.check_extended()?;
+        if level > 63 {
+            return Err(anyhow::anyhow!("Contrast out of range"));
+        }
+        self.extended.contrast = level;
+        self.send_table_1(&[CONTRAST_SET | (level & 0x0f), self.power_icon_contrast()])
+    }
+
+    /// Turns the internal voltage booster on or off.
+    ///
+    /// Modules running at 3.3V need the booster; at 5V it is usually off.
+    ///
+    /// # Arguments
+    ///
+    /// * `on` - A boolean indicating whether to turn the booster on (`true`) or off (`false`).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the booster is successfully switched.
+    /// * `Err(anyhow::Error)` - If the controller has no extended instruction set or there is an error while sending the command.
+    pub fn set_booster(&mut self, on: bool) -> anyhow::Result<()> {
+        self.check_extended()?;
+        self.extended.booster = on;
+        self.send_table_1(&[self.power_icon_contrast()])
+    }
+
+    /// Sets the LCD bias ratio.
+    ///
+    /// # Arguments
+    ///
+    /// * `bias` - The bias ratio of the module's glass.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the bias is successfully set.
+    /// * `Err(anyhow::Error)` - If the controller has no extended instruction set or there is an error while sending the command.
+    pub fn set_bias(&mut self, bias: Bias) -> anyhow::Result<()> {
+        self.check_extended()?;
+        self.extended.bias = bias;
+        self.send_table_1(&[self.bias_set()])
+    }
+
+    /// Sets the amplification ratio of the voltage follower, which scales the LCD drive voltage
+    /// together with the contrast.
+    ///
+    /// Waits for the follower to settle before returning.
+    ///
+    /// # Arguments
+    ///
+    /// * `ratio` - The ratio setting from 0 to 7.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the ratio is successfully set.
+    /// * `Err(anyhow::Error)` - If the controller has no extended instruction set, the ratio is out of range, or there is an error while sending the command.
+    pub fn set_amplifier_ratio(&mut self, ratio: u8) -> anyhow::Result<()> {
+        self.check_extended()?;
+        if ratio > 7 {
+            return Err(anyhow::anyhow!("Amplifier ratio out of range"));
+        }
+        self.extended.amplifier_ratio = ratio;
+        self.send_table_1(&[FOLLOWER_CONTROL | FOLLOWER_ON | ratio])?;
+        Ets::delay_ms(FOLLOWER_SETTLE_MS);
+        Ok(())
+    }
+
+    /// Shows or hides the icons driven by icon RAM.
+    ///
+    /// # Arguments
+    ///
+    /// * `on` - A boolean indicating whether to show (`true`) or hide (`false`) the icons.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the icons are successfully shown or hidden.
+    /// * `Err(anyhow::Error)` - If the controller has no extended instruction set or there is an error while sending the command.
+    pub fn show_icons(&mut self, on: bool) -> anyhow::Result<()> {
+        self.check_extended()?;
+        self.extended.icons = on;
+        self.send_table_1(&[self.power_icon_contrast()])
+    }
+
+    /// Writes the segment bits of one icon RAM address.
+    ///
+    /// This moves the address counter into icon RAM, so set the cursor again before printing.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The icon RAM address from 0 to 15.
+    /// * `segments` - The segment bits, of which the low 5 are used.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the icon RAM is successfully written.
+    /// * `Err(anyhow::Error)` - If the controller has no extended instruction set, the address is out of range, or there is an error while sending the command.
+    pub fn write_icon_ram(&mut self, address: u8, segments: u8) -> anyhow::Result<()> {
+        self.check_extended()?;
+        if address >= ICON_RAM_SIZE {
+            return Err(anyhow::anyhow!("Icon RAM address out of range"));
+        }
+        self.extended.icon_ram[address as usize] = segments & 0x1f;
+        self.send(self.function_set() | TABLE_1, 0x0)?;
+        self.send(ICON_ADDRESS | address, 0x0)?;
+        self.send(segments & 0x1f, RS)?;
+        self.send(self.function_set(), 0x0)?;
+        Ok(())
+    }
+
+    /// Turns one of the module's fixed icons on or off.
+    ///
+    /// The icon's location comes from the [`icons`](crate::ControllerProfile::icons) map of the
+    /// controller profile. Icons are only shown after [`show_icons`](Self::show_icons), and like
+    /// [`write_icon_ram`](Self::write_icon_ram) this leaves the cursor in icon RAM.
+    ///
+    /// # Arguments
+    ///
+    /// * `segment` - The icon to switch.
+    /// * `on` - A boolean indicating whether to turn the icon on (`true`) or off (`false`).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the icon is successfully switched.
+    /// * `Err(anyhow::Error)` - If the module has no such icon or there is an error while sending the command.
+    pub fn set_icon(&mut self, segment: IconSegment, on: bool) -> anyhow::Result<()> {
+        let icon = self
+            .profile()
+            .icons
+            .iter()
+            .find(|icon| icon.segment == segment)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Module has no {:?} icon", segment))?;
+        let mut segments = self.extended.icon_ram[icon.address as usize];
+        if on {
+            segments |= 1 << icon.bit;
+        } else {
+            segments &= !(1 << icon.bit);
+        }
+        self.write_icon_ram(icon.address, segments)
+    }
+
+    /// Merges two lines into one line of double-height characters.
+    ///
+    /// Only two-line panels are supported; the panel then shows a single line and
+    /// [`rows`](Self::rows) drops by one while the mode is on, so the merged pair is addressed
+    /// as a single row. Use [`print_large`](Self::print_large) for large text that also works
+    /// on controllers without double height.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - [`DoubleHeight::Top`] to merge the lines, or [`DoubleHeight::Off`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the mode is successfully set.
+    /// * `Err(anyhow::Error)` - If the controller has no extended instruction set, the panel does not have two lines, or there is an error while sending the command.
+    pub fn set_double_height(&mut self, mode: DoubleHeight) -> anyhow::Result<()> {
+        self.check_extended()?;
+        if mode != DoubleHeight::Off && self.panel_rows() != 2 {
+            return Err(anyhow::anyhow!("Double height needs a two-line panel"));
+        }
+        self.extended.double_height = mode;
+        self.send(self.function_set(), 0x0)?;
+        Ok(())
+    }
+
+    /// Sends every extended setting, as part of [`init`](Self::init).
+    pub(crate) fn apply_extended(&mut self) -> anyhow::Result<()> {
+        let ratio = self.extended.amplifier_ratio;
+        self.send_table_1(&[
+            self.bias_set(),
+            CONTRAST_SET | (self.extended.contrast & 0x0f),
+            self.power_icon_contrast(),
+            FOLLOWER_CONTROL | FOLLOWER_ON | ratio,
+        ])?;
+        Ets::delay_ms(FOLLOWER_SETTLE_MS);
+        Ok(())
+    }
+
+    fn check_extended(&self) -> anyhow::Result<()> {
+        if !self.profile().extended_instructions {
+            return Err(anyhow::anyhow!(
+                "Controller has no extended instruction set"
+            ));
+        }
+        Ok(())
+    }
+
+    fn send_table_1(&mut self, commands: &[u8]) -> anyhow::Result<()> {
+        self.send(self.function_set() | TABLE_1, 0x0)?;
+        for &cmd in commands {
+            self.send(cmd, 0x0)?;
+        }
+        self.send(self.function_set(), 0x0)?;
+        Ok(())
+    }
+
+    fn bias_set(&self) -> u8 {
+        match self.extended.bias {
+            Bias::OneFifth => BIAS_SET,
+            Bias::OneFourth => BIAS_SET | BIAS_ONE_FOURTH,
+        }
+    }
+
+    fn power_icon_contrast(&self) -> u8 {
+        let mut cmd = POWER_ICON_CONTRAST | ((self.extended.contrast >> 4) & 0x03);
+        if self.extended.icons {
+            cmd |= ICON_ON;
+        }
+        if self.extended.booster {
+            cmd |= BOOSTER_ON;
+        }
+        cmd
+    }
+}
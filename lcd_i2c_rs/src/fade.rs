@@ -0,0 +1,146 @@
+use crate::{AnimClock, Lcd, Moment};
+use std::time::Duration;
+
+/// How a backlight fade moves between two brightness levels.
+///
+/// The eye is far more sensitive to changes in dim light than in bright light, so a fade that
+/// steps the PWM duty evenly seems to rush through the low end and crawl at the top. The
+/// perceptual curves step evenly in perceived lightness instead and convert each step back to
+/// a duty for the [`Dimmer`](crate::Dimmer).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FadeCurve {
+    /// Steps the duty evenly.
+    Linear,
+    /// Steps evenly in perceived lightness, taking duty as lightness raised to the given
+    /// power, typically 2.2.
+    Gamma(f32),
+    /// Steps evenly in CIE 1931 lightness (L*), the closest match to how bright an LED looks.
+    #[default]
+    Cie1931,
+}
+
+impl FadeCurve {
+    /// Converts a duty to a perceived lightness from 0.0 to 1.0.
+    fn lightness(self, level: u8) -> f32 {
+        let duty = level as f32 / 255.0;
+        match self {
+            Self::Linear => duty,
+            Self::Gamma(gamma) => duty.powf(1.0 / gamma.max(0.1)),
+            Self::Cie1931 if duty <= 0.008856 => duty * 9.033,
+            Self::Cie1931 => (116.0 * duty.cbrt() - 16.0) / 100.0,
+        }
+    }
+
+    /// Converts a perceived lightness from 0.0 to 1.0 to a duty.
+    fn duty(self, lightness: f32) -> u8 {
+        let lightness = lightness.clamp(0.0, 1.0);
+        let duty = match self {
+            Self::Linear => lightness,
+            Self::Gamma(gamma) => lightness.powf(gamma.max(0.1)),
+            Self::Cie1931 if lightness <= 0.08 => lightness / 9.033,
+            Self::Cie1931 => ((lightness * 100.0 + 16.0) / 116.0).powi(3),
+        };
+        (duty * 255.0).round() as u8
+    }
+
+    /// Returns the brightness a fractional way through a fade.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The brightness the fade starts at.
+    /// * `to` - The brightness the fade ends at.
+    /// * `progress` - How far through the fade, from 0.0 to 1.0.
+    pub fn interpolate(self, from: u8, to: u8, progress: f32) -> u8 {
+        if progress >= 1.0 {
+            return to;
+        }
+        if progress <= 0.0 {
+            return from;
+        }
+        let (from_l, to_l) = (self.lightness(from), self.lightness(to));
+        self.duty(from_l + (to_l - from_l) * progress)
+    }
+}
+
+/// A brightness fade being played.
+pub(crate) struct Fade {
+    from: u8,
+    to: u8,
+    duration: Duration,
+    started: Moment,
+}
+
+impl Lcd<'_> {
+    /// Sets the curve used by [`fade_to`](Self::fade_to) and by
+    /// [`BacklightSchedule`](crate::BacklightSchedule), [`FadeCurve::Cie1931`] by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `curve` - The curve to fade along.
+    pub fn set_fade_curve(&mut self, curve: FadeCurve) {
+        self.fade_curve = curve;
+    }
+
+    /// Returns the curve backlight fades follow.
+    pub fn fade_curve(&self) -> FadeCurve {
+        self.fade_curve
+    }
+
+    /// Starts fading the brightness to a level, without blocking.
+    ///
+    /// The fade advances on each call to [`tick`](Self::tick), which should be made from the
+    /// main loop every few tens of milliseconds; the more often, the smoother it is. Starting a
+    /// fade while another plays carries on from the brightness reached. Without a
+    /// [`Dimmer`](crate::Dimmer) the backlight can only switch, so it is on throughout and
+    /// turns off at the end of a fade to 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The brightness to end at, from 0 (off) to 255 (full).
+    /// * `duration` - How long the fade takes; zero sets the level at once.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the fade is started.
+    /// * `Err(anyhow::Error)` - If the dimmer fails or there is an error while switching the backlight.
+    pub fn fade_to(&mut self, level: u8, duration: Duration) -> anyhow::Result<()> {
+        self.fade = Some(Fade {
+            from: self.brightness(),
+            to: level,
+            duration,
+            started: AnimClock::now(),
+        });
+        self.tick_fade().map(|_| ())
+    }
+
+    /// Stops a fade at the brightness it has reached.
+    pub fn stop_fade(&mut self) {
+        self.fade = None;
+    }
+
+    /// Returns `true` while a fade is playing.
+    pub fn is_fading(&self) -> bool {
+        self.fade.is_some()
+    }
+
+    /// Sets the brightness a running fade has reached.
+    pub(crate) fn tick_fade(&mut self) -> anyhow::Result<bool> {
+        let Some(fade) = &self.fade else {
+            return Ok(false);
+        };
+        let elapsed = fade.started.elapsed();
+        let level = if elapsed >= fade.duration {
+            let to = fade.to;
+            self.fade = None;
+            to
+        } else {
+            let progress = elapsed.as_secs_f32() / fade.duration.as_secs_f32();
+            self.fade_curve.interpolate(fade.from, fade.to, progress)
+        };
+        if level == self.brightness() {
+            return Ok(false);
+        }
+        self.set_brightness(level)?;
+        Ok(true)
+    }
+}
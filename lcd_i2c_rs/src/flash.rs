@@ -0,0 +1,152 @@
+use crate::{AnimClock, Lcd, Moment};
+
+/// A rhythm of backlight flashes for [`Lcd::flash_backlight`].
+///
+/// The steps alternate between flash and pause durations in milliseconds, starting with a
+/// flash. A flash inverts the backlight from the state it was in when the pattern started, so
+/// a lit display blinks dark and a dark one blinks lit. A trailing pause separates
+/// repetitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlashPattern {
+    steps: &'static [u16],
+    repeats: u8,
+}
+
+impl FlashPattern {
+    /// One short flash.
+    pub const SINGLE: FlashPattern = FlashPattern::new(&[200, 300]);
+    /// `... --- ...` in Morse code.
+    pub const SOS: FlashPattern = FlashPattern::new(&[
+        150, 150, 150, 150, 150, 450, 450, 150, 450, 150, 450, 450, 150, 150, 150, 150, 150, 1050,
+    ]);
+    /// Two quick flashes, like a heartbeat.
+    pub const HEARTBEAT: FlashPattern = FlashPattern::new(&[100, 150, 100, 650]);
+
+    /// Creates a pattern that plays once.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - Alternating flash and pause durations in milliseconds, starting with a flash.
+    pub const fn new(steps: &'static [u16]) -> Self {
+        Self { steps, repeats: 1 }
+    }
+
+    /// Plays the pattern `times` times in a row; 0 repeats it until stopped.
+    pub const fn repeat(mut self, times: u8) -> Self {
+        self.repeats = times;
+        self
+    }
+
+    fn period_ms(&self) -> u32 {
+        self.steps.iter().map(|&step| step as u32).sum()
+    }
+}
+
+/// A backlight flash pattern being played.
+pub(crate) struct Flash {
+    pattern: FlashPattern,
+    started: Moment,
+    resting: bool,
+}
+
+impl Lcd<'_> {
+    /// Starts flashing the backlight in a pattern, without blocking.
+    ///
+    /// The pattern advances on each call to [`tick`](Self::tick), which should be made from the
+    /// main loop every few tens of milliseconds. Starting a pattern while another plays
+    /// replaces it. Modules without a backlight ignore it.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The pattern, e.g. [`FlashPattern::SOS`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the pattern is started.
+    /// * `Err(anyhow::Error)` - If there is an error while switching the backlight.
+    pub fn flash_backlight(&mut self, pattern: FlashPattern) -> anyhow::Result<()> {
+        if !self.profile().backlight {
+            return Ok(());
+        }
+        let resting = match &self.flash {
+            Some(flash) => flash.resting,
+            None => self.is_backlight_on(),
+        };
+        self.flash = Some(Flash {
+            pattern,
+            started: AnimClock::now(),
+            resting,
+        });
+        self.tick_flash().map(|_| ())
+    }
+
+    /// Stops a flash pattern early and puts the backlight back as it was.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the pattern is stopped, or none was playing.
+    /// * `Err(anyhow::Error)` - If there is an error while switching the backlight.
+    pub fn stop_flashing(&mut self) -> anyhow::Result<()> {
+        if let Some(flash) = self.flash.take() {
+            self.switch_backlight(flash.resting)?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` while a flash pattern is playing.
+    pub fn is_flashing(&self) -> bool {
+        self.flash.is_some()
+    }
+
+    /// Advances time-based effects such as [`flash_backlight`](Self::flash_backlight) and
+    /// [`fade_to`](Self::fade_to).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `true` if anything was sent to the display.
+    /// * `Err(anyhow::Error)` - If there is an error while sending data.
+    pub fn tick(&mut self) -> anyhow::Result<bool> {
+        let faded = self.tick_fade()?;
+        Ok(self.tick_flash()? || faded)
+    }
+
+    /// Advances a running flash pattern.
+    fn tick_flash(&mut self) -> anyhow::Result<bool> {
+        let Some(flash) = &self.flash else {
+            return Ok(false);
+        };
+        let period = flash.pattern.period_ms();
+        let elapsed = flash.started.elapsed().as_millis();
+        let repeats = flash.pattern.repeats as u128;
+        if period == 0 || (repeats > 0 && elapsed >= period as u128 * repeats) {
+            let resting = flash.resting;
+            self.flash = None;
+            return self.switch_backlight(resting);
+        }
+
+        let mut offset = (elapsed % period as u128) as u32;
+        let mut flashing = false;
+        for (i, &step) in flash.pattern.steps.iter().enumerate() {
+            if offset < step as u32 {
+                flashing = i % 2 == 0;
+                break;
+            }
+            offset -= step as u32;
+        }
+        let lit = flash.resting != flashing;
+        self.switch_backlight(lit)
+    }
+
+    /// Switches the backlight if it is not already in the given state.
+    fn switch_backlight(&mut self, on: bool) -> anyhow::Result<bool> {
+        if self.is_backlight_on() == on {
+            return Ok(false);
+        }
+        if on {
+            self.backlight_on()?;
+        } else {
+            self.backlight_off()?;
+        }
+        Ok(true)
+    }
+}
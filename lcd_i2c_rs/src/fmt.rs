@@ -0,0 +1,71 @@
+use core::fmt;
+
+/// A fixed-capacity string used to format numbers and labels without allocating.
+///
+/// Writes that do not fit are truncated at a character boundary.
+pub(crate) struct FmtBuf<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> FmtBuf<N> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        // Only whole UTF-8 sequences are ever copied in, see `write_str`.
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or("")
+    }
+}
+
+impl<const N: usize> fmt::Write for FmtBuf<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for ch in s.chars() {
+            let len = ch.len_utf8();
+            if self.len + len > N {
+                return Err(fmt::Error);
+            }
+            ch.encode_utf8(&mut self.buf[self.len..]);
+            self.len += len;
+        }
+        Ok(())
+    }
+}
+
+/// Formats `args` into a [`FmtBuf`], truncating on overflow.
+macro_rules! fmt_buf {
+    ($n:literal, $($arg:tt)*) => {{
+        let mut buf = $crate::fmt::FmtBuf::<$n>::new();
+        let _ = core::fmt::Write::write_fmt(&mut buf, format_args!($($arg)*));
+        buf
+    }};
+}
+
+pub(crate) use fmt_buf;
+
+/// Formats a duration in seconds as `HH:MM:SS`, or `Nd HH:MM:SS` from one day on.
+#[cfg(feature = "widgets")]
+pub(crate) fn uptime(secs: u32) -> FmtBuf<16> {
+    if secs >= 86_400 {
+        fmt_buf!(
+            16,
+            "{}d {:02}:{:02}:{:02}",
+            secs / 86_400,
+            secs / 3600 % 24,
+            secs / 60 % 60,
+            secs % 60
+        )
+    } else {
+        fmt_buf!(
+            16,
+            "{:02}:{:02}:{:02}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )
+    }
+}
@@ -0,0 +1,123 @@
+#[cfg(feature = "buffer")]
+use crate::shadow::Shadow;
+use crate::Lcd;
+#[cfg(feature = "buffer")]
+use crate::{Compositor, RefreshScheduler};
+use core::fmt;
+use core::mem::size_of;
+
+/// The RAM the display stack takes, in bytes, worked out at compile time from the sizes of
+/// its types and their const generics.
+///
+/// Everything the crate keeps is sized up front, with no heap, so the footprint is known
+/// before the firmware runs. Start from the layer the application uses, add the widgets it
+/// owns with [`with`](Self::with), and either print the report or cap it with
+/// [`assert_within`](Self::assert_within) in a `const`, which fails the build instead of the
+/// device once a change pushes the display past its share of RAM. The sizes are those of the
+/// target being built, so check them in the firmware build, not a host test.
+///
+/// # Example
+///
+/// ```ignore
+/// // Fails to compile if the UI outgrows 4 KiB.
+/// const UI: MemoryFootprint = MemoryFootprint::compositor::<4>()
+///     .with::<Sparkline<32>>()
+///     .with::<StatusBar<4>>()
+///     .assert_within(4 * 1024);
+///
+/// log::info!("{UI}");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryFootprint {
+    /// The [`Lcd`] driver, including its shadow buffer and glyph cache.
+    pub driver: usize,
+    /// The shadow copy of the display memory, part of the driver (`buffer` feature).
+    pub shadow: usize,
+    /// The CGRAM bitmaps the driver caches to skip redefining glyphs, part of the driver.
+    pub glyph_cache: usize,
+    /// The frames of a [`RefreshScheduler`](crate::RefreshScheduler), with its alert, overlay
+    /// and toast queue.
+    pub frames: usize,
+    /// The widget slots of a [`Compositor`](crate::Compositor) and the widgets added with
+    /// [`with`](Self::with).
+    pub widgets: usize,
+}
+
+impl MemoryFootprint {
+    /// Returns the footprint of the [`Lcd`] driver alone.
+    pub const fn driver() -> Self {
+        Self {
+            driver: size_of::<Lcd>(),
+            #[cfg(feature = "buffer")]
+            shadow: size_of::<Shadow>(),
+            #[cfg(not(feature = "buffer"))]
+            shadow: 0,
+            // The `cgram` field of the driver.
+            glyph_cache: size_of::<[Option<[u8; 8]>; 8]>(),
+            frames: 0,
+            widgets: 0,
+        }
+    }
+
+    /// Returns the footprint of a [`RefreshScheduler`](crate::RefreshScheduler) with the
+    /// driver it owns (`buffer` feature).
+    #[cfg(feature = "buffer")]
+    pub const fn scheduler() -> Self {
+        let driver = Self::driver();
+        Self {
+            frames: size_of::<RefreshScheduler>() - driver.driver,
+            ..driver
+        }
+    }
+
+    /// Returns the footprint of a [`Compositor`](crate::Compositor) of `N` widgets with the
+    /// scheduler and driver it owns, not counting the widgets themselves (`buffer` feature).
+    #[cfg(feature = "buffer")]
+    pub const fn compositor<const N: usize>() -> Self {
+        let scheduler = Self::scheduler();
+        Self {
+            widgets: size_of::<Compositor<N>>() - size_of::<RefreshScheduler>(),
+            ..scheduler
+        }
+    }
+
+    /// Adds a widget, or any other value the UI keeps, e.g. `with::<Sparkline<32>>()`.
+    pub const fn with<T>(mut self) -> Self {
+        self.widgets += size_of::<T>();
+        self
+    }
+
+    /// Returns the bytes taken in all.
+    pub const fn total(&self) -> usize {
+        self.driver + self.frames + self.widgets
+    }
+
+    /// Fails the build, when evaluated in a `const`, if the footprint exceeds `budget`.
+    ///
+    /// # Arguments
+    ///
+    /// * `budget` - The most bytes the display stack may take.
+    ///
+    /// # Panics
+    ///
+    /// If the total is over `budget`; in a `const` this is a compile error.
+    pub const fn assert_within(self, budget: usize) -> Self {
+        assert!(
+            self.total() <= budget,
+            "Display stack exceeds its RAM budget"
+        );
+        self
+    }
+}
+
+/// Lists the parts, one per line, e.g. for a boot log.
+impl fmt::Display for MemoryFootprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "driver      {:>6} B", self.driver)?;
+        writeln!(f, "  shadow    {:>6} B", self.shadow)?;
+        writeln!(f, "  glyphs    {:>6} B", self.glyph_cache)?;
+        writeln!(f, "frames      {:>6} B", self.frames)?;
+        writeln!(f, "widgets     {:>6} B", self.widgets)?;
+        write!(f, "total       {:>6} B", self.total())
+    }
+}
@@ -0,0 +1,170 @@
+use crate::charset::CharMap;
+use crate::{Col, Row};
+
+/// The most columns and rows a frame holds, matching the largest supported panel.
+pub(crate) const MAX_COLS: usize = 40;
+pub(crate) const MAX_ROWS: usize = 4;
+
+/// A rectangle of cells on the display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    /// The leftmost column (0-indexed).
+    pub col: u8,
+    /// The top row (0-indexed).
+    pub row: u8,
+    /// The number of columns.
+    pub width: u8,
+    /// The number of rows.
+    pub height: u8,
+}
+
+impl Rect {
+    /// Creates a rectangle.
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - The leftmost column (0-indexed).
+    /// * `row` - The top row (0-indexed).
+    /// * `width` - The number of columns.
+    /// * `height` - The number of rows.
+    pub const fn new(col: u8, row: u8, width: u8, height: u8) -> Self {
+        Self {
+            col,
+            row,
+            width,
+            height,
+        }
+    }
+
+    /// Returns `true` if the two rectangles share at least one cell.
+    pub fn overlaps(&self, other: &Rect) -> bool {
+        self.col < other.col.saturating_add(other.width)
+            && other.col < self.col.saturating_add(self.width)
+            && self.row < other.row.saturating_add(other.height)
+            && other.row < self.row.saturating_add(self.height)
+    }
+
+    /// Returns `true` if the rectangle lies within a display of the given size.
+    pub fn fits(&self, cols: u8, rows: u8) -> bool {
+        self.col as u16 + self.width as u16 <= cols as u16
+            && self.row as u16 + self.height as u16 <= rows as u16
+    }
+}
+
+/// A buffer of character codes laid out like the display.
+///
+/// Draw into a frame, then let a [`RefreshScheduler`](crate::RefreshScheduler) send only the
+/// cells that differ from what the display shows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    cells: [[u8; MAX_COLS]; MAX_ROWS],
+    cols: u8,
+    rows: u8,
+}
+
+impl Frame {
+    /// Creates a frame filled with spaces.
+    ///
+    /// # Arguments
+    ///
+    /// * `cols` - The number of columns, at most 40.
+    /// * `rows` - The number of rows, at most 4.
+    pub fn new(cols: u8, rows: u8) -> Self {
+        Self {
+            cells: [[b' '; MAX_COLS]; MAX_ROWS],
+            cols: cols.min(MAX_COLS as u8),
+            rows: rows.min(MAX_ROWS as u8),
+        }
+    }
+
+    /// Returns the number of columns.
+    pub fn cols(&self) -> u8 {
+        self.cols
+    }
+
+    /// Returns the number of rows.
+    pub fn rows(&self) -> u8 {
+        self.rows
+    }
+
+    /// Fills the frame with spaces.
+    pub fn clear(&mut self) {
+        self.cells = [[b' '; MAX_COLS]; MAX_ROWS];
+    }
+
+    /// Returns the character code of a cell, or `None` if it is out of bounds.
+    pub fn get(&self, col: impl Into<Col>, row: impl Into<Row>) -> Option<u8> {
+        let (col, row) = (col.into().0, row.into().0);
+        if col >= self.cols || row >= self.rows {
+            return None;
+        }
+        Some(self.cells[row as usize][col as usize])
+    }
+
+    /// Sets the character code of a cell. Cells out of bounds are ignored.
+    pub fn set(&mut self, col: impl Into<Col>, row: impl Into<Row>, code: u8) {
+        let (col, row) = (col.into().0, row.into().0);
+        if col < self.cols && row < self.rows {
+            self.cells[row as usize][col as usize] = code;
+        }
+    }
+
+    /// Returns the character codes of one row, or an empty slice if the row is out of bounds.
+    pub fn row(&self, row: u8) -> &[u8] {
+        if row >= self.rows {
+            return &[];
+        }
+        &self.cells[row as usize][..self.cols as usize]
+    }
+
+    /// Writes raw character codes from a position, clipped at the end of the row.
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - The column position (0-indexed).
+    /// * `row` - The row position (0-indexed).
+    /// * `codes` - The character codes to write.
+    pub fn write_bytes(&mut self, col: impl Into<Col>, row: impl Into<Row>, codes: &[u8]) {
+        let (col, row) = (col.into().0, row.into().0);
+        for (i, &code) in codes.iter().enumerate() {
+            let Some(col) = col.checked_add(i as u8) else {
+                break;
+            };
+            self.set(col, row, code);
+        }
+    }
+
+    /// Prints text from a position, clipped at the end of the row.
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - The column position (0-indexed).
+    /// * `row` - The row position (0-indexed).
+    /// * `text` - The text to print.
+    /// * `charmap` - How characters map to character codes, usually [`Lcd::charmap`](crate::Lcd::charmap).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the text is successfully printed.
+    /// * `Err(anyhow::Error)` - If a character cannot be shown and the fallback policy is [`Fallback::Error`](crate::charset::Fallback::Error).
+    pub fn print(
+        &mut self,
+        col: impl Into<Col>,
+        row: impl Into<Row>,
+        text: &str,
+        charmap: &CharMap,
+    ) -> anyhow::Result<()> {
+        let (col, row) = (col.into().0, row.into().0);
+        let mut col = col;
+        for ch in text.chars() {
+            if col >= self.cols {
+                break;
+            }
+            if let Some(code) = charmap.encode(ch)? {
+                self.set(col, row, code);
+                col += 1;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+//! Typed display coordinates.
+//!
+//! Methods that take a position accept either a plain `u8` or these newtypes for each
+//! coordinate. Plain numbers keep existing code working; the newtypes make a swapped column
+//! and row a compile error, which is easy to get wrong when porting from libraries that take
+//! `(row, col)`.
+//!
+//! ```ignore
+//! lcd.set_cursor(Col::new(4), Row::new(1))?;
+//! lcd.set_cursor(Row::new(1), Col::new(4))?; // Does not compile.
+//! ```
+
+/// The most columns of any supported panel.
+const MAX_COLS: u8 = 40;
+/// The most rows of any supported panel.
+const MAX_ROWS: u8 = 4;
+
+/// A column on the display (0-indexed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Col(pub u8);
+
+impl Col {
+    /// Creates a column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `col` is 40 or more, which no panel has. In a `const` this is a compile
+    /// error.
+    pub const fn new(col: u8) -> Self {
+        assert!(col < MAX_COLS, "Column out of range");
+        Self(col)
+    }
+
+    /// Returns the column number.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<u8> for Col {
+    fn from(col: u8) -> Self {
+        Self(col)
+    }
+}
+
+/// A row on the display (0-indexed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Row(pub u8);
+
+impl Row {
+    /// Creates a row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` is 4 or more, which no panel has. In a `const` this is a compile
+    /// error.
+    pub const fn new(row: u8) -> Self {
+        assert!(row < MAX_ROWS, "Row out of range");
+        Self(row)
+    }
+
+    /// Returns the row number.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<u8> for Row {
+    fn from(row: u8) -> Self {
+        Self(row)
+    }
+}
+
+/// The position of a cell counted row by row from the top left, for iterating over a display
+/// with a single index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct CellIndex(pub u16);
+
+impl CellIndex {
+    /// Returns the index of a cell on a display `cols` wide.
+    pub const fn new(col: Col, row: Row, cols: u8) -> Self {
+        Self(row.0 as u16 * cols as u16 + col.0 as u16)
+    }
+
+    /// Returns the column and row of the cell on a display `cols` wide.
+    pub const fn position(self, cols: u8) -> (Col, Row) {
+        let cols = if cols == 0 { 1 } else { cols as u16 };
+        (Col((self.0 % cols) as u8), Row((self.0 / cols) as u8))
+    }
+}
@@ -0,0 +1,183 @@
+//! Built-in custom character bitmaps.
+//!
+//! Each glyph is an 8-byte, 5x8 bitmap suitable for [`Lcd::create_custom_chars`](crate::Lcd::create_custom_chars).
+//! [`overlay`], [`mask`], [`invert`], [`shift`] and [`mirror`] derive new glyphs from these at
+//! compile time.
+
+/// Vertical bar glyphs, one per fill level.
+///
+/// `BAR_GLYPHS[0]` has the bottom pixel row lit and `BAR_GLYPHS[7]` fills the whole cell,
+/// giving eight distinct levels per character cell (nine including a blank cell).
+pub const BAR_GLYPHS: [[u8; 8]; 8] = [
+    bar_glyph(1),
+    bar_glyph(2),
+    bar_glyph(3),
+    bar_glyph(4),
+    bar_glyph(5),
+    bar_glyph(6),
+    bar_glyph(7),
+    bar_glyph(8),
+];
+
+/// Building blocks of the large digits printed by
+/// [`Lcd::print_large`](crate::Lcd::print_large), each digit being three cells wide and two
+/// cells high.
+///
+/// In order: rounded upper left, upper bar, rounded upper right, rounded lower left, lower bar,
+/// rounded lower right, and upper and lower bar.
+pub const BIG_DIGIT_GLYPHS: [[u8; 8]; 7] = [
+    [0x07, 0x0f, 0x1f, 0x1f, 0x1f, 0x1f, 0x1f, 0x1f],
+    [0x1f, 0x1f, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x1c, 0x1e, 0x1f, 0x1f, 0x1f, 0x1f, 0x1f, 0x1f],
+    [0x1f, 0x1f, 0x1f, 0x1f, 0x1f, 0x1f, 0x0f, 0x07],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x1f, 0x1f, 0x1f],
+    [0x1f, 0x1f, 0x1f, 0x1f, 0x1f, 0x1f, 0x1e, 0x1c],
+    [0x1f, 0x1f, 0x1f, 0x00, 0x00, 0x00, 0x1f, 0x1f],
+];
+
+/// Wi-Fi state icons.
+///
+/// In order: disconnected, connecting, connected and access point mode.
+pub const WIFI_GLYPHS: [[u8; 8]; 4] = [
+    [0x00, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x00, 0x00],
+    [0x1f, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x1f, 0x00],
+    [0x00, 0x0e, 0x11, 0x04, 0x0a, 0x00, 0x04, 0x00],
+    [0x15, 0x15, 0x0e, 0x04, 0x04, 0x04, 0x0e, 0x00],
+];
+
+/// Signal strength glyphs with zero to four rising bars over a baseline.
+pub const SIGNAL_GLYPHS: [[u8; 8]; 5] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x0f],
+    [0x00, 0x00, 0x00, 0x00, 0x04, 0x04, 0x0c, 0x0f],
+    [0x00, 0x00, 0x02, 0x02, 0x06, 0x06, 0x0e, 0x0f],
+    [0x01, 0x01, 0x03, 0x03, 0x07, 0x07, 0x0f, 0x0f],
+];
+
+/// Battery glyphs from empty to full, filling from the bottom.
+pub const BATTERY_GLYPHS: [[u8; 8]; 5] = [
+    battery_glyph(0),
+    battery_glyph(1),
+    battery_glyph(3),
+    battery_glyph(4),
+    battery_glyph(5),
+];
+
+/// Trend arrows: rising, falling and steady.
+pub const TREND_GLYPHS: [[u8; 8]; 3] = [
+    [0x04, 0x0e, 0x15, 0x04, 0x04, 0x04, 0x04, 0x00],
+    [0x04, 0x04, 0x04, 0x04, 0x15, 0x0e, 0x04, 0x00],
+    [0x00, 0x04, 0x02, 0x1f, 0x02, 0x04, 0x00, 0x00],
+];
+
+/// The pixels of a row, five to a row with the leftmost in bit 4.
+const ROW_MASK: u8 = 0b11111;
+
+/// Lights every pixel lit in either glyph, e.g. to put a symbol over a frame.
+///
+/// These operations are `const`, so variants can be derived from a base glyph at compile time:
+///
+/// ```ignore
+/// const BOLT: [u8; 8] = [0x00, 0x02, 0x04, 0x0e, 0x04, 0x08, 0x00, 0x00];
+/// const CHARGING: [u8; 8] = glyphs::overlay(
+///     glyphs::mask(BATTERY_GLYPHS[2], glyphs::invert(BOLT)),
+///     glyphs::shift(BOLT, 0, 1),
+/// );
+/// const LEFT_ARROW: [u8; 8] = glyphs::mirror(TREND_GLYPHS[2]);
+/// ```
+pub const fn overlay(a: [u8; 8], b: [u8; 8]) -> [u8; 8] {
+    let mut glyph = [0u8; 8];
+    let mut row = 0;
+    while row < 8 {
+        glyph[row] = (a[row] | b[row]) & ROW_MASK;
+        row += 1;
+    }
+    glyph
+}
+
+/// Keeps only the pixels lit in both glyphs, e.g. to cut a shape out of a glyph with an
+/// [`invert`]ed stencil.
+pub const fn mask(a: [u8; 8], b: [u8; 8]) -> [u8; 8] {
+    let mut glyph = [0u8; 8];
+    let mut row = 0;
+    while row < 8 {
+        glyph[row] = a[row] & b[row] & ROW_MASK;
+        row += 1;
+    }
+    glyph
+}
+
+/// Swaps lit and dark pixels, since the controller has no inverse video.
+pub const fn invert(glyph: [u8; 8]) -> [u8; 8] {
+    let mut inverted = [0u8; 8];
+    let mut row = 0;
+    while row < 8 {
+        inverted[row] = !glyph[row] & ROW_MASK;
+        row += 1;
+    }
+    inverted
+}
+
+/// Moves the pixels of a glyph; those moved past an edge are dropped and dark pixels come in
+/// from the other side.
+///
+/// # Arguments
+///
+/// * `glyph` - The glyph to move.
+/// * `dx` - The number of pixels to move right, or left if negative.
+/// * `dy` - The number of pixels to move down, or up if negative.
+pub const fn shift(glyph: [u8; 8], dx: i8, dy: i8) -> [u8; 8] {
+    let mut shifted = [0u8; 8];
+    let mut row = 0;
+    while row < 8 {
+        let from = row as i16 - dy as i16;
+        if from >= 0 && from < 8 && dx > -5 && dx < 5 {
+            let bits = glyph[from as usize] & ROW_MASK;
+            // Bit 4 is the leftmost pixel, so moving right shifts towards bit 0.
+            shifted[row] = if dx >= 0 {
+                bits >> dx
+            } else {
+                (bits << -dx) & ROW_MASK
+            };
+        }
+        row += 1;
+    }
+    shifted
+}
+
+/// Flips a glyph left to right, e.g. to turn a right arrow into a left one.
+pub const fn mirror(glyph: [u8; 8]) -> [u8; 8] {
+    let mut mirrored = [0u8; 8];
+    let mut row = 0;
+    while row < 8 {
+        let mut pixel = 0;
+        while pixel < 5 {
+            if glyph[row] & (1 << pixel) != 0 {
+                mirrored[row] |= 1 << (4 - pixel);
+            }
+            pixel += 1;
+        }
+        row += 1;
+    }
+    mirrored
+}
+
+const fn bar_glyph(level: usize) -> [u8; 8] {
+    let mut glyph = [0u8; 8];
+    let mut row = 8 - level;
+    while row < 8 {
+        glyph[row] = 0b11111;
+        row += 1;
+    }
+    glyph
+}
+
+const fn battery_glyph(filled: usize) -> [u8; 8] {
+    let mut glyph = [0x0e, 0x1f, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1f];
+    let mut row = 7 - filled;
+    while row < 7 {
+        glyph[row] = 0x1f;
+        row += 1;
+    }
+    glyph
+}
@@ -0,0 +1,138 @@
+// Golden files are compared in host tests, where allocating is fine.
+#![allow(clippy::disallowed_types, clippy::disallowed_methods)]
+
+use crate::consts::ROW_OFFSETS;
+use crate::Lcd;
+use std::fmt::Write;
+use std::path::Path;
+
+/// The environment variable that makes [`Lcd::check_golden`] write the golden file instead of
+/// comparing against it.
+pub const BLESS_VAR: &str = "LCD_BLESS";
+
+impl Lcd<'_> {
+    /// Returns the display memory as text, for comparing renderings in tests.
+    ///
+    /// The visible DDRAM is drawn as a box with one line per row. Printable ASCII is shown as
+    /// is, custom characters as `#` and other ROM characters as `?`; every cell that is not
+    /// plain ASCII is also listed by column with its code under the box. Custom characters
+    /// that have been defined follow as 5x8 bitmaps drawn with `#` and `.`.
+    pub fn snapshot_text(&self) -> String {
+        let mut text = String::new();
+        let cols = self.cols().min(40) as usize;
+        let rows = self.rows().min(4) as usize;
+        let border = "-".repeat(cols);
+        let _ = writeln!(text, "+{}+", border);
+        let mut codes = String::new();
+        for (row, &offset) in ROW_OFFSETS.iter().enumerate().take(rows) {
+            let cells = &self.shadow.ddram[offset as usize..offset as usize + cols];
+            text.push('|');
+            let mut listed = false;
+            for (col, &code) in cells.iter().enumerate() {
+                text.push(match code {
+                    0x00..=0x07 => '#',
+                    0x20..=0x7e => code as char,
+                    _ => '?',
+                });
+                if !(0x20..=0x7e).contains(&code) {
+                    if !listed {
+                        let _ = write!(codes, "row {}:", row);
+                        listed = true;
+                    }
+                    let _ = write!(codes, " {}={:02x}", col, code);
+                }
+            }
+            text.push_str("|\n");
+            if listed {
+                codes.push('\n');
+            }
+        }
+        let _ = writeln!(text, "+{}+", border);
+        text.push_str(&codes);
+
+        let slots: Vec<usize> = (0..8)
+            .filter(|&slot| self.shadow.cgram[slot].iter().any(|&line| line & 0x1f != 0))
+            .collect();
+        if slots.is_empty() {
+            return text;
+        }
+        text.push_str("cgram\n");
+        for slot in &slots {
+            let _ = write!(text, "{:<6}", slot);
+        }
+        text.truncate(text.trim_end().len());
+        text.push('\n');
+        for line in 0..8 {
+            for (i, &slot) in slots.iter().enumerate() {
+                if i > 0 {
+                    text.push(' ');
+                }
+                let bits = self.shadow.cgram[slot][line];
+                for bit in (0..5).rev() {
+                    text.push(if bits & (1 << bit) != 0 { '#' } else { '.' });
+                }
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Compares [`snapshot_text`](Self::snapshot_text) against a golden file checked in with
+    /// the tests.
+    ///
+    /// With the `LCD_BLESS` environment variable set, the golden file is written instead, so
+    /// intended changes to a rendering can be accepted with `LCD_BLESS=1 cargo test`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The golden file, relative to the package root when run by `cargo test`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the display matches the golden file, or the file was written.
+    /// * `Err(anyhow::Error)` - If the file is missing or differs, showing both renderings, or cannot be written.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// #[test]
+    /// fn battery_charging() {
+    ///     let mut lcd = Lcd::new(i2c, 16, 2);
+    ///     lcd.probe(); // No display on the host, so the driver goes headless.
+    ///     let mut battery = BatteryWidget::new().with_percentage(true);
+    ///     battery.set_level(60);
+    ///     battery.render(&mut lcd, 0, 0).unwrap();
+    ///     lcd.check_golden("tests/golden/battery_60.txt").unwrap();
+    /// }
+    /// ```
+    pub fn check_golden(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let actual = self.snapshot_text();
+        if std::env::var_os(BLESS_VAR).is_some() {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(path, &actual)?;
+            return Ok(());
+        }
+        let expected = std::fs::read_to_string(path).map_err(|e| {
+            anyhow::anyhow!(
+                "Cannot read golden file {}: {} (run with {}=1 to create it)\n{}",
+                path.display(),
+                e,
+                BLESS_VAR,
+                actual
+            )
+        })?;
+        if expected.replace("\r\n", "\n") != actual {
+            return Err(anyhow::anyhow!(
+                "Display differs from golden file {} (run with {}=1 to accept)\nexpected:\n{}actual:\n{}",
+                path.display(),
+                BLESS_VAR,
+                expected,
+                actual
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,363 @@
+//! A small catalog of translated labels, switched at runtime without allocating, and the
+//! number and date formats of each market.
+
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+/// The ID of a label: its index in the label table of every [`Language`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Label(pub u16);
+
+/// The labels of one language, indexed by [`Label`].
+#[derive(Debug, Clone, Copy)]
+pub struct Language<'c> {
+    code: &'c str,
+    labels: &'c [&'c str],
+}
+
+impl<'c> Language<'c> {
+    /// Creates a language.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The code the language is selected by, e.g. `"en"` or `"de"`.
+    /// * `labels` - The text of each label, in [`Label`] order. A missing or empty entry falls back to the default language.
+    pub const fn new(code: &'c str, labels: &'c [&'c str]) -> Self {
+        Self { code, labels }
+    }
+
+    /// Returns the code of the language.
+    pub fn code(&self) -> &'c str {
+        self.code
+    }
+}
+
+/// Resolves [`Label`]s to the text of the current language, for products shipped to several
+/// markets.
+///
+/// The label tables are static slices, so the catalog needs no allocation and can itself be a
+/// `static` shared by every widget and task. [`set_language`](Self::set_language) takes
+/// `&self` and bumps a [`generation`](Self::generation) counter; a
+/// [`Compositor`](crate::Compositor) given the catalog with
+/// [`with_catalog`](crate::Compositor::with_catalog) redraws all its widgets when it changes,
+/// and other code can compare the counter to know when to redraw. A label missing from the
+/// current language shows the default language's text instead, so a new label can ship before
+/// every translation is done.
+///
+/// Text is shown through the display's [`CharMap`](crate::charset::CharMap), so translations
+/// may use any character it maps, such as accented letters on the A02 ROM.
+///
+/// # Example
+///
+/// ```ignore
+/// const HELLO: Label = Label(0);
+/// const SETTINGS: Label = Label(1);
+///
+/// static LANGUAGES: [Language; 2] = [
+///     Language::new("en", &["Hello", "Settings"]),
+///     Language::new("de", &["Hallo", "Einstellungen"]),
+/// ];
+/// static CATALOG: Catalog = Catalog::new(&LANGUAGES);
+///
+/// CATALOG.set_language("de")?;
+/// canvas.print(0, 0, CATALOG.get(SETTINGS))?;
+/// ```
+#[derive(Debug)]
+pub struct Catalog<'c> {
+    languages: &'c [Language<'c>],
+    default: usize,
+    current: AtomicUsize,
+    generation: AtomicU32,
+}
+
+impl<'c> Catalog<'c> {
+    /// Creates a catalog showing the first language, which is also the default.
+    ///
+    /// # Arguments
+    ///
+    /// * `languages` - The languages, with the default first.
+    pub const fn new(languages: &'c [Language<'c>]) -> Self {
+        Self {
+            languages,
+            default: 0,
+            current: AtomicUsize::new(0),
+            generation: AtomicU32::new(0),
+        }
+    }
+
+    /// Sets the language whose text fills in labels missing from the current language, and
+    /// starts from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the default language in the slice given to [`new`](Self::new).
+    pub const fn with_default(mut self, index: usize) -> Self {
+        let index = if index < self.languages.len() {
+            index
+        } else {
+            0
+        };
+        self.default = index;
+        self.current = AtomicUsize::new(index);
+        self
+    }
+
+    /// Switches to another language.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The code of the language.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the language is switched to, or already current.
+    /// * `Err(anyhow::Error)` - If the catalog has no language with that code.
+    pub fn set_language(&self, code: &str) -> anyhow::Result<()> {
+        let index = self
+            .languages
+            .iter()
+            .position(|language| language.code == code)
+            .ok_or_else(|| anyhow::anyhow!("Unknown language"))?;
+        if self.current.swap(index, Ordering::Relaxed) != index {
+            self.generation.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Returns the code of the current language.
+    pub fn language(&self) -> &'c str {
+        self.languages
+            .get(self.current.load(Ordering::Relaxed))
+            .map_or("", |language| language.code)
+    }
+
+    /// Returns the languages, e.g. to offer them in a [`Picker`](crate::widgets::Picker).
+    pub fn languages(&self) -> &'c [Language<'c>] {
+        self.languages
+    }
+
+    /// Returns a counter that changes each time the language does.
+    pub fn generation(&self) -> u32 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Returns the text of a label in the current language, falling back to the default
+    /// language, or an empty string if neither has it.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label.
+    pub fn get(&self, label: Label) -> &'c str {
+        let lookup = |index: usize| {
+            self.languages
+                .get(index)
+                .and_then(|language| language.labels.get(label.0 as usize))
+                .copied()
+                .filter(|text| !text.is_empty())
+        };
+        lookup(self.current.load(Ordering::Relaxed))
+            .or_else(|| lookup(self.default))
+            .unwrap_or("")
+    }
+}
+
+/// The order of the day, month and year in a date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DateOrder {
+    /// `31/12/2024`, as in most of Europe.
+    #[default]
+    DayMonthYear,
+    /// `12/31/2024`, as in the United States.
+    MonthDayYear,
+    /// `2024-12-31`, as in ISO 8601 and East Asia.
+    YearMonthDay,
+}
+
+/// How numbers, dates and times are written for a market.
+///
+/// [`NumberEditor`](crate::widgets::NumberEditor),
+/// [`DateTimeEditor`](crate::widgets::DateTimeEditor) and
+/// [`EnvDashboard`](crate::widgets::EnvDashboard) take a locale with `with_locale`, and the
+/// `write_*` methods format with it for the application's own screens. The default is
+/// [`Locale::EN_GB`], the formats the widgets have always used.
+///
+/// # Example
+///
+/// ```ignore
+/// let locale = Locale { hour12: false, ..Locale::EN_US };
+/// let mut text = String::new();
+/// locale.write_time(&mut text, 23, 59, None)?; // "23:59"
+/// Locale::DE.write_number(&mut text, 21.5, 1)?; // "21,5"
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Locale {
+    /// The decimal separator, an ASCII character such as `.` or `,`.
+    pub decimal: char,
+    /// The order of the parts of a date.
+    pub date_order: DateOrder,
+    /// The character between the parts of a date, ASCII such as `/`, `.` or `-`.
+    pub date_separator: char,
+    /// Shows times on the 12-hour clock with `AM` and `PM` instead of the 24-hour clock.
+    pub hour12: bool,
+}
+
+impl Locale {
+    /// `21.5`, `31/12/2024` and `23:59`.
+    pub const EN_GB: Self = Self {
+        decimal: '.',
+        date_order: DateOrder::DayMonthYear,
+        date_separator: '/',
+        hour12: false,
+    };
+    /// `21.5`, `12/31/2024` and `11:59 PM`.
+    pub const EN_US: Self = Self {
+        decimal: '.',
+        date_order: DateOrder::MonthDayYear,
+        date_separator: '/',
+        hour12: true,
+    };
+    /// `21,5`, `31.12.2024` and `23:59`, as in Germany.
+    pub const DE: Self = Self {
+        decimal: ',',
+        date_order: DateOrder::DayMonthYear,
+        date_separator: '.',
+        hour12: false,
+    };
+    /// `21,5`, `31/12/2024` and `23:59`, as in France.
+    pub const FR: Self = Self {
+        decimal: ',',
+        date_order: DateOrder::DayMonthYear,
+        date_separator: '/',
+        hour12: false,
+    };
+    /// `21.5`, `2024-12-31` and `23:59`.
+    pub const ISO: Self = Self {
+        decimal: '.',
+        date_order: DateOrder::YearMonthDay,
+        date_separator: '-',
+        hour12: false,
+    };
+
+    /// Writes a number with a fixed number of decimals and the locale's decimal separator.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Where to write, e.g. a `String` or a fixed-capacity buffer.
+    /// * `value` - The number.
+    /// * `decimals` - The number of places after the separator.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the number is written.
+    /// * `Err(fmt::Error)` - If `out` fails, e.g. when it is full.
+    pub fn write_number(&self, out: &mut impl fmt::Write, value: f32, decimals: u8) -> fmt::Result {
+        let mut out = Localized {
+            out,
+            decimal: self.decimal,
+        };
+        write!(out, "{:.*}", decimals as usize, value)
+    }
+
+    /// Writes a date in the locale's order with its separator, e.g. `31.12.2024`.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Where to write.
+    /// * `year` - The year, written with four digits.
+    /// * `month` - The month, 1 to 12.
+    /// * `day` - The day of the month.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the date is written.
+    /// * `Err(fmt::Error)` - If `out` fails, e.g. when it is full.
+    pub fn write_date(
+        &self,
+        out: &mut impl fmt::Write,
+        year: u16,
+        month: u8,
+        day: u8,
+    ) -> fmt::Result {
+        let sep = self.date_separator;
+        match self.date_order {
+            DateOrder::DayMonthYear => write!(out, "{:02}{sep}{:02}{sep}{:04}", day, month, year),
+            DateOrder::MonthDayYear => write!(out, "{:02}{sep}{:02}{sep}{:04}", month, day, year),
+            DateOrder::YearMonthDay => write!(out, "{:04}{sep}{:02}{sep}{:02}", year, month, day),
+        }
+    }
+
+    /// Writes a time of day, `23:59` on the 24-hour clock or `11:59 PM` on the 12-hour clock,
+    /// where the hour is padded with a space so every time takes the same width.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Where to write.
+    /// * `hour` - The hour, 0 to 23.
+    /// * `minute` - The minute.
+    /// * `second` - The second, or `None` to leave seconds out.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the time is written.
+    /// * `Err(fmt::Error)` - If `out` fails, e.g. when it is full.
+    pub fn write_time(
+        &self,
+        out: &mut impl fmt::Write,
+        hour: u8,
+        minute: u8,
+        second: Option<u8>,
+    ) -> fmt::Result {
+        match self.hour12 {
+            true => write!(out, "{:2}:{:02}", Self::hour_of_12(hour), minute)?,
+            false => write!(out, "{:02}:{:02}", hour, minute)?,
+        }
+        if let Some(second) = second {
+            write!(out, ":{:02}", second)?;
+        }
+        match (self.hour12, hour < 12) {
+            (true, true) => out.write_str(" AM"),
+            (true, false) => out.write_str(" PM"),
+            (false, _) => Ok(()),
+        }
+    }
+
+    /// Returns the hour on the 12-hour clock, 1 to 12.
+    pub(crate) const fn hour_of_12(hour: u8) -> u8 {
+        match hour % 12 {
+            0 => 12,
+            hour => hour,
+        }
+    }
+
+    /// Writes already formatted number text with the locale's decimal separator.
+    #[cfg(feature = "widgets")]
+    pub(crate) fn write_localized(&self, out: &mut impl fmt::Write, text: &str) -> fmt::Result {
+        Localized {
+            out,
+            decimal: self.decimal,
+        }
+        .write_str(text)
+    }
+}
+
+/// Passes text through to `out` with each `.` replaced by a decimal separator.
+struct Localized<'o, W: fmt::Write> {
+    out: &'o mut W,
+    decimal: char,
+}
+
+impl<W: fmt::Write> fmt::Write for Localized<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for ch in s.chars() {
+            self.out
+                .write_char(if ch == '.' { self.decimal } else { ch })?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::EN_GB
+    }
+}
@@ -0,0 +1,196 @@
+use std::time::{Duration, Instant};
+
+/// How a [`ButtonEngine`] times presses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ButtonTiming {
+    /// How long the input must read the same before a change counts.
+    pub debounce: Duration,
+    /// How long a button must be held for [`ButtonAction::LongPress`], if at all.
+    pub long_press: Option<Duration>,
+    /// How long a button must be held before it starts repeating, if at all.
+    pub repeat_delay: Option<Duration>,
+    /// The time between the first repeats.
+    pub repeat_interval: Duration,
+    /// The percentage each repeat interval is of the one before, e.g. 80 to speed up by a
+    /// fifth each time; 100 repeats at a steady rate.
+    pub acceleration: u8,
+    /// The shortest time between repeats, however long the button is held.
+    pub min_interval: Duration,
+}
+
+impl ButtonTiming {
+    /// A 20 ms debounce, no long press and no repeat.
+    pub const fn new() -> Self {
+        Self {
+            debounce: Duration::from_millis(20),
+            long_press: None,
+            repeat_delay: None,
+            repeat_interval: Duration::from_millis(200),
+            acceleration: 100,
+            min_interval: Duration::from_millis(200),
+        }
+    }
+
+    /// Sets the debounce time.
+    pub const fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Sends [`ButtonAction::LongPress`] once a button is held for `hold`.
+    pub const fn with_long_press(mut self, hold: Duration) -> Self {
+        self.long_press = Some(hold);
+        self
+    }
+
+    /// Sends [`ButtonAction::Repeat`] while a button is held, starting after `delay` and then
+    /// every `interval`.
+    pub const fn with_repeat(mut self, delay: Duration, interval: Duration) -> Self {
+        self.repeat_delay = Some(delay);
+        self.repeat_interval = interval;
+        self.min_interval = interval;
+        self
+    }
+
+    /// Speeds repeats up the longer a button is held, so holding Down scrolls a long list
+    /// quickly. Call it after [`with_repeat`](Self::with_repeat).
+    ///
+    /// # Arguments
+    ///
+    /// * `percent` - Each interval as a percentage of the one before, e.g. 80.
+    /// * `min_interval` - The shortest interval.
+    pub const fn with_acceleration(mut self, percent: u8, min_interval: Duration) -> Self {
+        self.acceleration = percent;
+        self.min_interval = min_interval;
+        self
+    }
+}
+
+impl Default for ButtonTiming {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What a button did, reported by [`ButtonEngine::update`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ButtonAction {
+    /// The button went down.
+    Press,
+    /// The button was held for the long-press time.
+    LongPress,
+    /// The button is still held and repeats.
+    Repeat,
+    /// The button came back up. `long` is `true` if a long press was reported before.
+    Release {
+        /// Whether the press was long.
+        long: bool,
+    },
+}
+
+/// Debounces one button and detects long presses and repeats while held.
+///
+/// The shared core of the input adapters: feed it the raw state of a button on every poll and
+/// it reports what the button did, timed by a [`ButtonTiming`]. One timing is usually shared
+/// by all the buttons of an adapter. Call it at least every few milliseconds, since each call
+/// reports at most one action.
+///
+/// # Example
+///
+/// ```ignore
+/// const TIMING: ButtonTiming = ButtonTiming::new()
+///     .with_repeat(Duration::from_millis(400), Duration::from_millis(150))
+///     .with_acceleration(80, Duration::from_millis(30));
+/// let mut down = ButtonEngine::new();
+/// loop {
+///     match down.update(pin.is_low(), &TIMING, Instant::now()) {
+///         Some(ButtonAction::Press | ButtonAction::Repeat) => menu.handle(InputEvent::Down),
+///         _ => {}
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ButtonEngine {
+    raw: bool,
+    since: Option<Instant>,
+    pressed_at: Option<Instant>,
+    long_sent: bool,
+    next_repeat: Option<Instant>,
+    interval: Duration,
+}
+
+impl ButtonEngine {
+    /// Creates an engine for a button that is up.
+    pub const fn new() -> Self {
+        Self {
+            raw: false,
+            since: None,
+            pressed_at: None,
+            long_sent: false,
+            next_repeat: None,
+            interval: Duration::ZERO,
+        }
+    }
+
+    /// Returns `true` while the button is down, after debouncing.
+    pub fn is_pressed(&self) -> bool {
+        self.pressed_at.is_some()
+    }
+
+    /// Feeds the raw state of the button and returns what it did, if anything.
+    ///
+    /// # Arguments
+    ///
+    /// * `down` - Whether the button reads as pressed.
+    /// * `timing` - The debounce, long-press and repeat times.
+    /// * `now` - The time of the reading.
+    pub fn update(
+        &mut self,
+        down: bool,
+        timing: &ButtonTiming,
+        now: Instant,
+    ) -> Option<ButtonAction> {
+        if down != self.raw {
+            self.raw = down;
+            self.since = Some(now);
+        }
+        let settled = self
+            .since
+            .is_some_and(|since| now.duration_since(since) >= timing.debounce);
+
+        match self.pressed_at {
+            None if self.raw && settled => {
+                self.pressed_at = Some(now);
+                self.long_sent = false;
+                self.interval = timing.repeat_interval;
+                self.next_repeat = timing.repeat_delay.map(|delay| now + delay);
+                Some(ButtonAction::Press)
+            }
+            Some(_) if !self.raw && settled => {
+                self.pressed_at = None;
+                Some(ButtonAction::Release {
+                    long: self.long_sent,
+                })
+            }
+            Some(start) => {
+                let long = timing.long_press.filter(|_| !self.long_sent);
+                if long.is_some_and(|hold| now.duration_since(start) >= hold) {
+                    self.long_sent = true;
+                    return Some(ButtonAction::LongPress);
+                }
+                self.next_repeat.filter(|&next| now >= next)?;
+                self.next_repeat = Some(now + self.interval);
+                let faster = self.interval * timing.acceleration as u32 / 100;
+                self.interval = faster.max(timing.min_interval);
+                Some(ButtonAction::Repeat)
+            }
+            None => None,
+        }
+    }
+}
+
+impl Default for ButtonEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
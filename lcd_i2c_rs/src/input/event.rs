@@ -0,0 +1,38 @@
+/// An event from an input adapter, for driving menus and other screens.
+///
+/// Every adapter produces these, so a screen works the same whichever buttons, pads or remote
+/// the device has. Hand them to [`Compositor::dispatch`](crate::Compositor::dispatch), or
+/// straight to a widget's [`handle`](crate::Widget::handle).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputEvent {
+    /// Move up, or to the previous item.
+    Up,
+    /// Move down, or to the next item.
+    Down,
+    /// Move left.
+    Left,
+    /// Move right.
+    Right,
+    /// Choose the current item.
+    Select,
+    /// Leave the current screen.
+    Back,
+    /// A character key, e.g. from a keypad.
+    Char(char),
+    /// A rotary encoder turned by a number of detents, positive clockwise.
+    Encoder(i8),
+}
+
+/// An input adapter that can be polled for events.
+///
+/// Implemented by the adapters that are read from the main loop, so they can be handed to
+/// [`Compositor::poll_input`](crate::Compositor::poll_input) interchangeably.
+pub trait InputSource {
+    /// Returns the next event, if any.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Option<InputEvent>)` - The event, if there is one.
+    /// * `Err(anyhow::Error)` - If the input cannot be read.
+    fn next_event(&mut self) -> anyhow::Result<Option<InputEvent>>;
+}
@@ -0,0 +1,209 @@
+use super::InputEvent;
+use std::time::{Duration, Instant};
+
+/// The NEC leader mark, in microseconds.
+const LEADER_MARK_US: u32 = 9000;
+/// The space after the leader of a code frame.
+const LEADER_SPACE_US: u32 = 4500;
+/// The space after the leader of a repeat frame.
+const REPEAT_SPACE_US: u32 = 2250;
+/// The mark before every bit.
+const BIT_MARK_US: u32 = 562;
+/// The space after the mark of a 0 bit.
+const ZERO_SPACE_US: u32 = 562;
+/// The space after the mark of a 1 bit.
+const ONE_SPACE_US: u32 = 1687;
+/// Repeat frames come every 108 ms while a key is held; a longer gap means it was let go.
+const REPEAT_GAP: Duration = Duration::from_millis(150);
+
+/// Returns `true` if `us` is within 25% of `nominal`, enough for cheap receivers and remotes.
+fn near(us: u32, nominal: u32) -> bool {
+    us.abs_diff(nominal) <= nominal / 4
+}
+
+/// A code sent by an NEC remote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NecCode {
+    /// The device address, 8 bits for plain NEC and 16 bits for extended NEC.
+    pub address: u16,
+    /// The key's command.
+    pub command: u8,
+}
+
+/// A decoded NEC frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NecFrame {
+    /// A key was pressed.
+    Code(NecCode),
+    /// The last key is still held.
+    Repeat,
+}
+
+impl NecFrame {
+    /// Decodes a frame from the receiver's pulses.
+    ///
+    /// # Arguments
+    ///
+    /// * `pulses` - The frame as `(mark, microseconds)` pairs, `mark` being `true` while the IR
+    ///   carrier is received. Leading spaces are skipped, and anything after the frame ignored.
+    ///
+    /// # Returns
+    ///
+    /// The frame, or `None` if the pulses are not a valid NEC frame.
+    pub fn decode(pulses: &[(bool, u32)]) -> Option<Self> {
+        let start = pulses.iter().position(|&(mark, _)| mark)?;
+        let mut pulses = pulses[start..].iter().copied();
+        let (_, leader) = pulses.next()?;
+        let (_, gap) = pulses.next()?;
+        if !near(leader, LEADER_MARK_US) {
+            return None;
+        }
+        if near(gap, REPEAT_SPACE_US) {
+            return Some(NecFrame::Repeat);
+        }
+        if !near(gap, LEADER_SPACE_US) {
+            return None;
+        }
+
+        let mut bits = 0u32;
+        for bit in 0..32 {
+            let (mark, mark_us) = pulses.next()?;
+            let (space, space_us) = pulses.next()?;
+            if !mark || space || !near(mark_us, BIT_MARK_US) {
+                return None;
+            }
+            if near(space_us, ONE_SPACE_US) {
+                bits |= 1 << bit;
+            } else if !near(space_us, ZERO_SPACE_US) {
+                return None;
+            }
+        }
+
+        let [address, address_inv, command, command_inv] = bits.to_le_bytes();
+        if command ^ command_inv != 0xff {
+            return None;
+        }
+        let address = match address ^ address_inv {
+            0xff => address as u16,
+            _ => u16::from_le_bytes([address, address_inv]),
+        };
+        Some(NecFrame::Code(NecCode { address, command }))
+    }
+}
+
+/// Turns an NEC infrared remote into navigation events, for controlling a display from across
+/// the room.
+///
+/// Receive each frame with the RMT peripheral and pass its pulses to
+/// [`receive`](Self::receive), which decodes it and looks the command up in the key map. While
+/// a key is held the remote sends repeat frames, which repeat the last event, so holding Down
+/// keeps scrolling.
+///
+/// # Example
+///
+/// ```ignore
+/// const KEYS: [(u8, InputEvent); 5] = [
+///     (0x18, InputEvent::Up),
+///     (0x52, InputEvent::Down),
+///     (0x08, InputEvent::Left),
+///     (0x5a, InputEvent::Right),
+///     (0x1c, InputEvent::Select),
+/// ];
+/// let mut remote = IrRemote::new(&KEYS).with_address(0x00);
+/// // A divider of 80 makes one tick a microsecond.
+/// let config = RxRmtConfig::new().clock_divider(80).idle_threshold(12000);
+/// let mut rx = RxRmtDriver::new(channel, pin, &config, 250)?;
+/// rx.start()?;
+/// let mut buffer = [(PinState::Low, PulseTicks::zero()); 250];
+/// loop {
+///     if let ReceiveResult::Received(n) = rx.receive(&mut buffer, 10)? {
+///         // Receiver modules pull their output low while they see the carrier.
+///         let pulses: Vec<_> = buffer[..n]
+///             .iter()
+///             .map(|(level, ticks)| (*level == PinState::Low, ticks.ticks() as u32))
+///             .collect();
+///         if let Some(event) = remote.receive(&pulses) {
+///             menu.handle(event);
+///         }
+///     }
+/// }
+/// ```
+pub struct IrRemote<'k> {
+    keymap: &'k [(u8, InputEvent)],
+    address: Option<u16>,
+    repeat: bool,
+    last: Option<(InputEvent, Instant)>,
+}
+
+impl<'k> IrRemote<'k> {
+    /// Creates an adapter accepting any address and repeating held keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `keymap` - Pairs of `(command, event)`. Commands not in the map are ignored.
+    pub fn new(keymap: &'k [(u8, InputEvent)]) -> Self {
+        Self {
+            keymap,
+            address: None,
+            repeat: true,
+            last: None,
+        }
+    }
+
+    /// Only accepts codes for one address, so other remotes in the room are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The remote's address.
+    pub fn with_address(mut self, address: u16) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Sets whether holding a key repeats its event.
+    ///
+    /// # Arguments
+    ///
+    /// * `repeat` - `false` sends one event per press.
+    pub fn with_repeat(mut self, repeat: bool) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Decodes a received frame into an event.
+    ///
+    /// # Arguments
+    ///
+    /// * `pulses` - The frame as `(mark, microseconds)` pairs, see [`NecFrame::decode`].
+    ///
+    /// # Returns
+    ///
+    /// The event, or `None` if the frame is not valid, is for another address, is a command
+    /// not in the key map, or repeats a key when repeating is off.
+    pub fn receive(&mut self, pulses: &[(bool, u32)]) -> Option<InputEvent> {
+        let now = Instant::now();
+        match NecFrame::decode(pulses)? {
+            NecFrame::Repeat => {
+                let (event, at) = self.last?;
+                if now.duration_since(at) > REPEAT_GAP {
+                    self.last = None;
+                    return None;
+                }
+                self.last = Some((event, now));
+                self.repeat.then_some(event)
+            }
+            NecFrame::Code(code) => {
+                if self.address.is_some_and(|address| address != code.address) {
+                    return None;
+                }
+                let event = self
+                    .keymap
+                    .iter()
+                    .find(|(command, _)| *command == code.command)
+                    .map(|(_, event)| *event);
+                self.last = event.map(|event| (event, now));
+                event
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use super::{ButtonAction, ButtonEngine, ButtonTiming, InputEvent, InputSource};
+use esp_idf_hal::delay::Ets;
+use std::time::{Duration, Instant};
+
+/// How long a selected row is given to settle before its columns are read.
+const SETTLE_US: u32 = 5;
+
+/// The row and column lines of a key matrix.
+///
+/// Rows are driven and columns read: with the usual wiring each row is an open-drain output
+/// and each column an input with a pull-up, so a pressed key pulls its column low while its
+/// row is selected.
+///
+/// # Example
+///
+/// ```ignore
+/// struct Pins<'d> {
+///     rows: [PinDriver<'d, AnyIOPin, InputOutput>; 4],
+///     cols: [PinDriver<'d, AnyIOPin, Input>; 4],
+/// }
+///
+/// impl MatrixPins for Pins<'_> {
+///     fn select_row(&mut self, row: usize) -> anyhow::Result<()> {
+///         for (i, pin) in self.rows.iter_mut().enumerate() {
+///             pin.set_level((i != row).into())?;
+///         }
+///         Ok(())
+///     }
+///
+///     fn is_col_active(&mut self, col: usize) -> anyhow::Result<bool> {
+///         Ok(self.cols[col].is_low())
+///     }
+/// }
+/// ```
+pub trait MatrixPins {
+    /// Selects a row, deselecting all the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row to select (0-indexed).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the row is selected.
+    /// * `Err(anyhow::Error)` - If a pin cannot be set.
+    fn select_row(&mut self, row: usize) -> anyhow::Result<()>;
+
+    /// Returns `true` if the key in the selected row and the given column is pressed.
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - The column to read (0-indexed).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - Whether the key is pressed.
+    /// * `Err(anyhow::Error)` - If the pin cannot be read.
+    fn is_col_active(&mut self, col: usize) -> anyhow::Result<bool>;
+}
+
+/// A change of a key, named by its character in the key map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyEvent {
+    /// The key went down.
+    Pressed(char),
+    /// The key came back up.
+    Released(char),
+    /// The key was held for the long-press time, if one is set.
+    LongPressed(char),
+    /// The key is still held and repeats, if repeating is set.
+    Repeated(char),
+}
+
+impl KeyEvent {
+    /// Returns the key that changed.
+    pub const fn key(&self) -> char {
+        match *self {
+            KeyEvent::Pressed(key)
+            | KeyEvent::Released(key)
+            | KeyEvent::LongPressed(key)
+            | KeyEvent::Repeated(key) => key,
+        }
+    }
+}
+
+/// Sends each key press and repeat as [`InputEvent::Char`]; other changes are skipped.
+impl<P: MatrixPins, const ROWS: usize, const COLS: usize> InputSource
+    for MatrixKeypad<P, ROWS, COLS>
+{
+    fn next_event(&mut self) -> anyhow::Result<Option<InputEvent>> {
+        while let Some(event) = self.poll()? {
+            if let KeyEvent::Pressed(key) | KeyEvent::Repeated(key) = event {
+                return Ok(Some(InputEvent::Char(key)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Scans a matrix keypad, such as the common 4x4 membrane keypad, and reports debounced key
+/// presses and releases.
+///
+/// Each call to [`poll`](Self::poll) scans every key. A key counts as changed once it has read
+/// the same for the debounce time, so call it from the main loop at least every few
+/// milliseconds. Long presses and repeats while held can be turned on with
+/// [`with_timing`](Self::with_timing).
+///
+/// # Example
+///
+/// ```ignore
+/// const KEYS: [[char; 4]; 4] = [
+///     ['1', '2', '3', 'A'],
+///     ['4', '5', '6', 'B'],
+///     ['7', '8', '9', 'C'],
+///     ['*', '0', '#', 'D'],
+/// ];
+/// let mut keypad = MatrixKeypad::new(pins, KEYS);
+/// loop {
+///     if let Some(KeyEvent::Pressed(key)) = keypad.poll()? {
+///         lcd.print(key)?;
+///     }
+/// }
+/// ```
+pub struct MatrixKeypad<P, const ROWS: usize, const COLS: usize> {
+    pins: P,
+    keymap: [[char; COLS]; ROWS],
+    timing: ButtonTiming,
+    keys: [[ButtonEngine; COLS]; ROWS],
+}
+
+impl<P: MatrixPins, const ROWS: usize, const COLS: usize> MatrixKeypad<P, ROWS, COLS> {
+    /// Creates a keypad with a 20 ms debounce time, without long presses or repeats.
+    ///
+    /// # Arguments
+    ///
+    /// * `pins` - The row and column lines.
+    /// * `keymap` - The character each key stands for, by row and column.
+    pub fn new(pins: P, keymap: [[char; COLS]; ROWS]) -> Self {
+        Self {
+            pins,
+            keymap,
+            timing: ButtonTiming::new(),
+            keys: [[ButtonEngine::new(); COLS]; ROWS],
+        }
+    }
+
+    /// Sets how long a key must read the same before a change counts.
+    ///
+    /// # Arguments
+    ///
+    /// * `debounce` - The debounce time; zero reports every change at once.
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.timing.debounce = debounce;
+        self
+    }
+
+    /// Sets the debounce, long-press and repeat times.
+    ///
+    /// # Arguments
+    ///
+    /// * `timing` - The times, shared by all keys.
+    pub fn with_timing(mut self, timing: ButtonTiming) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    /// Scans the keys and returns the next debounced change, if any.
+    ///
+    /// When several keys change at once one is returned per call, so poll until `None` to
+    /// catch them all.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Option<KeyEvent>)` - The change, if any key changed.
+    /// * `Err(anyhow::Error)` - If a pin cannot be set or read.
+    pub fn poll(&mut self) -> anyhow::Result<Option<KeyEvent>> {
+        let now = Instant::now();
+        for row in 0..ROWS {
+            self.pins.select_row(row)?;
+            Ets::delay_us(SETTLE_US);
+            for col in 0..COLS {
+                let down = self.pins.is_col_active(col)?;
+                let Some(action) = self.keys[row][col].update(down, &self.timing, now) else {
+                    continue;
+                };
+                // The remaining keys are scanned again on the next poll.
+                let key = self.keymap[row][col];
+                return Ok(Some(match action {
+                    ButtonAction::Press => KeyEvent::Pressed(key),
+                    ButtonAction::LongPress => KeyEvent::LongPressed(key),
+                    ButtonAction::Repeat => KeyEvent::Repeated(key),
+                    ButtonAction::Release { .. } => KeyEvent::Released(key),
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns `true` if a key is held down, after debouncing.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key's character in the key map.
+    pub fn is_pressed(&self, key: char) -> bool {
+        self.keymap
+            .iter()
+            .flatten()
+            .zip(self.keys.iter().flatten())
+            .any(|(&ch, engine)| ch == key && engine.is_pressed())
+    }
+
+    /// Releases the pins.
+    pub fn into_inner(self) -> P {
+        self.pins
+    }
+}
@@ -0,0 +1,13 @@
+//! Input adapters that turn keys and buttons into events for driving screens.
+
+mod button;
+mod event;
+mod ir;
+mod keypad;
+mod touch;
+
+pub use button::{ButtonAction, ButtonEngine, ButtonTiming};
+pub use event::{InputEvent, InputSource};
+pub use ir::{IrRemote, NecCode, NecFrame};
+pub use keypad::{KeyEvent, MatrixKeypad, MatrixPins};
+pub use touch::{TouchInput, TouchKey};
@@ -0,0 +1,214 @@
+use super::{ButtonAction, ButtonEngine, ButtonTiming, InputEvent, InputSource};
+use std::time::{Duration, Instant};
+
+/// A touch pad and the events it sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TouchKey {
+    /// The touch channel the pad is wired to.
+    pub channel: u8,
+    /// The event sent by a tap.
+    pub event: InputEvent,
+    /// The event sent once the pad is held for the long-press time, if any.
+    pub long_press: Option<InputEvent>,
+}
+
+impl TouchKey {
+    /// Creates a pad that sends `event` as soon as it is touched.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The touch channel the pad is wired to.
+    /// * `event` - The event to send.
+    pub const fn new(channel: u8, event: InputEvent) -> Self {
+        Self {
+            channel,
+            event,
+            long_press: None,
+        }
+    }
+
+    /// Sends `event` when the pad is held instead.
+    ///
+    /// A tap then sends its event when the pad is let go, since until then it could still
+    /// become a long press, and holding the pad does not repeat.
+    pub const fn with_long_press(mut self, event: InputEvent) -> Self {
+        self.long_press = Some(event);
+        self
+    }
+}
+
+/// Turns ESP32 capacitive touch pads into navigation events, for designs without mechanical
+/// buttons.
+///
+/// Each pad is calibrated untouched by [`calibrate`](Self::calibrate), and counts as touched
+/// while its reading differs from that baseline by more than the threshold. This works both on
+/// the ESP32, whose readings drop when touched, and on the S2 and S3, whose readings rise.
+/// Changes are debounced. Holding a pad repeats its event, faster the longer it is held, so
+/// holding Down scrolls through a long menu quickly; a pad can instead send a second event
+/// when held.
+///
+/// # Example
+///
+/// ```ignore
+/// const PADS: [TouchKey; 3] = [
+///     TouchKey::new(3, InputEvent::Up),
+///     TouchKey::new(4, InputEvent::Down),
+///     TouchKey::new(5, InputEvent::Select).with_long_press(InputEvent::Back),
+/// ];
+/// let read = |channel| {
+///     let mut value = 0;
+///     esp!(unsafe { touch_pad_read_filtered(channel as touch_pad_t, &mut value) })?;
+///     Ok(value as u32)
+/// };
+/// let mut touch = TouchInput::new(read, PADS);
+/// touch.calibrate()?;
+/// loop {
+///     if let Some(event) = touch.poll()? {
+///         menu.handle(event);
+///     }
+/// }
+/// ```
+pub struct TouchInput<F, const N: usize> {
+    read: F,
+    keys: [TouchKey; N],
+    baselines: [u32; N],
+    pads: [ButtonEngine; N],
+    threshold: f32,
+    timing: ButtonTiming,
+}
+
+impl<F, const N: usize> TouchInput<F, N>
+where
+    F: FnMut(u8) -> anyhow::Result<u32>,
+{
+    /// Creates an adapter with a 10% threshold, 30 ms debounce and 600 ms long press, repeating
+    /// held pads after 500 ms, from every 200 ms down to every 50 ms.
+    ///
+    /// # Arguments
+    ///
+    /// * `read` - Returns the raw reading of a touch channel.
+    /// * `keys` - The pads and their events.
+    pub fn new(read: F, keys: [TouchKey; N]) -> Self {
+        Self {
+            read,
+            keys,
+            baselines: [0; N],
+            pads: [ButtonEngine::new(); N],
+            threshold: 0.1,
+            timing: ButtonTiming::new()
+                .with_debounce(Duration::from_millis(30))
+                .with_long_press(Duration::from_millis(600))
+                .with_repeat(Duration::from_millis(500), Duration::from_millis(200))
+                .with_acceleration(80, Duration::from_millis(50)),
+        }
+    }
+
+    /// Sets how far a reading has to move from the baseline to count as a touch.
+    ///
+    /// # Arguments
+    ///
+    /// * `fraction` - The change relative to the baseline, e.g. 0.1 for 10%.
+    pub fn with_threshold(mut self, fraction: f32) -> Self {
+        self.threshold = fraction.max(0.0);
+        self
+    }
+
+    /// Sets how long a pad must read the same before a change counts.
+    ///
+    /// # Arguments
+    ///
+    /// * `debounce` - The debounce time.
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.timing.debounce = debounce;
+        self
+    }
+
+    /// Sets how long a pad must be held for its long-press event.
+    ///
+    /// # Arguments
+    ///
+    /// * `long_press` - The hold time.
+    pub fn with_long_press(mut self, long_press: Duration) -> Self {
+        self.timing.long_press = Some(long_press);
+        self
+    }
+
+    /// Sets the debounce, long-press and repeat times.
+    ///
+    /// A timing without a long press never sends the long-press events, and one without
+    /// repeats sends each event once per touch.
+    ///
+    /// # Arguments
+    ///
+    /// * `timing` - The times, shared by all pads.
+    pub fn with_timing(mut self, timing: ButtonTiming) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    /// Records the untouched reading of every pad.
+    ///
+    /// Call this at start-up, with no finger on the pads.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every pad is read.
+    /// * `Err(anyhow::Error)` - If a channel cannot be read.
+    pub fn calibrate(&mut self) -> anyhow::Result<()> {
+        for (i, key) in self.keys.iter().enumerate() {
+            self.baselines[i] = (self.read)(key.channel)?;
+            self.pads[i] = ButtonEngine::new();
+        }
+        Ok(())
+    }
+
+    /// Reads the pads and returns the next event, if any.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Option<InputEvent>)` - The event, if a pad was tapped or held.
+    /// * `Err(anyhow::Error)` - If a channel cannot be read.
+    pub fn poll(&mut self) -> anyhow::Result<Option<InputEvent>> {
+        let now = Instant::now();
+        for (i, key) in self.keys.iter().enumerate() {
+            let reading = (self.read)(key.channel)?;
+            let baseline = self.baselines[i] as f32;
+            let touched = (reading as f32 - baseline).abs() > baseline * self.threshold;
+            let Some(action) = self.pads[i].update(touched, &self.timing, now) else {
+                continue;
+            };
+            let found = match (action, key.long_press) {
+                (ButtonAction::Press | ButtonAction::Repeat, None) => Some(key.event),
+                (ButtonAction::Release { long: false }, Some(_)) => Some(key.event),
+                (ButtonAction::LongPress, Some(long)) => Some(long),
+                _ => None,
+            };
+            if found.is_some() {
+                // The other pads are read again on the next poll.
+                return Ok(found);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns `true` while a pad's channel reads as touched, after debouncing.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The touch channel.
+    pub fn is_touched(&self, channel: u8) -> bool {
+        self.keys
+            .iter()
+            .zip(&self.pads)
+            .any(|(key, pad)| key.channel == channel && pad.is_pressed())
+    }
+}
+
+impl<F, const N: usize> InputSource for TouchInput<F, N>
+where
+    F: FnMut(u8) -> anyhow::Result<u32>,
+{
+    fn next_event(&mut self) -> anyhow::Result<Option<InputEvent>> {
+        self.poll()
+    }
+}
@@ -0,0 +1,405 @@
+//! Japanese text on modules with the A00 (Japanese) character ROM.
+//!
+//! The A00 ROM has the half-width katakana of JIS X 0201 at codes 0xa1-0xdf. Voiced letters
+//! such as ガ have no code of their own and are printed as the base letter followed by the
+//! voicing mark (ｶﾞ), so one character can take two display cells.
+
+#[cfg(feature = "driver")]
+use crate::Lcd;
+
+/// The A00 code of the prolonged sound mark ー.
+const LONG_VOWEL: u8 = 0xb0;
+/// The A00 code of the small tsu ッ that doubles the next consonant.
+const SMALL_TSU: u8 = 0xaf;
+/// The A00 code of ン.
+const N: u8 = 0xdd;
+
+/// A00 codes of U+30A1 (ァ) to U+30FA (ヺ): the half-width base letter and the voicing mark
+/// printed after it, or 0 for none.
+const KATAKANA: [(u8, u8); 90] = [
+    (0xa7, 0x00), // ァ
+    (0xb1, 0x00), // ア
+    (0xa8, 0x00), // ィ
+    (0xb2, 0x00), // イ
+    (0xa9, 0x00), // ゥ
+    (0xb3, 0x00), // ウ
+    (0xaa, 0x00), // ェ
+    (0xb4, 0x00), // エ
+    (0xab, 0x00), // ォ
+    (0xb5, 0x00), // オ
+    (0xb6, 0x00), // カ
+    (0xb6, 0xde), // ガ
+    (0xb7, 0x00), // キ
+    (0xb7, 0xde), // ギ
+    (0xb8, 0x00), // ク
+    (0xb8, 0xde), // グ
+    (0xb9, 0x00), // ケ
+    (0xb9, 0xde), // ゲ
+    (0xba, 0x00), // コ
+    (0xba, 0xde), // ゴ
+    (0xbb, 0x00), // サ
+    (0xbb, 0xde), // ザ
+    (0xbc, 0x00), // シ
+    (0xbc, 0xde), // ジ
+    (0xbd, 0x00), // ス
+    (0xbd, 0xde), // ズ
+    (0xbe, 0x00), // セ
+    (0xbe, 0xde), // ゼ
+    (0xbf, 0x00), // ソ
+    (0xbf, 0xde), // ゾ
+    (0xc0, 0x00), // タ
+    (0xc0, 0xde), // ダ
+    (0xc1, 0x00), // チ
+    (0xc1, 0xde), // ヂ
+    (0xaf, 0x00), // ッ
+    (0xc2, 0x00), // ツ
+    (0xc2, 0xde), // ヅ
+    (0xc3, 0x00), // テ
+    (0xc3, 0xde), // デ
+    (0xc4, 0x00), // ト
+    (0xc4, 0xde), // ド
+    (0xc5, 0x00), // ナ
+    (0xc6, 0x00), // ニ
+    (0xc7, 0x00), // ヌ
+    (0xc8, 0x00), // ネ
+    (0xc9, 0x00), // ノ
+    (0xca, 0x00), // ハ
+    (0xca, 0xde), // バ
+    (0xca, 0xdf), // パ
+    (0xcb, 0x00), // ヒ
+    (0xcb, 0xde), // ビ
+    (0xcb, 0xdf), // ピ
+    (0xcc, 0x00), // フ
+    (0xcc, 0xde), // ブ
+    (0xcc, 0xdf), // プ
+    (0xcd, 0x00), // ヘ
+    (0xcd, 0xde), // ベ
+    (0xcd, 0xdf), // ペ
+    (0xce, 0x00), // ホ
+    (0xce, 0xde), // ボ
+    (0xce, 0xdf), // ポ
+    (0xcf, 0x00), // マ
+    (0xd0, 0x00), // ミ
+    (0xd1, 0x00), // ム
+    (0xd2, 0x00), // メ
+    (0xd3, 0x00), // モ
+    (0xac, 0x00), // ャ
+    (0xd4, 0x00), // ヤ
+    (0xad, 0x00), // ュ
+    (0xd5, 0x00), // ユ
+    (0xae, 0x00), // ョ
+    (0xd6, 0x00), // ヨ
+    (0xd7, 0x00), // ラ
+    (0xd8, 0x00), // リ
+    (0xd9, 0x00), // ル
+    (0xda, 0x00), // レ
+    (0xdb, 0x00), // ロ
+    (0xdc, 0x00), // ヮ
+    (0xdc, 0x00), // ワ
+    (0xb2, 0x00), // ヰ
+    (0xb4, 0x00), // ヱ
+    (0xa6, 0x00), // ヲ
+    (0xdd, 0x00), // ン
+    (0xb3, 0xde), // ヴ
+    (0xb6, 0x00), // ヵ
+    (0xb9, 0x00), // ヶ
+    (0xdc, 0xde), // ヷ
+    (0xb2, 0xde), // ヸ
+    (0xb4, 0xde), // ヹ
+    (0xa6, 0xde), // ヺ
+];
+
+/// Hepburn (and common Kunrei) syllables and their A00 codes, longest first.
+const ROMAJI: &[(&str, &[u8])] = &[
+    ("bya", &[0xcb, 0xde, 0xac]),
+    ("byo", &[0xcb, 0xde, 0xae]),
+    ("byu", &[0xcb, 0xde, 0xad]),
+    ("cha", &[0xc1, 0xac]),
+    ("che", &[0xc1, 0xaa]),
+    ("chi", &[0xc1]),
+    ("cho", &[0xc1, 0xae]),
+    ("chu", &[0xc1, 0xad]),
+    ("gya", &[0xb7, 0xde, 0xac]),
+    ("gyo", &[0xb7, 0xde, 0xae]),
+    ("gyu", &[0xb7, 0xde, 0xad]),
+    ("hya", &[0xcb, 0xac]),
+    ("hyo", &[0xcb, 0xae]),
+    ("hyu", &[0xcb, 0xad]),
+    ("jya", &[0xbc, 0xde, 0xac]),
+    ("jyo", &[0xbc, 0xde, 0xae]),
+    ("jyu", &[0xbc, 0xde, 0xad]),
+    ("kya", &[0xb7, 0xac]),
+    ("kyo", &[0xb7, 0xae]),
+    ("kyu", &[0xb7, 0xad]),
+    ("mya", &[0xd0, 0xac]),
+    ("myo", &[0xd0, 0xae]),
+    ("myu", &[0xd0, 0xad]),
+    ("nya", &[0xc6, 0xac]),
+    ("nyo", &[0xc6, 0xae]),
+    ("nyu", &[0xc6, 0xad]),
+    ("pya", &[0xcb, 0xdf, 0xac]),
+    ("pyo", &[0xcb, 0xdf, 0xae]),
+    ("pyu", &[0xcb, 0xdf, 0xad]),
+    ("rya", &[0xd8, 0xac]),
+    ("ryo", &[0xd8, 0xae]),
+    ("ryu", &[0xd8, 0xad]),
+    ("sha", &[0xbc, 0xac]),
+    ("she", &[0xbc, 0xaa]),
+    ("shi", &[0xbc]),
+    ("sho", &[0xbc, 0xae]),
+    ("shu", &[0xbc, 0xad]),
+    ("sya", &[0xbc, 0xac]),
+    ("syo", &[0xbc, 0xae]),
+    ("syu", &[0xbc, 0xad]),
+    ("tsu", &[0xc2]),
+    ("tya", &[0xc1, 0xac]),
+    ("tyo", &[0xc1, 0xae]),
+    ("tyu", &[0xc1, 0xad]),
+    ("ba", &[0xca, 0xde]),
+    ("be", &[0xcd, 0xde]),
+    ("bi", &[0xcb, 0xde]),
+    ("bo", &[0xce, 0xde]),
+    ("bu", &[0xcc, 0xde]),
+    ("da", &[0xc0, 0xde]),
+    ("de", &[0xc3, 0xde]),
+    ("di", &[0xc1, 0xde]),
+    ("do", &[0xc4, 0xde]),
+    ("du", &[0xc2, 0xde]),
+    ("fa", &[0xcc, 0xa7]),
+    ("fe", &[0xcc, 0xaa]),
+    ("fi", &[0xcc, 0xa8]),
+    ("fo", &[0xcc, 0xab]),
+    ("fu", &[0xcc]),
+    ("ga", &[0xb6, 0xde]),
+    ("ge", &[0xb9, 0xde]),
+    ("gi", &[0xb7, 0xde]),
+    ("go", &[0xba, 0xde]),
+    ("gu", &[0xb8, 0xde]),
+    ("ha", &[0xca]),
+    ("he", &[0xcd]),
+    ("hi", &[0xcb]),
+    ("ho", &[0xce]),
+    ("hu", &[0xcc]),
+    ("ja", &[0xbc, 0xde, 0xac]),
+    ("je", &[0xbc, 0xde, 0xaa]),
+    ("ji", &[0xbc, 0xde]),
+    ("jo", &[0xbc, 0xde, 0xae]),
+    ("ju", &[0xbc, 0xde, 0xad]),
+    ("ka", &[0xb6]),
+    ("ke", &[0xb9]),
+    ("ki", &[0xb7]),
+    ("ko", &[0xba]),
+    ("ku", &[0xb8]),
+    ("ma", &[0xcf]),
+    ("me", &[0xd2]),
+    ("mi", &[0xd0]),
+    ("mo", &[0xd3]),
+    ("mu", &[0xd1]),
+    ("na", &[0xc5]),
+    ("ne", &[0xc8]),
+    ("ni", &[0xc6]),
+    ("no", &[0xc9]),
+    ("nu", &[0xc7]),
+    ("pa", &[0xca, 0xdf]),
+    ("pe", &[0xcd, 0xdf]),
+    ("pi", &[0xcb, 0xdf]),
+    ("po", &[0xce, 0xdf]),
+    ("pu", &[0xcc, 0xdf]),
+    ("ra", &[0xd7]),
+    ("re", &[0xda]),
+    ("ri", &[0xd8]),
+    ("ro", &[0xdb]),
+    ("ru", &[0xd9]),
+    ("sa", &[0xbb]),
+    ("se", &[0xbe]),
+    ("si", &[0xbc]),
+    ("so", &[0xbf]),
+    ("su", &[0xbd]),
+    ("ta", &[0xc0]),
+    ("te", &[0xc3]),
+    ("ti", &[0xc1]),
+    ("to", &[0xc4]),
+    ("tu", &[0xc2]),
+    ("va", &[0xb3, 0xde, 0xa7]),
+    ("ve", &[0xb3, 0xde, 0xaa]),
+    ("vi", &[0xb3, 0xde, 0xa8]),
+    ("vo", &[0xb3, 0xde, 0xab]),
+    ("vu", &[0xb3, 0xde]),
+    ("wa", &[0xdc]),
+    ("we", &[0xb3, 0xaa]),
+    ("wi", &[0xb3, 0xa8]),
+    ("wo", &[0xa6]),
+    ("ya", &[0xd4]),
+    ("yo", &[0xd6]),
+    ("yu", &[0xd5]),
+    ("za", &[0xbb, 0xde]),
+    ("ze", &[0xbe, 0xde]),
+    ("zi", &[0xbc, 0xde]),
+    ("zo", &[0xbf, 0xde]),
+    ("zu", &[0xbd, 0xde]),
+    ("a", &[0xb1]),
+    ("e", &[0xb4]),
+    ("i", &[0xb2]),
+    ("o", &[0xb5]),
+    ("u", &[0xb3]),
+];
+
+/// Returns the A00 codes for a katakana or hiragana character.
+///
+/// Full-width and half-width katakana, hiragana (shown as katakana) and Japanese punctuation
+/// are supported.
+///
+/// # Returns
+///
+/// The code of the letter and, for voiced letters, the code of the voicing mark printed after
+/// it. `None` if the character is not kana.
+pub fn kana_codes(ch: char) -> Option<(u8, Option<u8>)> {
+    let cp = ch as u32;
+    let code = match cp {
+        // Hiragana are shown as the katakana 0x60 code points above them.
+        0x3041..=0x3094 => return kana_codes(char::from_u32(cp + 0x60)?),
+        0x30a1..=0x30fa => {
+            let (base, mark) = KATAKANA[(cp - 0x30a1) as usize];
+            return Some((base, (mark != 0).then_some(mark)));
+        }
+        0xff61..=0xff9f => (cp - 0xff61 + 0xa1) as u8,
+        0x3002 => 0xa1,
+        0x300c => 0xa2,
+        0x300d => 0xa3,
+        0x3001 => 0xa4,
+        0x30fb => 0xa5,
+        0x30fc => LONG_VOWEL,
+        0x309b => 0xde,
+        0x309c => 0xdf,
+        _ => return None,
+    };
+    Some((code, None))
+}
+
+/// Transliterates romaji to A00 katakana codes.
+///
+/// Hepburn spelling is expected (`shi`, `chi`, `tsu`, `fu`, `ja`), with common Kunrei
+/// spellings (`si`, `ti`, `tu`) accepted too. A doubled consonant becomes a small ッ, `n` not
+/// followed by a vowel (or written `n'`) becomes ン, and `-` becomes the long vowel mark ー.
+/// Case is ignored. Other ASCII characters such as digits and spaces are passed through, and
+/// non-ASCII characters become `?`, so spell long vowels out (`toukyou`) or with `-` instead
+/// of using macrons.
+///
+/// # Example
+///
+/// ```ignore
+/// let codes: Vec<u8> = romaji("Konnichiwa").collect(); // ｺﾝﾆﾁﾜ
+/// ```
+pub fn romaji(text: &str) -> Romaji<'_> {
+    Romaji {
+        rest: text,
+        pending: &[],
+    }
+}
+
+/// An iterator over the A00 codes of romaji text, created by [`romaji`].
+#[derive(Debug, Clone)]
+pub struct Romaji<'t> {
+    rest: &'t str,
+    pending: &'static [u8],
+}
+
+impl Iterator for Romaji<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if let Some((&code, rest)) = self.pending.split_first() {
+            self.pending = rest;
+            return Some(code);
+        }
+
+        let ch = self.rest.chars().next()?;
+        if !ch.is_ascii() {
+            self.rest = &self.rest[ch.len_utf8()..];
+            return Some(b'?');
+        }
+
+        let bytes = self.rest.as_bytes();
+        let c = ch.to_ascii_lowercase();
+        let next = bytes.get(1).map(u8::to_ascii_lowercase);
+
+        if c == '-' {
+            self.rest = &self.rest[1..];
+            return Some(LONG_VOWEL);
+        }
+        let doubled = next == Some(c as u8) || (c == 't' && next == Some(b'c'));
+        if doubled && c.is_ascii_alphabetic() && !"aeioun".contains(c) {
+            self.rest = &self.rest[1..];
+            return Some(SMALL_TSU);
+        }
+
+        for &(syllable, codes) in ROMAJI {
+            let len = syllable.len();
+            if bytes.len() >= len && bytes[..len].eq_ignore_ascii_case(syllable.as_bytes()) {
+                self.rest = &self.rest[len..];
+                let (&first, rest) = codes.split_first()?;
+                self.pending = rest;
+                return Some(first);
+            }
+        }
+
+        if c == 'n' || (c == 'm' && matches!(next, Some(b'b' | b'p' | b'm'))) {
+            self.rest = &self.rest[1..];
+            if self.rest.starts_with('\'') {
+                self.rest = &self.rest[1..];
+            }
+            return Some(N);
+        }
+
+        self.rest = &self.rest[1..];
+        Some(ch as u8)
+    }
+}
+
+#[cfg(feature = "driver")]
+impl Lcd<'_> {
+    /// Prints text containing katakana or hiragana on a module with the A00 ROM.
+    ///
+    /// Kana are converted with [`kana_codes`]; all other characters are printed with
+    /// [`print`](Self::print).
+    ///
+    /// # Arguments
+    ///
+    /// * `str` - The text to print.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the text is successfully printed.
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character.
+    pub fn print_kana(&mut self, str: &str) -> anyhow::Result<()> {
+        for ch in str.chars() {
+            match kana_codes(ch) {
+                Some((code, mark)) => {
+                    self.write_byte(code)?;
+                    if let Some(mark) = mark {
+                        self.write_byte(mark)?;
+                    }
+                }
+                None => self.print(ch)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints romaji as katakana on a module with the A00 ROM, see [`romaji`].
+    ///
+    /// # Arguments
+    ///
+    /// * `str` - The romaji text to print.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the text is successfully printed.
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character.
+    pub fn print_romaji(&mut self, str: &str) -> anyhow::Result<()> {
+        for code in romaji(str) {
+            self.write_byte(code)?;
+        }
+        Ok(())
+    }
+}
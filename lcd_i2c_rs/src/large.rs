@@ -0,0 +1,104 @@
+use crate::extended::DoubleHeight;
+use crate::glyphs::BIG_DIGIT_GLYPHS;
+use crate::Lcd;
+
+// CGRAM codes of the glyphs in `BIG_DIGIT_GLYPHS`, plus the ROM full block.
+const LT: u8 = 0;
+const UB: u8 = 1;
+const RT: u8 = 2;
+const LL: u8 = 3;
+const LB: u8 = 4;
+const LR: u8 = 5;
+const UMB: u8 = 6;
+const FULL: u8 = 0xff;
+const SP: u8 = b' ';
+
+/// The top and bottom cells of each digit.
+const DIGITS: [([u8; 3], [u8; 3]); 10] = [
+    ([LT, UB, RT], [LL, LB, LR]),
+    ([UB, RT, SP], [LB, FULL, LB]),
+    ([UMB, UMB, RT], [LL, LB, LB]),
+    ([UMB, UMB, RT], [LB, LB, LR]),
+    ([LL, LB, FULL], [SP, SP, FULL]),
+    ([LT, UMB, UMB], [LB, LB, LR]),
+    ([LT, UMB, UMB], [LL, LB, LR]),
+    ([UB, UB, RT], [SP, SP, FULL]),
+    ([LT, UMB, RT], [LL, LB, LR]),
+    ([LT, UMB, RT], [LB, LB, LR]),
+];
+
+impl Lcd<'_> {
+    /// Prints a line of large text over two rows.
+    ///
+    /// Controllers with double height (ST7036, ST7032) merge the two lines of a two-line panel
+    /// and print the text in their double-height font, so `row` must be 0 there. On other controllers the large text is emulated: digits
+    /// are drawn three cells wide from custom characters, `-`, `.` and `:` are drawn to match,
+    /// and any other character is printed at normal size in the lower row.
+    ///
+    /// Emulation loads CGRAM slots 0 to 6, replacing any custom characters stored there, and
+    /// stops at the last digit that fits on the row.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The upper of the two rows (0-indexed).
+    /// * `text` - The text to print.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the text is successfully printed.
+    /// * `Err(anyhow::Error)` - If the two rows are out of bounds, or there is an error while printing any character or setting the cursor.
+    pub fn print_large(&mut self, row: u8, text: &str) -> anyhow::Result<()> {
+        if self.profile().extended_instructions {
+            // Double height merges the two lines of a two-line panel, so only the top row fits.
+            if row != 0 {
+                return Err(anyhow::anyhow!("Row out of bounds"));
+            }
+            self.set_double_height(DoubleHeight::Top)?;
+            self.set_cursor(0, 0)?;
+            return self.print_str(text);
+        }
+
+        if row + 1 >= self.rows() {
+            return Err(anyhow::anyhow!("Row out of bounds"));
+        }
+        for (slot, glyph) in BIG_DIGIT_GLYPHS.iter().enumerate() {
+            self.create_custom_chars(slot as u8, glyph)?;
+        }
+
+        let mut col = 0;
+        for ch in text.chars() {
+            let mut cell = [SP];
+            let (top, bottom): (&[u8], &[u8]) = match ch {
+                '0'..='9' => {
+                    let (top, bottom) = &DIGITS[ch as usize - '0' as usize];
+                    (top, bottom)
+                }
+                '-' => (&[LB, LB], &[SP, SP]),
+                '.' => (&[SP], b"."),
+                ':' => (b".", b"."),
+                ' ' => (&[SP], &[SP]),
+                _ => match self.charmap().encode(ch)? {
+                    Some(code) => {
+                        cell[0] = code;
+                        (&[SP], &cell)
+                    }
+                    None => continue,
+                },
+            };
+            let width = top.len() as u8;
+            if col + width > self.cols() {
+                break;
+            }
+            self.set_cursor(col, row)?;
+            for &code in top {
+                self.write_byte(code)?;
+            }
+            self.set_cursor(col, row + 1)?;
+            for &code in bottom {
+                self.write_byte(code)?;
+            }
+            col += width;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+//! Values shared between the tasks producing them and the widgets showing them.
+
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::Duration;
+
+/// How often a waiting [`Lock`] spins before it sleeps.
+const SPINS: u32 = 64;
+
+/// A value behind a lock that spins and then sleeps instead of blocking in the OS.
+///
+/// `std::sync::Mutex` allocates its pthread mutex on first use on ESP-IDF; this needs no heap.
+/// Values are only held for a copy or a few arithmetic operations, so waits are short, and
+/// sleeping lets a lower-priority FreeRTOS task holding the lock run.
+struct Lock<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: the flag gives one thread at a time access to the value, as a mutex does.
+unsafe impl<T: Send> Sync for Lock<T> {}
+
+impl<T> Lock<T> {
+    const fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Runs `f` on the value while holding the lock. `f` must not panic or take the lock again.
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut spins = 0;
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            spins += 1;
+            if spins < SPINS {
+                core::hint::spin_loop();
+            } else {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+        // SAFETY: holding the flag, this is the only reference to the value.
+        let result = f(unsafe { &mut *self.value.get() });
+        self.locked.store(false, Ordering::Release);
+        result
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Lock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.with(|value| value.fmt(f))
+    }
+}
+
+/// A value that widgets bind to, such as a sensor reading, redrawn whenever it changes.
+///
+/// A sensor task calls [`set`](Self::set) with each new value; only a value different from the
+/// current one counts as a change and bumps the [`version`](Self::version). Widgets bound to
+/// the observable, such as a [`Readout`](crate::widgets::Readout) after
+/// [`bind`](crate::widgets::Readout::bind), report through [`Widget::needs_redraw`] that their
+/// value changed, and a [`Compositor`](crate::Compositor) then redraws them on its next tick
+/// without waiting for their interval. The value sits behind a lock that needs no heap, so it
+/// can be a `static` set from another task.
+///
+/// Code outside the widgets can keep the version it last saw and ask
+/// [`changed`](Self::changed) instead of comparing values by hand.
+///
+/// # Example
+///
+/// ```ignore
+/// static OUTSIDE: Observable<f32> = Observable::new(f32::NAN);
+///
+/// std::thread::spawn(|| loop {
+///     OUTSIDE.set(ds18b20.read_celsius()?);
+///     std::thread::sleep(Duration::from_secs(2));
+/// });
+///
+/// let mut readout = Readout::new(Dimension::Temperature, &UNITS);
+/// readout.bind(&OUTSIDE);
+/// compositor.add(Rect::new(10, 0, 6, 1), &mut readout, Duration::from_secs(60))?;
+/// loop {
+///     compositor.tick()?;
+/// }
+/// ```
+///
+/// [`Widget::needs_redraw`]: crate::Widget::needs_redraw
+#[derive(Debug)]
+pub struct Observable<T> {
+    value: Lock<T>,
+    version: AtomicU32,
+}
+
+impl<T: Copy + PartialEq> Observable<T> {
+    /// Creates an observable holding `value`.
+    pub const fn new(value: T) -> Self {
+        Self {
+            value: Lock::new(value),
+            version: AtomicU32::new(0),
+        }
+    }
+
+    /// Returns the current value.
+    pub fn get(&self) -> T {
+        self.value.with(|value| *value)
+    }
+
+    /// Sets the value, marking everything bound to it for a redraw if it differs from the
+    /// current one.
+    ///
+    /// A NaN float never equals itself, so setting NaN always counts as a change.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The new value.
+    pub fn set(&self, value: T) {
+        self.value.with(|current| {
+            if *current != value {
+                *current = value;
+                self.version.fetch_add(1, Ordering::Release);
+            }
+        });
+    }
+
+    /// Returns a counter that changes each time the value does.
+    pub fn version(&self) -> u32 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if the value changed since the version in `seen`, and updates `seen`.
+    ///
+    /// # Arguments
+    ///
+    /// * `seen` - The version last seen, starting from 0 or an earlier [`version`](Self::version).
+    pub fn changed(&self, seen: &mut u32) -> bool {
+        let version = self.version();
+        core::mem::replace(seen, version) != version
+    }
+}
+
+impl<T: Copy + PartialEq + Default> Default for Observable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// What a [`Tracker`] has folded in so far.
+#[derive(Debug)]
+struct TrackerState {
+    seen: u32,
+    count: u32,
+}
+
+/// Keeps the lowest, highest and average value of an [`Observable`] reading, the statistics
+/// every weather station shows next to the live value.
+///
+/// Each statistic is itself an [`Observable`], so it can be bound to a
+/// [`Readout`](crate::widgets::Readout) as a field of its own, or shown in turn with the live
+/// value through [`with_alternate`](crate::widgets::Readout::with_alternate). The average is an
+/// exponential moving average over about `window` readings, which starts out as the plain
+/// mean of the first readings so it is not biased towards the very first. Missing readings
+/// (NaN) are skipped.
+///
+/// The tracker only sees the source when [`update`](Self::update) is called, e.g. right after
+/// the sensor task sets the reading or once per pass of the UI loop. It takes `&self`, so the
+/// tracker can be a `static` next to its source.
+///
+/// # Example
+///
+/// ```ignore
+/// static OUTSIDE: Observable<f32> = Observable::new(f32::NAN);
+/// static OUTSIDE_STATS: Tracker = Tracker::new(&OUTSIDE, 30);
+///
+/// OUTSIDE.set(ds18b20.read_celsius()?);
+/// OUTSIDE_STATS.update();
+///
+/// let mut low = Readout::new(Dimension::Temperature, &UNITS);
+/// low.bind(OUTSIDE_STATS.min());
+/// ```
+#[derive(Debug)]
+pub struct Tracker<'o> {
+    source: &'o Observable<f32>,
+    window: u32,
+    state: Lock<TrackerState>,
+    min: Observable<f32>,
+    max: Observable<f32>,
+    average: Observable<f32>,
+}
+
+impl<'o> Tracker<'o> {
+    /// Creates a tracker without readings.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The reading to track.
+    /// * `window` - The number of readings the average spans, at least 1.
+    pub const fn new(source: &'o Observable<f32>, window: u32) -> Self {
+        Self {
+            source,
+            window: if window == 0 { 1 } else { window },
+            // Unlike any early version of the source, so the first update takes the reading.
+            state: Lock::new(TrackerState {
+                seen: u32::MAX,
+                count: 0,
+            }),
+            min: Observable::new(f32::NAN),
+            max: Observable::new(f32::NAN),
+            average: Observable::new(f32::NAN),
+        }
+    }
+
+    /// Folds the source's reading into the statistics if it changed since the last update.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a new reading was taken.
+    pub fn update(&self) -> bool {
+        self.state.with(|state| {
+            if !self.source.changed(&mut state.seen) {
+                return false;
+            }
+            let value = self.source.get();
+            if value.is_nan() {
+                return false;
+            }
+            state.count = state.count.saturating_add(1);
+            // `f32::min` and `f32::max` ignore the NaN before the first reading.
+            self.min.set(self.min.get().min(value));
+            self.max.set(self.max.get().max(value));
+            let weight = (2.0 / (self.window as f32 + 1.0)).max(1.0 / state.count as f32);
+            let average = match state.count {
+                1 => value,
+                _ => self.average.get() + weight * (value - self.average.get()),
+            };
+            self.average.set(average);
+            true
+        })
+    }
+
+    /// Forgets the readings so far, starting the statistics again from the next one.
+    pub fn reset(&self) {
+        self.state.with(|state| state.count = 0);
+        self.min.set(f32::NAN);
+        self.max.set(f32::NAN);
+        self.average.set(f32::NAN);
+    }
+
+    /// Returns the lowest reading, NaN before the first.
+    pub fn min(&self) -> &Observable<f32> {
+        &self.min
+    }
+
+    /// Returns the highest reading, NaN before the first.
+    pub fn max(&self) -> &Observable<f32> {
+        &self.max
+    }
+
+    /// Returns the moving average of the readings, NaN before the first.
+    pub fn average(&self) -> &Observable<f32> {
+        &self.average
+    }
+}
@@ -0,0 +1,38 @@
+//! Hooks for tracing the low-level traffic sent to the display.
+
+/// Receives a callback for every byte and I2C transaction the driver sends.
+///
+/// All methods have empty default implementations, so an observer only needs to implement the
+/// events it cares about. The methods take `&self` so the observer can stay borrowed by the
+/// application while the driver holds it; use `Cell`/`RefCell` to record state.
+///
+/// # Example
+///
+/// ```ignore
+/// struct CountWrites(Cell<usize>);
+///
+/// impl Observer for CountWrites {
+///     fn on_i2c_write(&self, _bytes: &[u8]) {
+///         self.0.set(self.0.get() + 1);
+///     }
+/// }
+///
+/// let counter = CountWrites(Cell::new(0));
+/// lcd.set_observer(&counter);
+/// lcd.print_str("Hi")?;
+/// println!("{} writes", counter.0.get());
+/// ```
+pub trait Observer {
+    /// Called before a command byte (RS low) is sent.
+    ///
+    /// The nibbles written during the reset sequence in [`Lcd::init`](crate::Lcd::init) are not
+    /// full commands and are only reported through [`on_i2c_write`](Self::on_i2c_write).
+    fn on_command(&self, _byte: u8) {}
+
+    /// Called before a data byte (RS high) is sent.
+    fn on_data(&self, _byte: u8) {}
+
+    /// Called before every I2C write to the port expander, including in headless mode, where
+    /// the write is then skipped.
+    fn on_i2c_write(&self, _bytes: &[u8]) {}
+}
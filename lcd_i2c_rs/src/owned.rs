@@ -0,0 +1,49 @@
+// The `alloc` layer formats into a `String` instead of a fixed buffer.
+#![allow(clippy::disallowed_types, clippy::disallowed_methods)]
+
+use crate::Lcd;
+#[cfg(feature = "buffer")]
+use crate::{Col, RefreshScheduler, Row};
+use std::fmt;
+
+impl Lcd<'_> {
+    /// Prints formatted text, e.g. `lcd.print_fmt(format_args!("{:.1}°C", reading))`
+    /// (`alloc` feature).
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The text to print, from `format_args!`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the text is successfully printed.
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character, or the [`CancelToken`](crate::CancelToken) is cancelled.
+    pub fn print_fmt(&mut self, args: fmt::Arguments) -> anyhow::Result<()> {
+        self.print_str(&fmt::format(args))
+    }
+}
+
+#[cfg(feature = "buffer")]
+impl RefreshScheduler<'_> {
+    /// Prints formatted text into the frame being drawn, like [`print`](Self::print)
+    /// (`alloc` feature).
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - The column position (0-indexed).
+    /// * `row` - The row position (0-indexed).
+    /// * `args` - The text to print, from `format_args!`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the text is successfully printed.
+    /// * `Err(anyhow::Error)` - If a character cannot be shown and the fallback policy is [`Fallback::Error`](crate::charset::Fallback::Error).
+    pub fn print_fmt(
+        &mut self,
+        col: impl Into<Col>,
+        row: impl Into<Row>,
+        args: fmt::Arguments,
+    ) -> anyhow::Result<()> {
+        self.print(col, row, &fmt::format(args))
+    }
+}
@@ -0,0 +1,44 @@
+use core::fmt;
+
+/// How far a long operation got before it failed.
+///
+/// Attached as context to the errors of operations that send many items, such as
+/// [`Lcd::print_long_str`](crate::Lcd::print_long_str), so a caller can tell how much made it
+/// to the display and resume from there rather than start over, e.g. after the expander
+/// stopped acknowledging once the write retries ran out. Retrieve it with
+/// [`anyhow::Error::downcast_ref`].
+///
+/// # Example
+///
+/// ```ignore
+/// if let Err(e) = lcd.print_long_str(text) {
+///     if let Some(progress) = e.downcast_ref::<ProgressReport>() {
+///         println!("{} characters not shown: {:#}", progress.remaining(), e);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgressReport {
+    /// The number of items sent before the error.
+    pub written: usize,
+    /// The number of items the operation would have sent.
+    pub total: usize,
+}
+
+impl ProgressReport {
+    /// Creates a report.
+    pub const fn new(written: usize, total: usize) -> Self {
+        Self { written, total }
+    }
+
+    /// Returns the number of items not sent.
+    pub const fn remaining(&self) -> usize {
+        self.total.saturating_sub(self.written)
+    }
+}
+
+impl fmt::Display for ProgressReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Stopped after {} of {}", self.written, self.total)
+    }
+}
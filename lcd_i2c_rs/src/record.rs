@@ -0,0 +1,291 @@
+use crate::{Frame, RefreshScheduler};
+use std::io::{Read, Write};
+use std::time::Instant;
+
+/// Marks the start of a recording.
+const MAGIC: &[u8; 4] = b"LCDR";
+/// The format version written after the magic.
+const VERSION: u8 = 1;
+/// A record holding every cell of the frame.
+const FULL: u8 = 0;
+/// A record holding only the cells that changed since the previous frame.
+const DELTA: u8 = 1;
+
+/// Records a sequence of frames with timestamps to a byte stream, for playback with
+/// [`FramePlayer`].
+///
+/// The stream starts with a small header giving the frame size. Each frame is stored with its
+/// timestamp in milliseconds, either as the cells that changed since the previous frame or, if
+/// that is shorter, as the whole frame, so recordings of mostly static screens stay small.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut recorder = FrameRecorder::new(File::create("boot.lcdr")?, 16, 2)?;
+/// loop {
+///     draw(scheduler.frame_mut());
+///     scheduler.present();
+///     recorder.record(scheduler.frame())?;
+/// }
+/// ```
+pub struct FrameRecorder<W: Write> {
+    writer: W,
+    previous: Frame,
+    started: Instant,
+    first: bool,
+}
+
+impl<W: Write> FrameRecorder<W> {
+    /// Starts a recording by writing its header.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where the recording is written.
+    /// * `cols` - The number of columns of the frames, at most 40.
+    /// * `rows` - The number of rows of the frames, at most 4.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FrameRecorder)` - The recorder.
+    /// * `Err(anyhow::Error)` - If the header cannot be written.
+    pub fn new(mut writer: W, cols: u8, rows: u8) -> anyhow::Result<Self> {
+        let previous = Frame::new(cols, rows);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION, previous.cols(), previous.rows()])?;
+        Ok(Self {
+            writer,
+            previous,
+            started: Instant::now(),
+            first: true,
+        })
+    }
+
+    /// Records a frame, timestamped with the time since the recorder was created.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The frame to record.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the frame is written.
+    /// * `Err(anyhow::Error)` - If the frame cannot be written.
+    pub fn record(&mut self, frame: &Frame) -> anyhow::Result<()> {
+        let ms = self.started.elapsed().as_millis().min(u32::MAX as u128) as u32;
+        self.record_at(frame, ms)
+    }
+
+    /// Records a frame with an explicit timestamp, e.g. from a simulated clock.
+    ///
+    /// Frames that are unchanged from the previous one are skipped, except the first.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The frame to record. Cells beyond the recording's size are ignored.
+    /// * `ms` - The timestamp in milliseconds since the start of the recording.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the frame is written.
+    /// * `Err(anyhow::Error)` - If the frame cannot be written.
+    pub fn record_at(&mut self, frame: &Frame, ms: u32) -> anyhow::Result<()> {
+        let (cols, rows) = (self.previous.cols(), self.previous.rows());
+        let changed = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (col, row)))
+            .filter(|&(col, row)| frame.get(col, row) != self.previous.get(col, row))
+            .count();
+        if changed == 0 && !self.first {
+            return Ok(());
+        }
+
+        self.writer.write_all(&ms.to_le_bytes())?;
+        let full_size = cols as usize * rows as usize;
+        if self.first || changed * 3 + 2 >= full_size {
+            self.writer.write_all(&[FULL])?;
+            for row in 0..rows {
+                for col in 0..cols {
+                    let code = frame.get(col, row).unwrap_or(b' ');
+                    self.writer.write_all(&[code])?;
+                    self.previous.set(col, row, code);
+                }
+            }
+        } else {
+            self.writer.write_all(&[DELTA])?;
+            self.writer.write_all(&(changed as u16).to_le_bytes())?;
+            for row in 0..rows {
+                for col in 0..cols {
+                    let code = frame.get(col, row).unwrap_or(b' ');
+                    if self.previous.get(col, row) != Some(code) {
+                        self.writer.write_all(&[col, row, code])?;
+                        self.previous.set(col, row, code);
+                    }
+                }
+            }
+        }
+        self.first = false;
+        Ok(())
+    }
+
+    /// Flushes and returns the writer.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(W)` - The writer.
+    /// * `Err(anyhow::Error)` - If flushing fails.
+    pub fn finish(mut self) -> anyhow::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Plays back a recording made by [`FrameRecorder`].
+///
+/// Frames can be read one at a time with [`next_frame`](Self::next_frame), or played in real
+/// time into a [`RefreshScheduler`] with [`tick`](Self::tick).
+///
+/// # Example
+///
+/// ```ignore
+/// let mut player = FramePlayer::new(&RECORDING[..])?;
+/// while player.tick(&mut scheduler)? {
+///     scheduler.tick()?;
+/// }
+/// ```
+pub struct FramePlayer<R: Read> {
+    reader: R,
+    frame: Frame,
+    next_ms: Option<u32>,
+    started: Option<Instant>,
+}
+
+impl<R: Read> FramePlayer<R> {
+    /// Opens a recording by reading its header.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Where the recording is read from.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FramePlayer)` - The player.
+    /// * `Err(anyhow::Error)` - If the header cannot be read or is not a recording this version understands.
+    pub fn new(mut reader: R) -> anyhow::Result<Self> {
+        let mut header = [0u8; 7];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(anyhow::anyhow!("Not a frame recording"));
+        }
+        if header[4] != VERSION {
+            return Err(anyhow::anyhow!(
+                "Unsupported recording version {}",
+                header[4]
+            ));
+        }
+        let mut player = Self {
+            reader,
+            frame: Frame::new(header[5], header[6]),
+            next_ms: None,
+            started: None,
+        };
+        player.next_ms = player.read_timestamp()?;
+        Ok(player)
+    }
+
+    /// Returns the number of columns of the recorded frames.
+    pub fn cols(&self) -> u8 {
+        self.frame.cols()
+    }
+
+    /// Returns the number of rows of the recorded frames.
+    pub fn rows(&self) -> u8 {
+        self.frame.rows()
+    }
+
+    /// Reads the next frame.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some((u32, &Frame)))` - The timestamp in milliseconds and the frame.
+    /// * `Ok(None)` - At the end of the recording.
+    /// * `Err(anyhow::Error)` - If the recording is truncated or corrupt.
+    pub fn next_frame(&mut self) -> anyhow::Result<Option<(u32, &Frame)>> {
+        let Some(ms) = self.next_ms else {
+            return Ok(None);
+        };
+        self.read_frame()?;
+        self.next_ms = self.read_timestamp()?;
+        Ok(Some((ms, &self.frame)))
+    }
+
+    /// Presents each frame to the scheduler once its timestamp has passed, measured from the
+    /// first call.
+    ///
+    /// # Arguments
+    ///
+    /// * `scheduler` - The scheduler to play into. Its frame is overwritten.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `false` once the whole recording has been presented.
+    /// * `Err(anyhow::Error)` - If the recording is truncated or corrupt.
+    pub fn tick(&mut self, scheduler: &mut RefreshScheduler) -> anyhow::Result<bool> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let elapsed = started.elapsed().as_millis();
+        let mut presented = false;
+        while let Some(ms) = self.next_ms {
+            if ms as u128 > elapsed {
+                break;
+            }
+            self.next_frame()?;
+            presented = true;
+        }
+        if presented {
+            let target = scheduler.frame_mut();
+            for row in 0..self.frame.rows() {
+                target.write_bytes(0, row, self.frame.row(row));
+            }
+            scheduler.present();
+        }
+        Ok(self.next_ms.is_some())
+    }
+
+    fn read_frame(&mut self) -> anyhow::Result<()> {
+        let mut kind = [0u8; 1];
+        self.reader.read_exact(&mut kind)?;
+        match kind[0] {
+            FULL => {
+                for row in 0..self.frame.rows() {
+                    let mut cells = [0u8; 40];
+                    let cells = &mut cells[..self.frame.cols() as usize];
+                    self.reader.read_exact(cells)?;
+                    self.frame.write_bytes(0, row, cells);
+                }
+            }
+            DELTA => {
+                let mut count = [0u8; 2];
+                self.reader.read_exact(&mut count)?;
+                for _ in 0..u16::from_le_bytes(count) {
+                    let mut cell = [0u8; 3];
+                    self.reader.read_exact(&mut cell)?;
+                    self.frame.set(cell[0], cell[1], cell[2]);
+                }
+            }
+            kind => return Err(anyhow::anyhow!("Unknown frame record {}", kind)),
+        }
+        Ok(())
+    }
+
+    /// Reads the timestamp of the next record, or `None` at a clean end of the stream.
+    fn read_timestamp(&mut self) -> anyhow::Result<Option<u32>> {
+        let mut bytes = [0u8; 4];
+        let mut read = 0;
+        while read < bytes.len() {
+            match self.reader.read(&mut bytes[read..])? {
+                0 if read == 0 => return Ok(None),
+                0 => return Err(anyhow::anyhow!("Truncated frame recording")),
+                n => read += n,
+            }
+        }
+        Ok(Some(u32::from_le_bytes(bytes)))
+    }
+}
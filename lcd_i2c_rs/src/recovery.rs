@@ -0,0 +1,166 @@
+//! Freeing an I2C bus that a device holds low, without a power cycle.
+
+use crate::Lcd;
+use esp_idf_hal::delay::Ets;
+
+/// Half a clock period at 100 kHz.
+const HALF_PERIOD_US: u32 = 5;
+
+/// The SDA and SCL lines, taken from the I2C peripheral as open-drain GPIO for a bus recovery.
+///
+/// When the ESP32 resets in the middle of a read, the PCF8574 can be left waiting for more
+/// clock pulses with a data bit driven low. It then holds SDA low until it is clocked out or
+/// powered down, and every transaction fails. [`Lcd::recover`] clocks it free through these
+/// pins. The I2C driver keeps the pins, so an implementation usually switches them to GPIO by
+/// number and hands them back to the peripheral in [`release`](Self::release).
+///
+/// # Example
+///
+/// ```ignore
+/// use esp_idf_hal::sys::*;
+///
+/// struct Bus {
+///     sda: i32,
+///     scl: i32,
+/// }
+///
+/// impl BusPins for Bus {
+///     fn set_scl(&mut self, high: bool) -> anyhow::Result<()> {
+///         unsafe { gpio_set_direction(self.scl, gpio_mode_t_GPIO_MODE_INPUT_OUTPUT_OD) };
+///         esp!(unsafe { gpio_set_level(self.scl, high.into()) })?;
+///         Ok(())
+///     }
+///
+///     fn set_sda(&mut self, high: bool) -> anyhow::Result<()> {
+///         unsafe { gpio_set_direction(self.sda, gpio_mode_t_GPIO_MODE_INPUT_OUTPUT_OD) };
+///         esp!(unsafe { gpio_set_level(self.sda, high.into()) })?;
+///         Ok(())
+///     }
+///
+///     fn is_sda_high(&mut self) -> anyhow::Result<bool> {
+///         Ok(unsafe { gpio_get_level(self.sda) } != 0)
+///     }
+///
+///     fn release(&mut self) -> anyhow::Result<()> {
+///         esp!(unsafe { i2c_set_pin(0, self.sda, self.scl, true, true, i2c_mode_t_I2C_MODE_MASTER) })?;
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait BusPins {
+    /// Drives SCL low, or lets it float high.
+    ///
+    /// # Arguments
+    ///
+    /// * `high` - Whether to release the line (`true`) or pull it low (`false`).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the line is set.
+    /// * `Err(anyhow::Error)` - If the pin cannot be set.
+    fn set_scl(&mut self, high: bool) -> anyhow::Result<()>;
+
+    /// Drives SDA low, or lets it float high.
+    ///
+    /// # Arguments
+    ///
+    /// * `high` - Whether to release the line (`true`) or pull it low (`false`).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the line is set.
+    /// * `Err(anyhow::Error)` - If the pin cannot be set.
+    fn set_sda(&mut self, high: bool) -> anyhow::Result<()>;
+
+    /// Returns `true` if SDA reads high, i.e. no device holds it low.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - The level of the line.
+    /// * `Err(anyhow::Error)` - If the pin cannot be read.
+    fn is_sda_high(&mut self) -> anyhow::Result<bool>;
+
+    /// Hands the pins back to the I2C peripheral.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the I2C peripheral has the pins again.
+    /// * `Err(anyhow::Error)` - If the pins cannot be switched back.
+    fn release(&mut self) -> anyhow::Result<()>;
+}
+
+impl Lcd<'_> {
+    /// Frees a bus that a device holds stuck low and brings the display back.
+    ///
+    /// If SDA reads low, SCL is pulsed up to nine times, enough for any device to clock out
+    /// the byte it was sending, until SDA is let go. A STOP condition then resets every device
+    /// on the bus, the pins go back to the I2C peripheral and the display is re-initialized
+    /// with [`reinit`](Self::reinit), leaving headless mode if it was in it. Call it when a
+    /// write returns an error, such as a bus error once the retries ran out or a
+    /// [`BusContention`](crate::BusContention), or when [`probe`](Self::probe) finds no
+    /// display, before resorting to a power cycle. A bus that is not stuck is left alone.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if let Err(e) = lcd.print_str("Ready") {
+    ///     log::warn!("Display write failed: {e}");
+    ///     if lcd.recover(&mut bus)? {
+    ///         lcd.print_str("Ready")?;
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `pins` - The bus lines, see [`BusPins`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `true` if the bus was stuck and has been freed.
+    /// * `Err(anyhow::Error)` - If SDA is still held low after nine pulses, the display does not answer afterwards, or there is an error on the pins or while sending data.
+    pub fn recover(&mut self, pins: &mut dyn BusPins) -> anyhow::Result<bool> {
+        if pins.is_sda_high()? {
+            return Ok(false);
+        }
+        let freed = unstick(pins);
+        pins.release()?;
+        if !freed? {
+            return Err(anyhow::anyhow!("SDA still held low after 9 clock pulses"));
+        }
+        if !self.probe() {
+            return Err(anyhow::anyhow!(
+                "Display does not answer after bus recovery"
+            ));
+        }
+        self.reinit()?;
+        Ok(true)
+    }
+}
+
+/// Clocks SCL until SDA is let go, then sends a STOP condition.
+fn unstick(pins: &mut dyn BusPins) -> anyhow::Result<bool> {
+    pins.set_sda(true)?;
+    pins.set_scl(true)?;
+    for _ in 0..9 {
+        if pins.is_sda_high()? {
+            break;
+        }
+        pins.set_scl(false)?;
+        Ets::delay_us(HALF_PERIOD_US);
+        pins.set_scl(true)?;
+        Ets::delay_us(HALF_PERIOD_US);
+    }
+    if !pins.is_sda_high()? {
+        return Ok(false);
+    }
+    // SDA rising while SCL is high is a STOP, which every device takes as the end of a transfer.
+    pins.set_scl(false)?;
+    pins.set_sda(false)?;
+    Ets::delay_us(HALF_PERIOD_US);
+    pins.set_scl(true)?;
+    Ets::delay_us(HALF_PERIOD_US);
+    pins.set_sda(true)?;
+    Ets::delay_us(HALF_PERIOD_US);
+    Ok(true)
+}
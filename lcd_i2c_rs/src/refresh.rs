@@ -0,0 +1,425 @@
+use crate::alert::Alert;
+use crate::rotation::Rotation;
+use crate::toast::Toasts;
+use crate::{AnimClock, Canvas, Col, Frame, Lcd, Moment, ProgressReport, Rect, Row};
+use std::time::{Duration, Instant};
+
+/// The most regions that can be checked out at once.
+const MAX_REGIONS: usize = 8;
+
+/// A rectangle of the display checked out from a [`RefreshScheduler`] for exclusive drawing.
+///
+/// While a region is checked out no overlapping region can be, and
+/// [`RefreshScheduler::print`] leaves its cells alone, so one module cannot clobber another's
+/// part of the layout. Draw through [`RefreshScheduler::canvas`] and hand the region back with
+/// [`RefreshScheduler::release`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct Region {
+    rect: Rect,
+    slot: usize,
+}
+
+impl Region {
+    /// Returns the rectangle the region covers.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+}
+
+/// Refreshes the display from a [`Frame`] at a capped rate and bus load.
+///
+/// The application draws into the scheduler's frame as often as it likes and calls
+/// [`present`](Self::present) when a frame is complete; [`tick`](Self::tick), called from the
+/// main loop, sends what changed. Only the latest presented frame is kept, so when the
+/// application updates faster than the bus allows, intermediate frames are dropped instead of
+/// queueing up and the display never lags behind.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut scheduler = RefreshScheduler::new(lcd).with_max_rate(10).with_byte_budget(32);
+/// loop {
+///     scheduler.frame_mut().clear();
+///     scheduler.print(0, 0, "Temp")?;
+///     scheduler.present();
+///     scheduler.tick()?;
+/// }
+/// ```
+pub struct RefreshScheduler<'a> {
+    pub(crate) lcd: Lcd<'a>,
+    pub(crate) back: Frame,
+    pub(crate) pending: Frame,
+    pub(crate) front: Frame,
+    pub(crate) shown: bool,
+    pub(crate) interval: Duration,
+    byte_budget: u16,
+    last_refresh: Option<Moment>,
+    dropped: u32,
+    regions: [Option<Rect>; MAX_REGIONS],
+    pub(crate) alert: Option<Alert>,
+    overlay: Option<Frame>,
+    pub(crate) toasts: Toasts,
+    last_activity: Moment,
+    pub(crate) rotation: Option<Rotation>,
+}
+
+impl<'a> RefreshScheduler<'a> {
+    /// Creates a scheduler that takes over the display, refreshing at most 10 times a second
+    /// with no byte budget.
+    ///
+    /// What the display already shows is taken from its shadow buffer, so the first refresh
+    /// only sends what the new frame changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The initialized display.
+    pub fn new(lcd: Lcd<'a>) -> Self {
+        let mut front = Frame::new(lcd.cols(), lcd.rows());
+        let screen = lcd.screenshot();
+        for row in 0..front.rows() {
+            front.write_bytes(0, row, screen.row(row));
+        }
+        Self {
+            lcd,
+            back: front.clone(),
+            pending: front.clone(),
+            front,
+            shown: true,
+            interval: Duration::from_millis(100),
+            byte_budget: u16::MAX,
+            last_refresh: None,
+            dropped: 0,
+            regions: [None; MAX_REGIONS],
+            alert: None,
+            overlay: None,
+            toasts: Toasts::new(),
+            last_activity: AnimClock::now(),
+            rotation: None,
+        }
+    }
+
+    /// Sets the highest refresh rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `hz` - Refreshes per second; 0 removes the cap.
+    pub fn with_max_rate(mut self, hz: u16) -> Self {
+        self.interval = match hz {
+            0 => Duration::ZERO,
+            hz => Duration::from_secs(1) / hz.into(),
+        };
+        self
+    }
+
+    /// Sets how many bytes one refresh may send to the controller.
+    ///
+    /// Each character and each cursor move counts as one byte. A frame that does not fit is
+    /// finished over the following refreshes.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The budget per refresh, at least 2.
+    pub fn with_byte_budget(mut self, bytes: u16) -> Self {
+        self.byte_budget = bytes.max(2);
+        self
+    }
+
+    /// Returns the frame being drawn.
+    pub fn frame(&self) -> &Frame {
+        &self.back
+    }
+
+    /// Returns the frame being drawn, for drawing into.
+    ///
+    /// Writes through the frame ignore checked-out regions.
+    pub fn frame_mut(&mut self) -> &mut Frame {
+        &mut self.back
+    }
+
+    /// Prints text into the frame being drawn, using the display's substitution table.
+    ///
+    /// Cells inside checked-out regions are skipped, so a stray print cannot clobber them.
+    ///
+    /// # Arguments
+    ///
+    /// * `col` - The column position (0-indexed).
+    /// * `row` - The row position (0-indexed).
+    /// * `text` - The text to print.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the text is successfully printed.
+    /// * `Err(anyhow::Error)` - If a character cannot be shown and the fallback policy is [`Fallback::Error`](crate::charset::Fallback::Error).
+    pub fn print(
+        &mut self,
+        col: impl Into<Col>,
+        row: impl Into<Row>,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let (col, row) = (col.into().0, row.into().0);
+        let charmap = *self.lcd.charmap();
+        let mut col = col;
+        for ch in text.chars() {
+            if col >= self.back.cols() {
+                break;
+            }
+            if let Some(code) = charmap.encode(ch)? {
+                if !self.is_locked(col, row) {
+                    self.back.set(col, row, code);
+                }
+                col += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks out a rectangle for exclusive drawing.
+    ///
+    /// # Arguments
+    ///
+    /// * `rect` - The rectangle to check out.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Region)` - The handle to draw through.
+    /// * `Err(anyhow::Error)` - If the rectangle is off the display, overlaps a checked-out region, or too many regions are checked out.
+    pub fn checkout(&mut self, rect: Rect) -> anyhow::Result<Region> {
+        if !rect.fits(self.back.cols(), self.back.rows()) {
+            return Err(anyhow::anyhow!("Region does not fit on the display"));
+        }
+        if self
+            .regions
+            .iter()
+            .flatten()
+            .any(|other| other.overlaps(&rect))
+        {
+            return Err(anyhow::anyhow!("Region overlaps a checked-out region"));
+        }
+        let slot = self
+            .regions
+            .iter()
+            .position(Option::is_none)
+            .ok_or_else(|| anyhow::anyhow!("Too many regions checked out"))?;
+        self.regions[slot] = Some(rect);
+        Ok(Region { rect, slot })
+    }
+
+    /// Hands a region back so its cells can be checked out again.
+    ///
+    /// # Arguments
+    ///
+    /// * `region` - The region to release.
+    pub fn release(&mut self, region: Region) {
+        if self.regions[region.slot] == Some(region.rect) {
+            self.regions[region.slot] = None;
+        }
+    }
+
+    /// Returns a canvas for drawing into a checked-out region, clipped to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `region` - The region to draw into.
+    pub fn canvas(&mut self, region: &Region) -> Canvas<'_> {
+        let charmap = *self.lcd.charmap();
+        Canvas::new(&mut self.back, region.rect, charmap)
+    }
+
+    fn is_locked(&self, col: u8, row: u8) -> bool {
+        let cell = Rect::new(col, row, 1, 1);
+        self.regions
+            .iter()
+            .flatten()
+            .any(|rect| rect.overlaps(&cell))
+    }
+
+    /// Marks the frame being drawn as complete, to be shown by the next refreshes.
+    ///
+    /// If the previous frame has not been fully shown yet it is dropped. While an
+    /// [`alert`](Self::alert) or a [`Screensaver`](crate::Screensaver) is shown the frame is held
+    /// back until it ends. A frame that differs from the previous one counts as activity, see
+    /// [`idle_time`](Self::idle_time).
+    pub fn present(&mut self) {
+        if self.pending != self.back {
+            self.last_activity = AnimClock::now();
+        }
+        if self.alert.is_some() || self.overlay.is_some() {
+            self.pending.clone_from(&self.back);
+            if self.toasts.frame.is_some() {
+                self.compose_toast();
+            }
+            return;
+        }
+        if !self.shown {
+            self.dropped = self.dropped.saturating_add(1);
+        }
+        self.pending.clone_from(&self.back);
+        if self.toasts.frame.is_some() {
+            self.compose_toast();
+        } else {
+            self.shown = self.pending == self.front;
+        }
+    }
+
+    /// Presents a frame that only changes how the content is shown, such as a blinking
+    /// cursor or a burn-in shift, without counting as activity.
+    pub(crate) fn present_quietly(&mut self) {
+        let last_activity = self.last_activity;
+        self.present();
+        self.last_activity = last_activity;
+    }
+
+    /// Returns how long ago a changed frame was last presented, or [`touch`](Self::touch) was
+    /// last called.
+    pub fn idle_time(&self) -> Duration {
+        self.last_activity.elapsed()
+    }
+
+    /// Records user activity, such as a key press, that did not change the frame.
+    pub fn touch(&mut self) {
+        self.last_activity = AnimClock::now();
+    }
+
+    /// Shows `overlay` instead of the presented frames, or goes back to them with `None`.
+    pub(crate) fn set_overlay(&mut self, overlay: Option<Frame>) {
+        self.overlay = overlay;
+        self.shown = *self.source() == self.front;
+    }
+
+    /// Returns the frame the display should show: an alert, then an overlay, then the
+    /// presented frame with any toast over it.
+    pub(crate) fn source(&self) -> &Frame {
+        match (&self.alert, &self.overlay, &self.toasts.frame) {
+            (Some(alert), _, _) => alert.frame(),
+            (None, Some(overlay), _) => overlay,
+            (None, None, Some(toast)) => toast,
+            (None, None, None) => &self.pending,
+        }
+    }
+
+    /// Sends the cells of the presented frame, or of the alert being shown, that differ from
+    /// the display, if the rate cap allows a refresh now. The time the refresh takes is kept as
+    /// the [`worst_flush`](crate::Stats::worst_flush) of [`Lcd::stats`] if it is the longest
+    /// so far.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `true` if anything was sent.
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character, setting the cursor, switching the backlight or creating the characters of a toast scroll. Errors while sending cells carry a [`ProgressReport`] of the changed cells sent; the next tick carries on from there.
+    pub fn tick(&mut self) -> anyhow::Result<bool> {
+        self.update_alert()?;
+        self.update_toasts()?;
+        if self.shown {
+            return Ok(false);
+        }
+        let now = AnimClock::now();
+        if let Some(last) = self.last_refresh {
+            if now.duration_since(last) < self.interval {
+                return Ok(false);
+            }
+        }
+        self.last_refresh = Some(now);
+
+        let started = Instant::now();
+        let result = self.send_changes();
+        self.lcd.record_flush(started.elapsed());
+        result
+    }
+
+    /// Sends the changed cells in runs, within the byte budget, and returns `true`.
+    fn send_changes(&mut self) -> anyhow::Result<bool> {
+        let source = match (&self.alert, &self.overlay, &self.toasts.frame) {
+            (Some(alert), _, _) => alert.frame(),
+            (None, Some(overlay), _) => overlay,
+            (None, None, Some(toast)) => toast,
+            (None, None, None) => &self.pending,
+        };
+        let total = (0..source.rows())
+            .map(|row| {
+                let front = self.front.row(row);
+                let cells = source.row(row).iter().zip(front);
+                cells.filter(|(code, shown)| code != shown).count()
+            })
+            .sum();
+        let mut written = 0;
+        let mut spent = 0;
+        for row in 0..source.rows() {
+            let codes = source.row(row);
+            let changed = |front: &Frame, col: usize| front.get(col as u8, row) != Some(codes[col]);
+            let mut col = 0;
+            while col < codes.len() {
+                if !changed(&self.front, col) {
+                    col += 1;
+                    continue;
+                }
+                // A run costs one byte per cell plus one for moving the cursor.
+                let start = col;
+                while col < codes.len()
+                    && changed(&self.front, col)
+                    && spent + col - start + 2 <= self.byte_budget as usize
+                {
+                    col += 1;
+                }
+                if col == start {
+                    return Ok(true);
+                }
+                let run = &codes[start..col];
+                match &mut self.rotation {
+                    Some(rotation) => {
+                        rotation.write_cells(&mut self.lcd, start as u8, row, run, source)
+                    }
+                    None => self.lcd.write_cells(start as u8, row, run),
+                }
+                .map_err(|e| e.context(ProgressReport::new(written, total)))?;
+                self.front.write_bytes(start as u8, row, run);
+                written += run.len();
+                spent += run.len() + 1;
+                if col < codes.len() && changed(&self.front, col) {
+                    return Ok(true);
+                }
+            }
+        }
+        self.shown = true;
+        Ok(true)
+    }
+
+    /// Returns `true` once the last presented frame is fully shown.
+    pub fn is_idle(&self) -> bool {
+        self.shown
+    }
+
+    /// Returns the number of presented frames replaced before they were fully shown.
+    pub fn dropped_frames(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Returns the display.
+    pub fn lcd(&self) -> &Lcd<'a> {
+        &self.lcd
+    }
+
+    /// Returns the display, for settings such as the backlight.
+    ///
+    /// Call [`resync`](Self::resync) after printing through it directly.
+    pub fn lcd_mut(&mut self) -> &mut Lcd<'a> {
+        &mut self.lcd
+    }
+
+    /// Re-reads what the display shows from its shadow buffer, after it was changed directly.
+    pub fn resync(&mut self) {
+        if let Some(rotation) = &self.rotation {
+            rotation.unturn(&self.lcd, &mut self.front);
+        } else {
+            let screen = self.lcd.screenshot();
+            for row in 0..self.front.rows() {
+                self.front.write_bytes(0, row, screen.row(row));
+            }
+        }
+        self.shown = *self.source() == self.front;
+    }
+
+    /// Releases the display.
+    pub fn into_inner(self) -> Lcd<'a> {
+        self.lcd
+    }
+}
@@ -0,0 +1,182 @@
+//! Showing frames upside down, for modules mounted inverted.
+
+use crate::{Frame, Lcd, RefreshScheduler};
+
+/// ROM characters that read as another, or as themselves, when turned upside down.
+const UPSIDE_DOWN: [(u8, u8); 16] = [
+    (b'6', b'9'),
+    (b'n', b'u'),
+    (b'd', b'p'),
+    (b'b', b'q'),
+    (b'M', b'W'),
+    (b'(', b')'),
+    (b'[', b']'),
+    (b'<', b'>'),
+    (b'{', b'}'),
+    (b'0', b'0'),
+    (b'8', b'8'),
+    (b'H', b'H'),
+    (b'N', b'N'),
+    (b'S', b'S'),
+    (b'X', b'X'),
+    (b'Z', b'Z'),
+];
+
+/// Returns the bitmap of a character code as the display's ROM draws it.
+type Font = fn(u8) -> Option<[u8; 8]>;
+
+/// The glyphs a [`RefreshScheduler`] draws upside down and the CGRAM slots holding them.
+pub(crate) struct Rotation {
+    font: Font,
+    first_slot: u8,
+    /// The character code each CGRAM location holds turned upside down, if any.
+    slots: [Option<u8>; 8],
+}
+
+impl Rotation {
+    /// Returns the code to send for `code` upside down: a CGRAM slot holding its turned
+    /// bitmap, else a ROM character that looks like it turned, else the code itself.
+    ///
+    /// A slot whose character is not in `target` any more is reused for a new one.
+    fn glyph(&mut self, lcd: &mut Lcd, code: u8, target: &Frame) -> anyhow::Result<u8> {
+        let locations = self.first_slot as usize..self.slots.len();
+        if let Some(location) = locations.clone().find(|&i| self.slots[i] == Some(code)) {
+            return Ok(location as u8);
+        }
+        // Spaces look the same either way, and codes below 8 are the application's own glyphs.
+        if code == b' ' || code < 8 {
+            return Ok(code);
+        }
+        let Some(bitmap) = (self.font)(code) else {
+            return Ok(rom_turned(code).unwrap_or(code));
+        };
+        let in_use = |code: u8| (0..target.rows()).any(|row| target.row(row).contains(&code));
+        let free = locations
+            .clone()
+            .find(|&i| self.slots[i].is_none())
+            .or_else(|| {
+                locations
+                    .clone()
+                    .find(|&i| self.slots[i].is_some_and(|code| !in_use(code)))
+            });
+        let Some(location) = free else {
+            return Ok(rom_turned(code).unwrap_or(code));
+        };
+        lcd.create_custom_chars(location as u8, &turn(bitmap))?;
+        self.slots[location] = Some(code);
+        Ok(location as u8)
+    }
+
+    /// Returns the character code that a code on the display shows turned upside down.
+    fn logical(&self, code: u8) -> u8 {
+        if let Some(Some(original)) = self.slots.get(code as usize) {
+            return *original;
+        }
+        rom_turned(code).unwrap_or(code)
+    }
+
+    /// Writes a run of cells of a frame to the display turned by 180 degrees: the run lands
+    /// on the mirrored row, right to left from the mirrored column.
+    pub(crate) fn write_cells(
+        &mut self,
+        lcd: &mut Lcd,
+        col: u8,
+        row: u8,
+        codes: &[u8],
+        target: &Frame,
+    ) -> anyhow::Result<()> {
+        let mut turned = [b' '; 40];
+        let len = codes.len().min(turned.len());
+        for (cell, &code) in turned[..len].iter_mut().rev().zip(codes) {
+            *cell = self.glyph(lcd, code, target)?;
+        }
+        let (cols, rows) = (lcd.cols(), lcd.rows());
+        let col = cols.saturating_sub(col.saturating_add(len as u8));
+        lcd.write_cells(col, rows.saturating_sub(row + 1), &turned[..len])
+    }
+
+    /// Fills `frame` with what the display shows, as the frame that shows that upside down.
+    pub(crate) fn unturn(&self, lcd: &Lcd, frame: &mut Frame) {
+        let screen = lcd.screenshot();
+        let (cols, rows) = (frame.cols(), frame.rows());
+        for row in 0..rows {
+            for (col, &code) in screen.row(rows - 1 - row).iter().rev().enumerate() {
+                if (col as u8) < cols {
+                    frame.set(col as u8, row, self.logical(code));
+                }
+            }
+        }
+    }
+}
+
+/// Returns the ROM character that looks like `code` upside down, if there is one.
+fn rom_turned(code: u8) -> Option<u8> {
+    UPSIDE_DOWN.iter().find_map(|&(a, b)| {
+        if code == a {
+            Some(b)
+        } else if code == b {
+            Some(a)
+        } else {
+            None
+        }
+    })
+}
+
+/// Turns a glyph by 180 degrees: the rows in reverse order, each mirrored.
+fn turn(glyph: [u8; 8]) -> [u8; 8] {
+    let mut turned = [0u8; 8];
+    for (row, bits) in turned.iter_mut().zip(glyph.iter().rev()) {
+        *row = (bits.reverse_bits() >> 3) & 0b11111;
+    }
+    turned
+}
+
+impl RefreshScheduler<'_> {
+    /// Shows every frame turned by 180 degrees, for an enclosure where the module is mounted
+    /// upside down.
+    ///
+    /// Frames are drawn as usual and mirrored cell by cell on the way to the display. The
+    /// characters themselves are turned through custom characters: the controller's ROM
+    /// cannot be read, so their bitmaps come from `font`, and each distinct character on
+    /// screen takes one CGRAM location from `first_slot` to 7. A character without a bitmap,
+    /// or one that finds every location taken, is shown as a ROM character that looks like it
+    /// turned (`6` for `9`, `u` for `n`, `S` for itself) or else as it is. Codes 0 to 7, the
+    /// application's own custom characters, are shown as they are.
+    ///
+    /// What the display shows is taken over as the frame that turns into it.
+    ///
+    /// # Arguments
+    ///
+    /// * `font` - Returns the 5x8 bitmap of a character code as the display shows it.
+    /// * `first_slot` - The first CGRAM location the rotation may use; it uses every one from there to 7.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn font(code: u8) -> Option<[u8; 8]> {
+    ///     FONT_5X8.get(code.checked_sub(0x20)? as usize).copied()
+    /// }
+    ///
+    /// let mut scheduler = RefreshScheduler::new(lcd).with_upside_down(font, 0);
+    /// scheduler.print(0, 0, "Temp 21.5C")?;
+    /// ```
+    pub fn with_upside_down(mut self, font: Font, first_slot: u8) -> Self {
+        let rotation = Rotation {
+            font,
+            first_slot: first_slot.min(8),
+            slots: [None; 8],
+        };
+        rotation.unturn(&self.lcd, &mut self.front);
+        self.back.clone_from(&self.front);
+        self.pending.clone_from(&self.front);
+        self.rotation = Some(rotation);
+        self.shown = true;
+        self
+    }
+
+    /// Returns `true` if frames are shown upside down, see
+    /// [`with_upside_down`](Self::with_upside_down).
+    pub fn is_upside_down(&self) -> bool {
+        self.rotation.is_some()
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{AnimClock, Lcd, Moment};
+use std::time::Duration;
+
+/// The application's source of the local time of day, such as SNTP or an RTC.
+pub trait TimeOfDay {
+    /// Returns the minutes since local midnight, or `None` while the time is not known yet.
+    fn minute_of_day(&self) -> Option<u16>;
+}
+
+/// The backlight brightness, and optionally the contrast, from a time of day on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    /// The start of the period in minutes since midnight.
+    pub start: u16,
+    /// The brightness, from 0 (off) to 255 (full).
+    pub brightness: u8,
+    /// The contrast from 0 to 63, on controllers with software contrast.
+    pub contrast: Option<u8>,
+}
+
+impl Period {
+    /// Creates a period starting at the given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `hour` - The hour the period starts at, from 0 to 23.
+    /// * `minute` - The minute the period starts at, from 0 to 59.
+    /// * `brightness` - The brightness, from 0 (off) to 255 (full).
+    pub const fn new(hour: u8, minute: u8, brightness: u8) -> Self {
+        Self {
+            start: hour as u16 * 60 + minute as u16,
+            brightness,
+            contrast: None,
+        }
+    }
+
+    /// Also sets the contrast during the period.
+    ///
+    /// # Arguments
+    ///
+    /// * `contrast` - The contrast from 0 to 63.
+    pub const fn with_contrast(mut self, contrast: u8) -> Self {
+        self.contrast = Some(contrast);
+        self
+    }
+}
+
+/// Switches the backlight between day and night settings by the time of day.
+///
+/// Each [`Period`] lasts until the next one starts; the last period of the day carries on past
+/// midnight until the first. When a new period starts the brightness fades to its level along
+/// the display's [`FadeCurve`](crate::FadeCurve).
+///
+/// # Example
+///
+/// ```ignore
+/// const PERIODS: [Period; 3] = [
+///     Period::new(7, 0, 255),
+///     Period::new(20, 0, 96),
+///     Period::new(23, 0, 16).with_contrast(20),
+/// ];
+/// let mut schedule = BacklightSchedule::new(&PERIODS);
+/// loop {
+///     schedule.tick(&mut lcd, &sntp_clock)?;
+/// }
+/// ```
+pub struct BacklightSchedule<'p> {
+    periods: &'p [Period],
+    fade: Duration,
+    active: Option<usize>,
+    fade_from: u8,
+    fade_start: Option<Moment>,
+}
+
+impl<'p> BacklightSchedule<'p> {
+    /// Creates a schedule fading over two seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `periods` - The periods of the day, in any order.
+    pub fn new(periods: &'p [Period]) -> Self {
+        Self {
+            periods,
+            fade: Duration::from_secs(2),
+            active: None,
+            fade_from: 0,
+            fade_start: None,
+        }
+    }
+
+    /// Sets how long the brightness takes to change when a period starts.
+    ///
+    /// # Arguments
+    ///
+    /// * `fade` - The fade duration; zero switches at once.
+    pub fn with_fade(mut self, fade: Duration) -> Self {
+        self.fade = fade;
+        self
+    }
+
+    /// Returns the period in effect at a time of day, if any periods are set.
+    ///
+    /// # Arguments
+    ///
+    /// * `minute` - The minutes since midnight.
+    pub fn period_at(&self, minute: u16) -> Option<&Period> {
+        self.index_at(minute).map(|index| &self.periods[index])
+    }
+
+    /// Applies the period in effect now and continues a running fade.
+    ///
+    /// Call this regularly from the main loop; fades are smoother the more often it runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display.
+    /// * `clock` - The source of the time of day.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `true` if the brightness or contrast was changed.
+    /// * `Err(anyhow::Error)` - If there is an error while setting the brightness or contrast.
+    pub fn tick(&mut self, lcd: &mut Lcd, clock: &dyn TimeOfDay) -> anyhow::Result<bool> {
+        let Some(index) = clock
+            .minute_of_day()
+            .and_then(|minute| self.index_at(minute))
+        else {
+            return Ok(false);
+        };
+        let period = self.periods[index];
+        let mut changed = false;
+        if self.active != Some(index) {
+            self.active = Some(index);
+            self.fade_from = lcd.brightness();
+            self.fade_start = Some(AnimClock::now());
+            if let Some(contrast) = period.contrast {
+                if lcd.profile().extended_instructions {
+                    lcd.set_contrast(contrast)?;
+                    changed = true;
+                }
+            }
+        }
+
+        if let Some(start) = self.fade_start {
+            let elapsed = start.elapsed();
+            let level = if elapsed >= self.fade {
+                self.fade_start = None;
+                period.brightness
+            } else {
+                let progress = elapsed.as_secs_f32() / self.fade.as_secs_f32();
+                let curve = lcd.fade_curve();
+                curve.interpolate(self.fade_from, period.brightness, progress)
+            };
+            if level != lcd.brightness() {
+                lcd.set_brightness(level)?;
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+
+    fn index_at(&self, minute: u16) -> Option<usize> {
+        let periods = self.periods.iter().enumerate();
+        periods
+            .clone()
+            .filter(|(_, period)| period.start <= minute)
+            .max_by_key(|(_, period)| period.start)
+            // Before the first period of the day, the last one carries on from yesterday.
+            .or_else(|| periods.max_by_key(|(_, period)| period.start))
+            .map(|(index, _)| index)
+    }
+}
@@ -0,0 +1,230 @@
+use crate::charset::DisplayWidth;
+use crate::{AnimClock, Frame, Moment, RefreshScheduler};
+use std::time::Duration;
+
+/// The widest display a screensaver animates.
+const MAX_COLS: usize = 40;
+/// The range of half-width katakana in the A00 character ROM, used by the matrix rain.
+const KATAKANA: core::ops::RangeInclusive<u8> = 0xa6..=0xdd;
+/// How many cells a raindrop trails behind it.
+const TRAIL: u8 = 3;
+
+/// The animation a [`Screensaver`] plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaverMode<'t> {
+    /// A `*` wandering randomly around the display.
+    Walker,
+    /// A line of text bouncing off the edges of the display.
+    Bounce(&'t str),
+    /// Columns of katakana falling down the display.
+    MatrixRain,
+}
+
+/// Replaces the display with a moving animation after a period of inactivity, to spare LCDs
+/// and OLEDs from burning in a static screen.
+///
+/// The screensaver watches the [`RefreshScheduler`] it is ticked with: once no changed frame
+/// has been presented, and [`touch`](RefreshScheduler::touch) has not been called, for the
+/// timeout, it takes over the display. The next changed frame or touch, or a call to
+/// [`dismiss`](Screensaver::dismiss), restores the application's latest frame. The animations
+/// only use characters from the ROM, so the application's custom characters survive.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut saver = Screensaver::new(SaverMode::MatrixRain, Duration::from_secs(300));
+/// loop {
+///     if button.is_pressed() {
+///         scheduler.touch();
+///     }
+///     saver.tick(&mut scheduler)?;
+///     scheduler.tick()?;
+/// }
+/// ```
+pub struct Screensaver<'t> {
+    mode: SaverMode<'t>,
+    timeout: Duration,
+    step: Duration,
+    seed: u32,
+    rng: u32,
+    activated: Option<Moment>,
+    last_step: Option<Moment>,
+    frame: Frame,
+    pos: (u8, u8),
+    velocity: (i8, i8),
+    drops: [Option<u8>; MAX_COLS],
+}
+
+impl<'t> Screensaver<'t> {
+    /// Creates a screensaver that starts after `timeout` without activity and animates five
+    /// times a second.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The animation to play.
+    /// * `timeout` - How long the display must be idle before the screensaver starts.
+    pub fn new(mode: SaverMode<'t>, timeout: Duration) -> Self {
+        Self {
+            mode,
+            timeout,
+            step: Duration::from_millis(200),
+            seed: 0x2545_f491,
+            rng: 0x2545_f491,
+            activated: None,
+            last_step: None,
+            frame: Frame::new(0, 0),
+            pos: (0, 0),
+            velocity: (1, 1),
+            drops: [None; MAX_COLS],
+        }
+    }
+
+    /// Sets how often the animation moves, 200 ms by default.
+    pub fn with_step(mut self, step: Duration) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Seeds the random numbers used by the walker and the rain, e.g. from a hardware RNG.
+    pub fn with_seed(mut self, seed: u32) -> Self {
+        // Xorshift never leaves zero.
+        self.seed = seed.max(1);
+        self.rng = self.seed;
+        self
+    }
+
+    /// Returns `true` while the screensaver is shown.
+    pub fn is_active(&self) -> bool {
+        self.activated.is_some()
+    }
+
+    /// Starts the screensaver if the display has been idle long enough, advances its
+    /// animation, and stops it once there is activity.
+    ///
+    /// # Arguments
+    ///
+    /// * `scheduler` - The scheduler refreshing the display.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `true` if the screensaver started, moved or stopped.
+    /// * `Err(anyhow::Error)` - If the bouncing text cannot be encoded and the fallback policy is [`Fallback::Error`](crate::charset::Fallback::Error).
+    pub fn tick(&mut self, scheduler: &mut RefreshScheduler) -> anyhow::Result<bool> {
+        let idle = scheduler.idle_time();
+        match self.activated {
+            Some(activated) if idle < activated.elapsed() => {
+                self.dismiss(scheduler);
+                return Ok(true);
+            }
+            Some(_) => {}
+            None if idle >= self.timeout => self.activate(scheduler),
+            None => return Ok(false),
+        }
+
+        let now = AnimClock::now();
+        if let Some(last) = self.last_step {
+            if now.duration_since(last) < self.step {
+                return Ok(false);
+            }
+        }
+        self.last_step = Some(now);
+        self.advance(scheduler)?;
+        scheduler.set_overlay(Some(self.frame.clone()));
+        Ok(true)
+    }
+
+    /// Stops the screensaver and restores the application's latest frame. The idle time
+    /// starts again from now.
+    ///
+    /// # Arguments
+    ///
+    /// * `scheduler` - The scheduler refreshing the display.
+    pub fn dismiss(&mut self, scheduler: &mut RefreshScheduler) {
+        if self.activated.take().is_some() {
+            scheduler.set_overlay(None);
+        }
+        scheduler.touch();
+    }
+
+    fn activate(&mut self, scheduler: &RefreshScheduler) {
+        let source = scheduler.frame();
+        self.frame = Frame::new(source.cols(), source.rows());
+        self.activated = Some(AnimClock::now());
+        self.last_step = None;
+        self.rng = self.seed;
+        self.pos = (
+            self.random(self.frame.cols().max(1)),
+            self.random(self.frame.rows().max(1)),
+        );
+        self.velocity = (1, 1);
+        self.drops = [None; MAX_COLS];
+    }
+
+    fn advance(&mut self, scheduler: &RefreshScheduler) -> anyhow::Result<()> {
+        let (cols, rows) = (self.frame.cols(), self.frame.rows());
+        if cols == 0 || rows == 0 {
+            return Ok(());
+        }
+        match self.mode {
+            SaverMode::Walker => {
+                let dx = self.random(3) as i16 - 1;
+                let dy = self.random(3) as i16 - 1;
+                self.pos.0 = (self.pos.0 as i16 + dx).clamp(0, cols as i16 - 1) as u8;
+                self.pos.1 = (self.pos.1 as i16 + dy).clamp(0, rows as i16 - 1) as u8;
+                self.frame.clear();
+                self.frame.set(self.pos.0, self.pos.1, b'*');
+            }
+            SaverMode::Bounce(text) => {
+                let charmap = *scheduler.lcd().charmap();
+                let width = text.display_width(&charmap).min(cols as usize) as u8;
+                let max_col = cols - width;
+                self.velocity.0 = Self::bounce(self.pos.0, self.velocity.0, max_col);
+                self.velocity.1 = Self::bounce(self.pos.1, self.velocity.1, rows - 1);
+                self.pos.0 = (self.pos.0.min(max_col) as i16 + self.velocity.0 as i16) as u8;
+                self.pos.1 = (self.pos.1.min(rows - 1) as i16 + self.velocity.1 as i16) as u8;
+                self.frame.clear();
+                self.frame.print(self.pos.0, self.pos.1, text, &charmap)?;
+            }
+            SaverMode::MatrixRain => {
+                for col in 0..cols {
+                    let falling = self.drops[col as usize];
+                    let head = match falling {
+                        Some(head) => head + 1,
+                        None if self.random(8) == 0 => 0,
+                        None => continue,
+                    };
+                    if head >= TRAIL {
+                        self.frame.set(col, head - TRAIL, b' ');
+                    }
+                    if head < rows {
+                        let code = KATAKANA.start() + self.random(KATAKANA.len() as u8);
+                        self.frame.set(col, head, code);
+                    }
+                    self.drops[col as usize] = (head < rows + TRAIL).then_some(head);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the velocity along one axis after bouncing off the edges `0` and `max`.
+    fn bounce(pos: u8, velocity: i8, max: u8) -> i8 {
+        if max == 0 {
+            0
+        } else if pos >= max {
+            -1
+        } else if pos == 0 {
+            1
+        } else {
+            velocity
+        }
+    }
+
+    /// Returns a pseudo-random number below `bound`.
+    fn random(&mut self, bound: u8) -> u8 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        (self.rng % bound.max(1) as u32) as u8
+    }
+}
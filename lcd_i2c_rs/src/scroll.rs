@@ -0,0 +1,184 @@
+//! Scrolling rows up by one over several frames.
+
+use crate::Lcd;
+
+/// The height of a character cell in pixels, and so the offset of the last frame of a scroll.
+pub(crate) const CELL_ROWS: u8 = 8;
+
+/// Returns the bitmap of a character code as the display's ROM draws it.
+pub(crate) type Font = fn(u8) -> Option<[u8; 8]>;
+
+/// An animation that moves rows up by one row over several frames, so a new line slides in
+/// from below instead of popping in.
+///
+/// Each frame is drawn at a pixel offset: every cell shows the bottom of the character it
+/// showed, moved up, over the top of the character rising into it from the row below. Those
+/// cells are drawn as custom characters built from a font (see [`with_smooth`](Self::with_smooth)),
+/// one CGRAM slot per distinct cell, so a smooth scroll suits sparse lines with few distinct
+/// characters. Whenever a frame needs more slots than there are, or a character has no bitmap,
+/// the scroll falls back to moving whole rows: it jumps to its last frame. Without a font it
+/// always moves whole rows.
+///
+/// The [`Logger`](crate::widgets::Logger) scrolls its lines with it, and a
+/// [`RefreshScheduler`](crate::RefreshScheduler) its toasts, see
+/// [`with_toast_scroll`](crate::RefreshScheduler::with_toast_scroll).
+#[derive(Debug, Clone, Copy)]
+pub struct ScrollUp {
+    steps: u8,
+    step: u8,
+    font: Option<Font>,
+    first_slot: u8,
+}
+
+impl ScrollUp {
+    /// Creates an idle scroll that takes `steps` frames, moving whole rows until a font is set
+    /// with [`with_smooth`](Self::with_smooth).
+    ///
+    /// A value of 0 or 1 makes every scroll instant.
+    pub const fn new(steps: u8) -> Self {
+        Self {
+            steps,
+            step: 0,
+            font: None,
+            first_slot: 0,
+        }
+    }
+
+    /// Scrolls a pixel row at a time instead of a whole row, spreading the eight pixel rows of
+    /// a cell over the steps.
+    ///
+    /// # Arguments
+    ///
+    /// * `font` - Returns the 5x8 bitmap of a character code as the display shows it.
+    /// * `first_slot` - The first CGRAM location the scroll may use; it uses every one from there to 7.
+    pub const fn with_smooth(mut self, font: Font, first_slot: u8) -> Self {
+        self.font = Some(font);
+        self.first_slot = first_slot;
+        self
+    }
+
+    /// Sets the number of frames a scroll takes, keeping any font.
+    pub const fn with_steps(mut self, steps: u8) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Returns the number of frames a scroll takes.
+    pub fn steps(&self) -> u8 {
+        self.steps
+    }
+
+    /// Returns `true` if the scroll moves a pixel row at a time.
+    pub fn is_smooth(&self) -> bool {
+        self.font.is_some()
+    }
+
+    /// Stops the scroll, as if its last frame had been drawn.
+    pub fn finish(&mut self) {
+        self.step = 0;
+    }
+
+    /// Starts a new scroll from the first frame.
+    pub fn start(&mut self) {
+        self.step = 1;
+    }
+
+    /// Returns `true` while the scroll has frames left to draw.
+    pub fn is_running(&self) -> bool {
+        self.step != 0
+    }
+
+    /// Returns the pixel offset of the current frame and moves to the next one.
+    ///
+    /// The offset runs from 1 to 8; at 8 the rows have moved up a whole row and the scroll
+    /// stops. A scroll without a font, or of one step, returns 8 straight away.
+    pub fn advance(&mut self) -> u8 {
+        if !self.is_running() {
+            return CELL_ROWS;
+        }
+        let steps = if self.font.is_some() {
+            self.steps.max(1)
+        } else {
+            1
+        };
+        let offset = (CELL_ROWS as u16 * self.step as u16).div_ceil(steps as u16) as u8;
+        if self.step >= steps {
+            self.step = 0;
+        } else {
+            self.step += 1;
+        }
+        offset.min(CELL_ROWS)
+    }
+
+    /// Returns the builder of the custom characters of one frame, or `None` without a font.
+    pub(crate) fn glyphs(&self, offset: u8) -> Option<ShiftedGlyphs> {
+        Some(ShiftedGlyphs {
+            font: self.font?,
+            first_slot: self.first_slot.min(8),
+            offset,
+            slots: [[0; 8]; 8],
+            used: 0,
+        })
+    }
+}
+
+/// The custom characters of one frame of a smooth [`ScrollUp`].
+pub(crate) struct ShiftedGlyphs {
+    font: Font,
+    first_slot: u8,
+    offset: u8,
+    slots: [[u8; 8]; 8],
+    used: usize,
+}
+
+impl ShiftedGlyphs {
+    /// Returns the code of a cell showing `upper` moved up by the offset over the top of
+    /// `lower`, taking a CGRAM slot for it unless an equal cell already has one.
+    ///
+    /// Returns `None` if a character has no bitmap or every slot is taken.
+    pub(crate) fn cell(&mut self, upper: u8, lower: u8) -> Option<u8> {
+        let bitmap = |code: u8| {
+            if code == b' ' {
+                Some([0; 8])
+            } else {
+                (self.font)(code)
+            }
+        };
+        let (upper, lower) = (bitmap(upper)?, bitmap(lower)?);
+        let offset = self.offset as usize;
+        let glyph: [u8; 8] = core::array::from_fn(|i| match upper.get(i + offset) {
+            Some(&line) => line,
+            None => lower[i + offset - 8],
+        });
+        if glyph == [0; 8] {
+            return Some(b' ');
+        }
+        let capacity = 8 - self.first_slot as usize;
+        let slot = match self.slots[..self.used]
+            .iter()
+            .position(|slot| *slot == glyph)
+        {
+            Some(slot) => slot,
+            None if self.used < capacity => {
+                self.slots[self.used] = glyph;
+                self.used += 1;
+                self.used - 1
+            }
+            None => return None,
+        };
+        Some(self.first_slot + slot as u8)
+    }
+
+    /// Loads the custom characters handed out into CGRAM.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the characters are loaded.
+    /// * `Err(anyhow::Error)` - If there is an error while creating the characters.
+    pub(crate) fn upload(&self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        for (slot, glyph) in self.slots[..self.used].iter().enumerate() {
+            lcd.create_custom_chars(self.first_slot + slot as u8, glyph)?;
+        }
+        Ok(())
+    }
+}
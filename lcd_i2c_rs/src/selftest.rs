@@ -0,0 +1,116 @@
+use crate::Lcd;
+
+/// How long each visual phase of the self-test stays on screen.
+const PHASE_DELAY_MS: u32 = 500;
+
+/// Number of passes of the rolling pattern.
+const PATTERN_PASSES: u8 = 3;
+
+/// The outcome of [`Lcd::self_test`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfTestReport {
+    /// The number of display cells written by the rolling pattern.
+    pub cells: u16,
+    /// The number of CGRAM slots loaded and printed.
+    pub cgram_slots: u8,
+    /// The number of cells that read back a different code than written, or `None` if
+    /// readback is not enabled.
+    pub ddram_mismatches: Option<u16>,
+}
+
+impl SelfTestReport {
+    /// Returns `true` if nothing failed verification.
+    ///
+    /// Without readback only the bus traffic is checked, so this is `true` whenever the test
+    /// ran to completion.
+    pub fn passed(&self) -> bool {
+        self.ddram_mismatches.unwrap_or(0) == 0
+    }
+}
+
+impl Lcd<'_> {
+    /// Exercises every feature of the display for production line testing.
+    ///
+    /// The test fills every cell with a rolling pattern of printable characters, cycles the
+    /// backlight, cursor and blink settings, loads all eight CGRAM slots with test patterns and
+    /// prints them, and, if readback is enabled, verifies that every cell reads back what was
+    /// written. It takes a few seconds so an operator can watch each phase.
+    ///
+    /// Afterwards the display is cleared with the backlight on and the cursor and blinking off.
+    /// The custom characters are overwritten.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SelfTestReport)` - The result of the test.
+    /// * `Err(anyhow::Error)` - If there is an error on the bus, or the [`CancelToken`](crate::CancelToken) is cancelled.
+    pub fn self_test(&mut self) -> anyhow::Result<SelfTestReport> {
+        let (cols, rows) = (self.cols(), self.rows());
+        let pattern = |pass: u8, col: u8, row: u8| -> u8 {
+            let index = row as u16 * cols as u16 + col as u16 + pass as u16;
+            b'!' + (index % 94) as u8
+        };
+
+        self.backlight_on()?;
+        for pass in 0..PATTERN_PASSES {
+            for row in 0..rows {
+                self.set_cursor(0, row)?;
+                for col in 0..cols {
+                    self.write_byte(pattern(pass, col, row))?;
+                }
+            }
+            self.delay_ms(PHASE_DELAY_MS)?;
+        }
+
+        let mut ddram_mismatches = None;
+        if self.has_readback() {
+            let mut mismatches = 0;
+            let last = PATTERN_PASSES - 1;
+            for row in 0..rows {
+                for col in 0..cols {
+                    if self.read_ddram(col, row)? != pattern(last, col, row) {
+                        mismatches += 1;
+                    }
+                }
+            }
+            ddram_mismatches = Some(mismatches);
+        }
+
+        self.backlight_off()?;
+        self.delay_ms(PHASE_DELAY_MS)?;
+        self.backlight_on()?;
+        self.cursor(true)?;
+        self.delay_ms(PHASE_DELAY_MS)?;
+        self.blink(true)?;
+        self.delay_ms(PHASE_DELAY_MS)?;
+        self.cursor(false)?;
+        self.blink(false)?;
+
+        for location in 0..8u8 {
+            let mut charmap = [0u8; 8];
+            for (line, bits) in charmap.iter_mut().enumerate() {
+                // Alternating stripes, offset per slot so each one looks different.
+                *bits = if (line as u8 + location) & 1 == 0 {
+                    0b10101
+                } else {
+                    0b01010
+                };
+                if line as u8 == location {
+                    *bits = 0b11111;
+                }
+            }
+            self.create_custom_chars(location, &charmap)?;
+        }
+        self.clear()?;
+        for location in 0..8u8 {
+            self.write_byte(location)?;
+        }
+        self.delay_ms(PHASE_DELAY_MS)?;
+        self.clear()?;
+
+        Ok(SelfTestReport {
+            cells: cols as u16 * rows as u16,
+            cgram_slots: 8,
+            ddram_mismatches,
+        })
+    }
+}
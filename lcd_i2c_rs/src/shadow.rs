@@ -0,0 +1,193 @@
+use crate::consts::*;
+use crate::driver::next_ddram_address;
+use core::fmt;
+
+/// A software copy of the controller's DDRAM and CGRAM.
+///
+/// The shadow follows every command and data byte sent through the driver and keeps the
+/// address counter in step with the controller, so it knows what the display shows without
+/// reading it back.
+#[derive(Clone)]
+pub(crate) struct Shadow {
+    pub(crate) ddram: [u8; 0x80],
+    pub(crate) cgram: [[u8; 8]; 8],
+    address: u8,
+    in_cgram: bool,
+    increment: bool,
+    two_line: bool,
+    extended: bool,
+    table: u8,
+    in_icon: bool,
+}
+
+impl Shadow {
+    pub(crate) const fn new() -> Self {
+        Self {
+            ddram: [b' '; 0x80],
+            cgram: [[0; 8]; 8],
+            address: 0,
+            in_cgram: false,
+            increment: true,
+            two_line: true,
+            extended: false,
+            table: 0,
+            in_icon: false,
+        }
+    }
+
+    /// Makes the shadow follow the instruction table switches of the ST7036 extended
+    /// instruction set.
+    pub(crate) fn with_extended(mut self, on: bool) -> Self {
+        self.extended = on;
+        self
+    }
+
+    /// Updates the shadow for a command byte.
+    pub(crate) fn command(&mut self, value: u8) {
+        // Outside table 0, the cursor shift and CGRAM commands are extended instructions.
+        let extended = self.table != 0
+            && (LCD_CURSORSHIFT..LCD_SETDDRAMADDR).contains(&value)
+            && value & 0xe0 != LCD_FUNCTIONSET;
+        if extended {
+            if self.table == 0x01 && value & 0xf0 == LCD_SETCGRAMADDR {
+                self.in_icon = true;
+            }
+            return;
+        }
+        if value & LCD_SETDDRAMADDR != 0 {
+            self.address = value & 0x7f;
+            self.in_cgram = false;
+            self.in_icon = false;
+        } else if value & LCD_SETCGRAMADDR != 0 {
+            self.address = value & 0x3f;
+            self.in_cgram = true;
+            self.in_icon = false;
+        } else if value & LCD_FUNCTIONSET != 0 {
+            self.two_line = value & LCD_2LINE != 0;
+            if self.extended {
+                self.table = value & 0x03;
+            }
+        } else if value & LCD_CURSORSHIFT != 0 {
+            // With both low bits set this is the WS0010 mode and power command.
+            if value & LCD_DISPLAYMOVE == 0 && value & 0x03 != 0x03 {
+                self.step(value & LCD_MOVERIGHT != 0);
+            }
+        } else if value & LCD_DISPLAYCONTROL != 0 {
+            // Display, cursor and blink state is tracked by the driver.
+        } else if value & LCD_ENTRYMODESET != 0 {
+            self.increment = value & LCD_ENTRYLEFT != 0;
+        } else if value & LCD_RETURNHOME != 0 {
+            self.address = 0;
+            self.in_cgram = false;
+            self.in_icon = false;
+        } else if value & LCD_CLEARDISPLAY != 0 {
+            self.ddram.fill(b' ');
+            self.address = 0;
+            self.in_cgram = false;
+            self.in_icon = false;
+            self.increment = true;
+        }
+    }
+
+    /// Updates the shadow for a data byte.
+    pub(crate) fn data(&mut self, value: u8) {
+        if self.in_icon {
+            return;
+        }
+        if self.in_cgram {
+            self.cgram[(self.address >> 3) as usize][(self.address & 0x07) as usize] = value;
+        } else {
+            self.ddram[self.address as usize] = value;
+        }
+        self.step(self.increment);
+    }
+
+    /// Updates the shadow for a data byte read back from the controller.
+    pub(crate) fn read(&mut self) {
+        self.step(self.increment);
+    }
+
+    /// Moves the address counter the way the controller does after a read or write.
+    fn step(&mut self, forward: bool) {
+        if self.in_cgram {
+            self.address = if forward {
+                (self.address + 1) & 0x3f
+            } else {
+                self.address.wrapping_sub(1) & 0x3f
+            };
+            return;
+        }
+        self.address = next_ddram_address(self.address, self.two_line, forward);
+    }
+
+    pub(crate) fn screenshot(&self, cols: u8, rows: u8) -> Screenshot {
+        let mut shot = Screenshot {
+            cells: [[b' '; 40]; 4],
+            cols: cols.min(40),
+            rows: rows.min(4),
+        };
+        let cols = shot.cols as usize;
+        for (row, &offset) in ROW_OFFSETS.iter().take(shot.rows as usize).enumerate() {
+            let start = offset as usize;
+            shot.cells[row][..cols].copy_from_slice(&self.ddram[start..start + cols]);
+        }
+        shot
+    }
+}
+
+/// A copy of the characters shown on the display, taken from the driver's shadow buffer.
+///
+/// Returned by [`Lcd::screenshot`](crate::Lcd::screenshot). The [`Display`](fmt::Display)
+/// implementation prints one line per row, showing custom characters as `#` and
+/// non-ASCII ROM characters as `?`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Screenshot {
+    cells: [[u8; 40]; 4],
+    cols: u8,
+    rows: u8,
+}
+
+impl Screenshot {
+    /// Returns the number of columns captured.
+    pub fn cols(&self) -> u8 {
+        self.cols
+    }
+
+    /// Returns the number of rows captured.
+    pub fn rows(&self) -> u8 {
+        self.rows
+    }
+
+    /// Returns the character codes of one row, or an empty slice if the row is out of bounds.
+    pub fn row(&self, row: u8) -> &[u8] {
+        if row >= self.rows {
+            return &[];
+        }
+        &self.cells[row as usize][..self.cols as usize]
+    }
+}
+
+impl fmt::Display for Screenshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            if row > 0 {
+                f.write_str("\n")?;
+            }
+            for &code in self.row(row) {
+                let ch = match code {
+                    0x00..=0x07 => '#',
+                    0x20..=0x7e => code as char,
+                    _ => '?',
+                };
+                fmt::Write::write_char(f, ch)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Screenshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
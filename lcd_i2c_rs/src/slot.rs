@@ -0,0 +1,76 @@
+//! Storage in statics for widgets and screens, without a heap.
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// A `static` place for one value, handed out once as `&'static mut`.
+///
+/// Nothing in the crate allocates: a [`Compositor`](crate::Compositor) borrows its widgets,
+/// sized by their const generics, instead of boxing them. Widgets that live as long as the
+/// firmware can sit in static slots, so they take RAM that shows up in the link map instead
+/// of the stack of `main`, and the compositor can hold them for `'static`.
+///
+/// # Example
+///
+/// ```ignore
+/// static UNITS: Units = Units::metric();
+/// static OUTSIDE: StaticSlot<Readout> = StaticSlot::new();
+/// static HISTORY: StaticSlot<Sparkline<32>> = StaticSlot::new();
+///
+/// let outside = OUTSIDE.init(Readout::new(Dimension::Temperature, &UNITS))?;
+/// let history = HISTORY.init(Sparkline::new())?;
+/// let mut compositor: Compositor<2> = Compositor::new(RefreshScheduler::new(lcd));
+/// compositor.add(Rect::new(10, 0, 6, 1), outside, Duration::from_secs(5))?;
+/// compositor.add(Rect::new(0, 1, 16, 1), history, Duration::from_secs(60))?;
+/// ```
+pub struct StaticSlot<T> {
+    taken: AtomicBool,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY: the value is only reachable through the one `&mut` that `init` hands to the caller
+// that moved it in, so sharing the slot itself shares nothing.
+unsafe impl<T> Sync for StaticSlot<T> {}
+
+impl<T> StaticSlot<T> {
+    /// Creates an empty slot.
+    pub const fn new() -> Self {
+        Self {
+            taken: AtomicBool::new(false),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Moves `value` into the slot and returns it for the rest of the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to keep.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&'static mut T)` - If the slot was empty.
+    /// * `Err(anyhow::Error)` - If the slot was already filled; `value` is dropped.
+    // The flag makes the `&mut` unique, which the lint cannot see.
+    #[allow(clippy::mut_from_ref)]
+    pub fn init(&'static self, value: T) -> anyhow::Result<&'static mut T> {
+        if self.taken.swap(true, Ordering::AcqRel) {
+            return Err(anyhow::anyhow!("Static slot is already in use"));
+        }
+        // SAFETY: the flag was clear, so this is the only reference to the value ever made.
+        let slot = unsafe { &mut *self.value.get() };
+        Ok(slot.write(value))
+    }
+
+    /// Returns `true` once the slot has been filled.
+    pub fn is_taken(&self) -> bool {
+        self.taken.load(Ordering::Acquire)
+    }
+}
+
+impl<T> Default for StaticSlot<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,147 @@
+//! Saving the cells under an overlay and putting them back when it closes.
+
+use crate::consts::ROW_OFFSETS;
+use crate::frame::{MAX_COLS, MAX_ROWS};
+use crate::{Frame, Lcd, Rect};
+
+/// The cells of a rectangle, saved before something is drawn over them.
+///
+/// Taken with [`Lcd::save_region`] or [`Frame::save_region`] and put back with
+/// [`Lcd::restore`] or [`Frame::restore`]. A popup, a toast or an editor drawn straight on the
+/// display can save the cells it covers when it opens and restore exactly those when it
+/// closes, instead of having the application redraw the whole screen. Restoring on the display
+/// only sends the cells that differ from what it shows.
+///
+/// # Example
+///
+/// ```ignore
+/// let covered = lcd.save_region(Rect::new(0, 1, 16, 1))?;
+/// let mut editor = NumberEditor::new(5.0, 30.0, 0.5).with_decimals(1).with_unit("C");
+/// while editor.state() == EditState::Editing {
+///     if let Some(event) = keypad.next_event()? {
+///         editor.handle(event);
+///     }
+///     editor.render(&mut lcd, 0, 1)?;
+/// }
+/// lcd.restore(&covered)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionSnapshot {
+    rect: Rect,
+    cells: [[u8; MAX_COLS]; MAX_ROWS],
+}
+
+impl RegionSnapshot {
+    /// Returns the rectangle the snapshot covers.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Returns the saved character code of a cell, relative to the top left corner of the
+    /// rectangle, or `None` if it is outside it.
+    pub fn get(&self, col: u8, row: u8) -> Option<u8> {
+        if col >= self.rect.width || row >= self.rect.height {
+            return None;
+        }
+        Some(self.cells[row as usize][col as usize])
+    }
+
+    /// Returns the cells of the rectangle with their position on the display.
+    fn cells(&self) -> impl Iterator<Item = (u8, u8, u8)> + '_ {
+        let Rect {
+            col,
+            row,
+            width,
+            height,
+        } = self.rect;
+        (0..height).flat_map(move |y| {
+            (0..width).map(move |x| (col + x, row + y, self.cells[y as usize][x as usize]))
+        })
+    }
+}
+
+impl Frame {
+    /// Saves the cells of a rectangle, clipped to the frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `rect` - The cells to save.
+    pub fn save_region(&self, rect: Rect) -> RegionSnapshot {
+        let rect = Rect::new(
+            rect.col.min(self.cols()),
+            rect.row.min(self.rows()),
+            rect.width.min(self.cols().saturating_sub(rect.col)),
+            rect.height.min(self.rows().saturating_sub(rect.row)),
+        );
+        let mut cells = [[b' '; MAX_COLS]; MAX_ROWS];
+        for y in 0..rect.height {
+            let row = self.row(rect.row + y);
+            let start = rect.col as usize;
+            cells[y as usize][..rect.width as usize]
+                .copy_from_slice(&row[start..start + rect.width as usize]);
+        }
+        RegionSnapshot { rect, cells }
+    }
+
+    /// Puts back the cells saved with [`save_region`](Self::save_region).
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The saved cells.
+    pub fn restore(&mut self, snapshot: &RegionSnapshot) {
+        for (col, row, code) in snapshot.cells() {
+            self.set(col, row, code);
+        }
+    }
+}
+
+impl Lcd<'_> {
+    /// Saves the cells of a rectangle as the display shows them, taken from the shadow buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `rect` - The cells to save.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RegionSnapshot)` - The saved cells.
+    /// * `Err(anyhow::Error)` - If the rectangle does not fit on the display.
+    pub fn save_region(&self, rect: Rect) -> anyhow::Result<RegionSnapshot> {
+        if !rect.fits(self.cols(), self.rows()) {
+            return Err(anyhow::anyhow!("Region does not fit on the display"));
+        }
+        let mut cells = [[b' '; MAX_COLS]; MAX_ROWS];
+        for y in 0..rect.height {
+            let start = (ROW_OFFSETS[(rect.row + y) as usize] + rect.col) as usize;
+            cells[y as usize][..rect.width as usize]
+                .copy_from_slice(&self.shadow.ddram[start..start + rect.width as usize]);
+        }
+        Ok(RegionSnapshot { rect, cells })
+    }
+
+    /// Puts back the cells saved with [`save_region`](Self::save_region), sending only those
+    /// that changed since, and returns the cursor to where it was.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The saved cells.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the cells are restored.
+    /// * `Err(anyhow::Error)` - If the rectangle no longer fits on the display, or there is an error while sending data.
+    pub fn restore(&mut self, snapshot: &RegionSnapshot) -> anyhow::Result<()> {
+        if !snapshot.rect.fits(self.cols(), self.rows()) {
+            return Err(anyhow::anyhow!("Region does not fit on the display"));
+        }
+        let (cursor_col, cursor_row) = self.cursor_position();
+        for (col, row, code) in snapshot.cells() {
+            let address = ROW_OFFSETS[row as usize] + col;
+            if self.shadow.ddram[address as usize] != code {
+                self.set_cursor(col, row)?;
+                self.write_byte(code)?;
+            }
+        }
+        self.set_cursor(cursor_col, cursor_row)
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(feature = "buffer")]
+use crate::consts::ROW_OFFSETS;
+use crate::{Command, Lcd, TextDirection};
+
+/// The settings of a display, captured with [`Lcd::capture_state`] and restored with
+/// [`Lcd::apply_state`].
+///
+/// Holds the backlight, brightness, display, cursor and blink settings, the entry mode and the
+/// cursor position. With the `buffer` feature it also holds the screen content, taken from the
+/// shadow buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayState {
+    backlight: bool,
+    brightness: u8,
+    display: bool,
+    cursor: bool,
+    blink: bool,
+    autoscroll: bool,
+    direction: TextDirection,
+    position: (u8, u8),
+    #[cfg(feature = "buffer")]
+    ddram: [u8; 0x80],
+}
+
+impl Lcd<'_> {
+    /// Captures the current settings, for restoring after the controller has lost them.
+    pub fn capture_state(&self) -> DisplayState {
+        DisplayState {
+            backlight: self.is_backlight_on(),
+            brightness: self.brightness(),
+            display: self.is_display_on(),
+            cursor: self.is_cursor_on(),
+            blink: self.is_blink_on(),
+            autoscroll: self.is_autoscroll_on(),
+            direction: self.entry_direction(),
+            position: self.cursor_position(),
+            #[cfg(feature = "buffer")]
+            ddram: self.shadow.ddram,
+        }
+    }
+
+    /// Restores settings captured with [`capture_state`](Self::capture_state).
+    ///
+    /// The custom characters are reloaded first, see [`reload_cgram`](Self::reload_cgram), and
+    /// with the `buffer` feature the screen content is rewritten. Then the entry mode, the display, cursor and blink settings, the
+    /// backlight and the cursor position are restored.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The settings to restore.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the settings are restored.
+    /// * `Err(anyhow::Error)` - If the geometry has changed so the cursor position is out of bounds, or there is an error while sending data.
+    pub fn apply_state(&mut self, state: &DisplayState) -> anyhow::Result<()> {
+        self.reload_cgram()?;
+        #[cfg(feature = "buffer")]
+        {
+            // The screen is rewritten left to right, whatever the captured entry mode.
+            self.raw_command(Command::EntryMode {
+                increment: true,
+                shift: false,
+            })?;
+            for &offset in ROW_OFFSETS.iter().take(self.panel_rows() as usize) {
+                self.raw_command(Command::SetDdram(offset))?;
+                let start = offset as usize;
+                for &code in &state.ddram[start..start + self.cols() as usize] {
+                    self.write_byte(code)?;
+                }
+            }
+        }
+
+        self.raw_command(Command::EntryMode {
+            increment: state.direction == TextDirection::LeftToRight,
+            shift: state.autoscroll,
+        })?;
+        self.raw_command(Command::DisplayControl {
+            display: state.display,
+            cursor: state.cursor,
+            blink: state.blink,
+        })?;
+        if state.backlight {
+            self.set_brightness(state.brightness.max(1))?;
+        } else {
+            self.backlight_off()?;
+        }
+        let (col, row) = state.position;
+        self.set_cursor(col, row)
+    }
+
+    /// Initializes the controller again and restores the settings it had, e.g. after the
+    /// display lost power or the bus was recovered.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the display is initialized and its settings restored.
+    /// * `Err(anyhow::Error)` - If there is an error during initialization or while sending data.
+    pub fn reinit(&mut self) -> anyhow::Result<()> {
+        let state = self.capture_state();
+        self.init()?;
+        self.apply_state(&state)
+    }
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+/// Counters describing the I2C traffic sent to the display.
+///
+/// Returned by [`Lcd::stats`](crate::Lcd::stats) and cleared by
+/// [`Lcd::reset_stats`](crate::Lcd::reset_stats). Compare snapshots taken before and after a
+/// change to measure the effect of batching or buffering on bus load.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Bytes written to the port expander, including failed attempts.
+    pub bytes_written: u32,
+    /// I2C write transactions issued, including failed attempts.
+    pub transactions: u32,
+    /// I2C read transactions issued, for display readback and port checks.
+    pub reads: u32,
+    /// Transactions that were repeated after a failed write, plus characters rewritten
+    /// because verification read back a different code.
+    pub retries: u32,
+    /// The longest time a single I2C transaction, write or read, took.
+    pub worst_transaction: Duration,
+    /// The longest time a [`RefreshScheduler`](crate::RefreshScheduler) refresh took, from its
+    /// first changed cell to its last (`buffer` feature).
+    ///
+    /// The scheduler sends only the cells that changed, so a full-screen redraw is its
+    /// slowest case. Zero without the scheduler.
+    pub worst_flush: Duration,
+}
+
+impl Stats {
+    pub(crate) fn record_transaction(&mut self, bytes: usize, elapsed: Duration) {
+        self.bytes_written = self.bytes_written.saturating_add(bytes as u32);
+        self.transactions = self.transactions.saturating_add(1);
+        self.worst_transaction = self.worst_transaction.max(elapsed);
+    }
+
+    pub(crate) fn record_read(&mut self, elapsed: Duration) {
+        self.reads = self.reads.saturating_add(1);
+        self.worst_transaction = self.worst_transaction.max(elapsed);
+    }
+
+    #[cfg(feature = "buffer")]
+    pub(crate) fn record_flush(&mut self, elapsed: Duration) {
+        self.worst_flush = self.worst_flush.max(elapsed);
+    }
+}
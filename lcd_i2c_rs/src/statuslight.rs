@@ -0,0 +1,275 @@
+use crate::{AnimClock, ColorDimmer, FadeCurve, Moment};
+use std::time::Duration;
+
+/// A backlight color, as red, green and blue channel levels.
+///
+/// Bi-color modules use the red and green channels only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Color(pub [u8; 3]);
+
+impl Color {
+    /// All channels off.
+    pub const OFF: Color = Color::new(0, 0, 0);
+    /// Red, e.g. for a fault.
+    pub const RED: Color = Color::new(255, 0, 0);
+    /// Green, e.g. for OK.
+    pub const GREEN: Color = Color::new(0, 255, 0);
+    /// Red and green mixed, e.g. for a warning.
+    pub const AMBER: Color = Color::new(255, 128, 0);
+    /// Blue, on RGB modules.
+    pub const BLUE: Color = Color::new(0, 0, 255);
+    /// All channels on.
+    pub const WHITE: Color = Color::new(255, 255, 255);
+
+    const NAMED: [(Color, &'static str); 6] = [
+        (Color::OFF, "off"),
+        (Color::RED, "red"),
+        (Color::GREEN, "green"),
+        (Color::AMBER, "amber"),
+        (Color::BLUE, "blue"),
+        (Color::WHITE, "white"),
+    ];
+
+    /// Creates a color from its channel levels.
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self([red, green, blue])
+    }
+
+    /// Returns the name of the color if it is one of the named constants, e.g. for showing it
+    /// on a status screen.
+    pub fn name(&self) -> Option<&'static str> {
+        Self::NAMED
+            .iter()
+            .find(|(color, _)| color == self)
+            .map(|(_, name)| *name)
+    }
+
+    /// Returns the color a fractional way from this one to `other`, along `curve`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The color to blend towards.
+    /// * `progress` - How far towards `other`, from 0.0 to 1.0.
+    /// * `curve` - How each channel moves between its levels.
+    pub fn blend(self, other: Color, progress: f32, curve: FadeCurve) -> Color {
+        let mut levels = self.0;
+        for (level, to) in levels.iter_mut().zip(other.0) {
+            *level = curve.interpolate(*level, to, progress);
+        }
+        Color(levels)
+    }
+}
+
+/// What a [`StatusLight`] is doing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Signal {
+    Steady,
+    Blend {
+        from: Color,
+        to: Color,
+        duration: Duration,
+    },
+    Blink {
+        on: Color,
+        off: Color,
+        half: Duration,
+    },
+    Pulse {
+        from: Color,
+        to: Color,
+        half: Duration,
+    },
+}
+
+/// Shows status on a backlight with several LED channels, such as a red/green module: green
+/// for OK, red for a fault, blinking or pulsing for attention.
+///
+/// Blends and patterns advance on each call to [`tick`](Self::tick), which should be made from
+/// the main loop every few tens of milliseconds; starting a new one replaces the last.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut light = StatusLight::new(&mut bicolor);
+/// light.set(Color::GREEN)?;
+/// loop {
+///     if sensor_failed() {
+///         light.blink(Color::RED, Color::OFF, Duration::from_millis(250))?;
+///     }
+///     light.tick()?;
+/// }
+/// ```
+pub struct StatusLight<'d> {
+    dimmer: &'d mut dyn ColorDimmer,
+    curve: FadeCurve,
+    color: Color,
+    signal: Signal,
+    started: Moment,
+}
+
+impl<'d> StatusLight<'d> {
+    /// Creates a status light, with its channels assumed off, blending along
+    /// [`FadeCurve::Cie1931`].
+    ///
+    /// # Arguments
+    ///
+    /// * `dimmer` - The outputs driving the channels.
+    pub fn new(dimmer: &'d mut dyn ColorDimmer) -> Self {
+        Self {
+            dimmer,
+            curve: FadeCurve::Cie1931,
+            color: Color::OFF,
+            signal: Signal::Steady,
+            started: AnimClock::now(),
+        }
+    }
+
+    /// Sets the curve blends and pulses follow.
+    ///
+    /// # Arguments
+    ///
+    /// * `curve` - The curve each channel moves along.
+    pub fn with_curve(mut self, curve: FadeCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Returns the color shown.
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Returns `true` while a blend, blink or pulse is playing.
+    pub fn is_animating(&self) -> bool {
+        self.signal != Signal::Steady
+    }
+
+    /// Shows a color at once, stopping any blend or pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The color to show.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the color is shown.
+    /// * `Err(anyhow::Error)` - If the dimmer fails.
+    pub fn set(&mut self, color: Color) -> anyhow::Result<()> {
+        self.start(Signal::Steady);
+        self.show(color)
+    }
+
+    /// Blends from the color shown to another.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The color to end at.
+    /// * `duration` - How long the blend takes.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the blend is started.
+    /// * `Err(anyhow::Error)` - If the dimmer fails.
+    pub fn blend_to(&mut self, color: Color, duration: Duration) -> anyhow::Result<()> {
+        self.start(Signal::Blend {
+            from: self.color,
+            to: color,
+            duration,
+        });
+        self.tick().map(|_| ())
+    }
+
+    /// Switches between two colors until another color or pattern is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `on` - The color shown first.
+    /// * `off` - The color shown in between, often [`Color::OFF`].
+    /// * `half` - How long each color is shown.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the pattern is started.
+    /// * `Err(anyhow::Error)` - If the dimmer fails.
+    pub fn blink(&mut self, on: Color, off: Color, half: Duration) -> anyhow::Result<()> {
+        self.start(Signal::Blink { on, off, half });
+        self.tick().map(|_| ())
+    }
+
+    /// Blends back and forth between two colors until another color or pattern is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The color the pulse starts at.
+    /// * `to` - The color it blends to and back from.
+    /// * `half` - How long each blend takes.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the pattern is started.
+    /// * `Err(anyhow::Error)` - If the dimmer fails.
+    pub fn pulse(&mut self, from: Color, to: Color, half: Duration) -> anyhow::Result<()> {
+        self.start(Signal::Pulse { from, to, half });
+        self.tick().map(|_| ())
+    }
+
+    /// Advances a running blend or pattern.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `true` if the color changed.
+    /// * `Err(anyhow::Error)` - If the dimmer fails.
+    pub fn tick(&mut self) -> anyhow::Result<bool> {
+        let elapsed = self.started.elapsed();
+        let color = match self.signal {
+            Signal::Steady => return Ok(false),
+            Signal::Blend { to, duration, .. } if elapsed >= duration => {
+                self.signal = Signal::Steady;
+                to
+            }
+            Signal::Blend { from, to, duration } => {
+                let progress = elapsed.as_secs_f32() / duration.as_secs_f32();
+                from.blend(to, progress, self.curve)
+            }
+            Signal::Blink { on, off, half } => match Self::half_periods(elapsed, half) % 2 {
+                0 => on,
+                _ => off,
+            },
+            Signal::Pulse { from, to, half } => {
+                let halves = Self::half_periods(elapsed, half);
+                let into = elapsed.as_secs_f32() - halves as f32 * half.as_secs_f32();
+                let progress = into / half.as_secs_f32().max(f32::EPSILON);
+                match halves % 2 {
+                    0 => from.blend(to, progress, self.curve),
+                    _ => to.blend(from, progress, self.curve),
+                }
+            }
+        };
+        if color == self.color {
+            return Ok(false);
+        }
+        self.show(color)?;
+        Ok(true)
+    }
+
+    /// Releases the outputs.
+    pub fn into_inner(self) -> &'d mut dyn ColorDimmer {
+        self.dimmer
+    }
+
+    fn start(&mut self, signal: Signal) {
+        self.signal = signal;
+        self.started = AnimClock::now();
+    }
+
+    fn show(&mut self, color: Color) -> anyhow::Result<()> {
+        self.dimmer.set_levels(color.0)?;
+        self.color = color;
+        Ok(())
+    }
+
+    /// Returns how many whole `half` periods fit in `elapsed`.
+    fn half_periods(elapsed: Duration, half: Duration) -> u128 {
+        elapsed.as_nanos() / half.as_nanos().max(1)
+    }
+}
@@ -0,0 +1,171 @@
+use crate::Lcd;
+
+/// The most rows and columns a stream keeps for scrolling.
+const MAX_ROWS: usize = 4;
+const MAX_COLS: usize = 40;
+
+/// What a stream does when it reaches the end of the last row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// Continue at the top, overwriting the first row, like
+    /// [`Lcd::print_long_str`](crate::Lcd::print_long_str).
+    #[default]
+    Wrap,
+    /// Drop the rest of the stream.
+    Stop,
+    /// Move all rows up by one and continue on a blank last row, like a terminal.
+    Scroll,
+}
+
+enum Item {
+    Code(u8),
+    Newline,
+}
+
+/// The state of one [`Lcd::print_iter`] or [`Lcd::print_bytes`] call.
+///
+/// The rows written so far are kept so that [`Overflow::Scroll`] can redraw them one row up
+/// without reading the display back.
+struct Stream {
+    lines: [[u8; MAX_COLS]; MAX_ROWS],
+    rows: usize,
+    cols: usize,
+    row: usize,
+    col: usize,
+    overflow: Overflow,
+    after_newline: bool,
+}
+
+impl Stream {
+    fn new(rows: u8, cols: u8, overflow: Overflow) -> Self {
+        Self {
+            lines: [[b' '; MAX_COLS]; MAX_ROWS],
+            rows: (rows as usize).min(MAX_ROWS),
+            cols: (cols as usize).min(MAX_COLS),
+            row: 0,
+            col: 0,
+            overflow,
+            after_newline: false,
+        }
+    }
+
+    /// Prints one item, returning `false` once the stream has to stop.
+    fn put(&mut self, lcd: &mut Lcd, item: Item) -> anyhow::Result<bool> {
+        match item {
+            Item::Code(code) => {
+                if self.col >= self.cols && !self.next_row(lcd)? {
+                    return Ok(false);
+                }
+                lcd.write_byte(code)?;
+                self.lines[self.row][self.col] = code;
+                self.col += 1;
+                self.after_newline = false;
+            }
+            Item::Newline => {
+                // A newline right after a full row only ends that row.
+                if self.col >= self.cols && self.after_newline && !self.next_row(lcd)? {
+                    return Ok(false);
+                }
+                while self.col < self.cols {
+                    lcd.write_byte(b' ')?;
+                    self.lines[self.row][self.col] = b' ';
+                    self.col += 1;
+                }
+                self.after_newline = true;
+            }
+        }
+        Ok(true)
+    }
+
+    fn next_row(&mut self, lcd: &mut Lcd) -> anyhow::Result<bool> {
+        if self.row + 1 < self.rows {
+            self.row += 1;
+        } else {
+            match self.overflow {
+                Overflow::Wrap => self.row = 0,
+                Overflow::Stop => return Ok(false),
+                Overflow::Scroll => {
+                    self.lines.copy_within(1..self.rows, 0);
+                    self.lines[self.rows - 1] = [b' '; MAX_COLS];
+                    for row in 0..self.rows {
+                        lcd.set_cursor(0, row as u8)?;
+                        for col in 0..self.cols {
+                            lcd.write_byte(self.lines[row][col])?;
+                        }
+                    }
+                }
+            }
+        }
+        self.col = 0;
+        lcd.set_cursor(0, self.row as u8)?;
+        Ok(true)
+    }
+}
+
+impl Lcd<'_> {
+    /// Prints characters as an iterator produces them, starting from the home position (0,0).
+    ///
+    /// Nothing is collected first, so the text can come straight from flash, a decompressor or
+    /// any other source too large for RAM. Lines wrap at the display width, `'\n'` ends a line,
+    /// and `overflow` decides what happens after the last row.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars` - The characters to print.
+    /// * `overflow` - What to do when the display is full.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the characters are successfully printed, or printing stopped under [`Overflow::Stop`].
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character or setting the cursor.
+    pub fn print_iter<I>(&mut self, chars: I, overflow: Overflow) -> anyhow::Result<()>
+    where
+        I: IntoIterator<Item = char>,
+    {
+        let mut stream = Stream::new(self.rows(), self.cols(), overflow);
+        self.set_cursor(0, 0)?;
+        for ch in chars {
+            let item = match ch {
+                '\n' => Item::Newline,
+                _ => match self.charmap().encode(ch)? {
+                    Some(code) => Item::Code(code),
+                    None => continue,
+                },
+            };
+            if !stream.put(self, item)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints raw character codes, like [`print_iter`](Self::print_iter) but without the
+    /// substitution table.
+    ///
+    /// `b'\n'` ends a line. Custom character 2 stays reachable as code 0x02, since codes 0x08 to
+    /// 0x0f only mirror custom characters 0 to 7.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The character codes to print.
+    /// * `overflow` - What to do when the display is full.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the codes are successfully printed, or printing stopped under [`Overflow::Stop`].
+    /// * `Err(anyhow::Error)` - If there is an error while printing any code or setting the cursor.
+    pub fn print_bytes(&mut self, bytes: &[u8], overflow: Overflow) -> anyhow::Result<()> {
+        let mut stream = Stream::new(self.rows(), self.cols(), overflow);
+        self.set_cursor(0, 0)?;
+        for &code in bytes {
+            let item = match code {
+                b'\n' => Item::Newline,
+                _ => Item::Code(code),
+            };
+            if !stream.put(self, item)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
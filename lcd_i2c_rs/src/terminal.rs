@@ -0,0 +1,154 @@
+use crate::{Frame, Lcd, Screenshot};
+use std::io::Write;
+
+/// Draws the display's content in a terminal, for developing screens on the host.
+///
+/// Each call draws a box the size of the display with its characters inside. With ANSI
+/// output, the default, the box is redrawn in place so the terminal shows the display live,
+/// the cells are coloured like a lit or unlit backlight, and custom characters are shown as
+/// their slot number in inverse video. Half-width katakana and the common A00 ROM symbols are
+/// shown as their Unicode equivalents; other codes are shown as `?`.
+///
+/// Pair it with a headless [`Lcd`] (see [`Lcd::probe`]), whose shadow buffer keeps track of
+/// what a real display would show, or draw [`Frame`]s directly.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut view = TerminalView::new(std::io::stdout());
+/// loop {
+///     app.update(&mut lcd)?;
+///     view.draw(&lcd)?;
+///     std::thread::sleep(Duration::from_millis(50));
+/// }
+/// ```
+pub struct TerminalView<W: Write> {
+    writer: W,
+    ansi: bool,
+    drawn_rows: Option<u8>,
+}
+
+impl<W: Write> TerminalView<W> {
+    /// Creates a view writing ANSI output to `writer`, usually standard output.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            ansi: true,
+            drawn_rows: None,
+        }
+    }
+
+    /// Turns ANSI colours and in-place redrawing on or off. Without them each draw appends a
+    /// plain box, which suits logs and test output.
+    pub fn with_ansi(mut self, ansi: bool) -> Self {
+        self.ansi = ansi;
+        self
+    }
+
+    /// Draws what the display shows, with its backlight state.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display, usually headless.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the box is written.
+    /// * `Err(anyhow::Error)` - If writing fails.
+    pub fn draw(&mut self, lcd: &Lcd) -> anyhow::Result<()> {
+        self.draw_screenshot(&lcd.screenshot(), lcd.is_backlight_on())
+    }
+
+    /// Draws a screenshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `screenshot` - The content to draw.
+    /// * `backlight` - Whether to colour the cells as lit.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the box is written.
+    /// * `Err(anyhow::Error)` - If writing fails.
+    pub fn draw_screenshot(
+        &mut self,
+        screenshot: &Screenshot,
+        backlight: bool,
+    ) -> anyhow::Result<()> {
+        self.draw_rows(screenshot.cols(), screenshot.rows(), backlight, |row| {
+            screenshot.row(row)
+        })
+    }
+
+    /// Draws a frame, coloured as lit.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The frame to draw.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the box is written.
+    /// * `Err(anyhow::Error)` - If writing fails.
+    pub fn draw_frame(&mut self, frame: &Frame) -> anyhow::Result<()> {
+        self.draw_rows(frame.cols(), frame.rows(), true, |row| frame.row(row))
+    }
+
+    /// Returns the writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn draw_rows<'r>(
+        &mut self,
+        cols: u8,
+        rows: u8,
+        backlight: bool,
+        row: impl Fn(u8) -> &'r [u8],
+    ) -> anyhow::Result<()> {
+        let w = &mut self.writer;
+        if self.ansi {
+            if let Some(drawn) = self.drawn_rows {
+                write!(w, "\x1b[{}A\r", drawn as u16 + 2)?;
+            }
+        }
+        let border = "\u{2500}".repeat(cols as usize);
+        writeln!(w, "\u{250c}{}\u{2510}", border)?;
+        for r in 0..rows {
+            write!(w, "\u{2502}")?;
+            if self.ansi {
+                let colours = if backlight { "30;42" } else { "32;40" };
+                write!(w, "\x1b[{}m", colours)?;
+            }
+            for &code in row(r) {
+                match code {
+                    0x00..=0x07 if self.ansi => write!(w, "\x1b[7m{}\x1b[27m", code)?,
+                    0x00..=0x07 => write!(w, "{}", code)?,
+                    _ => write!(w, "{}", Self::glyph(code))?,
+                }
+            }
+            if self.ansi {
+                write!(w, "\x1b[0m")?;
+            }
+            writeln!(w, "\u{2502}")?;
+        }
+        writeln!(w, "\u{2514}{}\u{2518}", border)?;
+        w.flush()?;
+        self.drawn_rows = Some(rows);
+        Ok(())
+    }
+
+    /// Returns the closest Unicode character to an A00 ROM character code.
+    fn glyph(code: u8) -> char {
+        match code {
+            0x20..=0x7d => code as char,
+            0x7e => '\u{2192}',
+            0x7f => '\u{2190}',
+            0xa1..=0xde => char::from_u32(0xff61 + (code - 0xa1) as u32).unwrap_or('?'),
+            0xdf => '\u{b0}',
+            0xe4 => '\u{3bc}',
+            0xff => '\u{2588}',
+            _ => '?',
+        }
+    }
+}
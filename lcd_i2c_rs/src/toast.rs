@@ -0,0 +1,255 @@
+use crate::charset::DisplayWidth;
+use crate::frame::MAX_COLS;
+use crate::scroll::Font;
+use crate::{AnimClock, Frame, Moment, RefreshScheduler, ScrollUp};
+use std::time::Duration;
+
+/// The most toasts waiting to be shown, including the one shown.
+const MAX_TOASTS: usize = 4;
+
+/// One queued toast: its line, already centered, and how long it stays.
+struct Toast {
+    line: Frame,
+    duration: Duration,
+}
+
+/// An empty place in the queue.
+const NO_TOAST: Option<Toast> = None;
+
+/// The toasts of a [`RefreshScheduler`], shown one after the other on one row.
+pub(crate) struct Toasts {
+    queue: [Option<Toast>; MAX_TOASTS],
+    started: Option<Moment>,
+    row: Option<u8>,
+    /// Slides each toast, and the row it gives back, up into the row.
+    scroll: ScrollUp,
+    /// What the row showed when the scroll started.
+    scrolled_out: [u8; MAX_COLS],
+    /// The row at the current frame of the scroll, while it runs.
+    shifted: Option<[u8; MAX_COLS]>,
+    /// When the scroll last moved to a frame.
+    stepped: Option<Moment>,
+    /// The presented frame with the current toast on its row.
+    pub(crate) frame: Option<Frame>,
+}
+
+impl Toasts {
+    pub(crate) const fn new() -> Self {
+        Self {
+            queue: [NO_TOAST; MAX_TOASTS],
+            started: None,
+            row: None,
+            scroll: ScrollUp::new(0),
+            scrolled_out: [b' '; MAX_COLS],
+            shifted: None,
+            stepped: None,
+            frame: None,
+        }
+    }
+}
+
+impl RefreshScheduler<'_> {
+    /// Shows toasts on `row` instead of the bottom row.
+    pub fn with_toast_row(mut self, row: u8) -> Self {
+        self.toasts.row = Some(row);
+        self
+    }
+
+    /// Slides each toast up into its row, and the row's own content back up when the last
+    /// toast ends, instead of swapping them at once.
+    ///
+    /// The scroll is a [`ScrollUp`] of `steps` frames, one per refresh interval (see
+    /// [`with_max_rate`](Self::with_max_rate)) once the previous frame is shown, drawn through
+    /// custom characters built from `font`. A frame
+    /// that needs more CGRAM slots than there are, or a character without a bitmap, makes the
+    /// toast appear at once. Frames shown [`upside down`](Self::with_upside_down) are not scrolled.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - The number of frames a scroll takes.
+    /// * `font` - Returns the 5x8 bitmap of a character code as the display shows it.
+    /// * `first_slot` - The first CGRAM location the scroll may use; it uses every one from there to 7.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut scheduler = RefreshScheduler::new(lcd).with_toast_scroll(4, font, 4);
+    /// scheduler.toast("Saved", Duration::from_secs(2))?;
+    /// ```
+    pub fn with_toast_scroll(mut self, steps: u8, font: Font, first_slot: u8) -> Self {
+        self.toasts.scroll = ScrollUp::new(steps).with_smooth(font, first_slot);
+        self
+    }
+
+    /// Briefly shows a message over one row, the bottom one unless set with
+    /// [`with_toast_row`](Self::with_toast_row), then restores what the row showed.
+    ///
+    /// The message is centered on the row. A toast raised while another is shown waits its
+    /// turn, so a burst of events shows every message; up to four toasts can be queued. The
+    /// rest of the display keeps following the presented frames. An [`alert`](Self::alert) or
+    /// a [`Screensaver`](crate::Screensaver) hides the toast while it is up, without pausing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The message, truncated to the width of the display.
+    /// * `duration` - How long the toast is shown once its turn comes.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the toast is queued.
+    /// * `Err(anyhow::Error)` - If four toasts are already queued, or a character cannot be shown and the fallback policy is [`Fallback::Error`](crate::charset::Fallback::Error).
+    pub fn toast(&mut self, text: &str, duration: Duration) -> anyhow::Result<()> {
+        let Some(free) = self.toasts.queue.iter().position(Option::is_none) else {
+            return Err(anyhow::anyhow!("Toast queue full"));
+        };
+        let cols = self.front.cols();
+        let charmap = *self.lcd.charmap();
+        let mut line = Frame::new(cols, 1);
+        let width = text.display_width(&charmap).min(cols as usize) as u8;
+        line.print((cols - width) / 2, 0, text, &charmap)?;
+        if free == 0 {
+            self.start_toast_scroll();
+        }
+        self.toasts.queue[free] = Some(Toast { line, duration });
+        if free == 0 {
+            self.toasts.started = Some(AnimClock::now());
+            self.scroll_toast()?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` while a toast is shown.
+    pub fn is_toasting(&self) -> bool {
+        self.toasts.queue[0].is_some()
+    }
+
+    /// Removes the toast shown and all queued ones, restoring the row.
+    pub fn clear_toasts(&mut self) {
+        self.toasts.queue = [NO_TOAST; MAX_TOASTS];
+        self.toasts.started = None;
+        self.toasts.scroll.finish();
+        self.toasts.shifted = None;
+        self.compose_toast();
+    }
+
+    /// Ends the toast shown once it expires and starts the next one, and draws the next frame
+    /// of a running scroll once the previous one is shown and the refresh interval has passed.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the toasts are up to date.
+    /// * `Err(anyhow::Error)` - If there is an error while creating the characters of a scroll frame.
+    pub(crate) fn update_toasts(&mut self) -> anyhow::Result<()> {
+        let expired = match (&self.toasts.queue[0], self.toasts.started) {
+            (Some(toast), Some(started)) => started.elapsed() >= toast.duration,
+            _ => false,
+        };
+        if expired {
+            self.start_toast_scroll();
+            self.toasts.queue.rotate_left(1);
+            self.toasts.queue[MAX_TOASTS - 1] = None;
+            self.toasts.started = self.toasts.queue[0].as_ref().map(|_| AnimClock::now());
+            return self.scroll_toast();
+        }
+        let due = |stepped: Moment| stepped.elapsed() >= self.interval;
+        if self.toasts.scroll.is_running() && self.shown && self.toasts.stepped.is_some_and(due) {
+            return self.scroll_toast();
+        }
+        Ok(())
+    }
+
+    /// Returns the row toasts are shown on.
+    fn toast_row(&self) -> u8 {
+        self.toasts
+            .row
+            .unwrap_or(self.pending.rows().saturating_sub(1))
+    }
+
+    /// Returns what the toast row shows once any scroll ends: the current toast, else the
+    /// row of the presented frame.
+    fn toast_line(&self) -> &[u8] {
+        match &self.toasts.queue[0] {
+            Some(toast) => toast.line.row(0),
+            None => self.pending.row(self.toast_row()),
+        }
+    }
+
+    /// Remembers what the toast row shows and starts a scroll, if toasts scroll, to slide
+    /// what comes next up over it.
+    fn start_toast_scroll(&mut self) {
+        if !self.toasts.scroll.is_smooth() || self.rotation.is_some() {
+            return;
+        }
+        let line = self.toast_line();
+        let mut scrolled_out = [b' '; MAX_COLS];
+        scrolled_out[..line.len()].copy_from_slice(line);
+        self.toasts.scrolled_out = scrolled_out;
+        self.toasts.scroll.start();
+    }
+
+    /// Moves a running scroll to its next frame and puts the result over the presented frame.
+    ///
+    /// A frame that cannot be drawn ends the scroll, showing the row as it ends up.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the frame is composed.
+    /// * `Err(anyhow::Error)` - If there is an error while creating the characters; the scroll ends.
+    fn scroll_toast(&mut self) -> anyhow::Result<()> {
+        let offset = self.toasts.scroll.advance();
+        self.toasts.stepped = Some(AnimClock::now());
+        // The last frame shows the row as it ends up, without custom characters.
+        let shifted = if self.toasts.scroll.is_running() {
+            self.shifted_toast_line(offset)
+        } else {
+            Ok(None)
+        };
+        self.toasts.shifted = shifted.as_ref().ok().copied().flatten();
+        if self.toasts.shifted.is_none() {
+            self.toasts.scroll.finish();
+        }
+        self.compose_toast();
+        shifted.map(drop)
+    }
+
+    /// Returns the toast row moved up by `offset` pixel rows over the top of what comes next,
+    /// loading the custom characters it takes.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some([u8; MAX_COLS]))` - The codes of the row.
+    /// * `Ok(None)` - If a character has no bitmap or the row needs more CGRAM slots than the scroll may use.
+    /// * `Err(anyhow::Error)` - If there is an error while creating the characters.
+    fn shifted_toast_line(&mut self, offset: u8) -> anyhow::Result<Option<[u8; MAX_COLS]>> {
+        let Some(mut glyphs) = self.toasts.scroll.glyphs(offset) else {
+            return Ok(None);
+        };
+        let mut shifted = [b' '; MAX_COLS];
+        let cells = shifted.iter_mut().zip(&self.toasts.scrolled_out);
+        for ((code, &above), &below) in cells.zip(self.toast_line()) {
+            match glyphs.cell(above, below) {
+                Some(cell) => *code = cell,
+                None => return Ok(None),
+            }
+        }
+        glyphs.upload(&mut self.lcd)?;
+        Ok(Some(shifted))
+    }
+
+    /// Puts the current toast, or the current frame of a scroll, over the presented frame, or
+    /// removes it when there is neither.
+    pub(crate) fn compose_toast(&mut self) {
+        let row = self.toast_row();
+        let line = match (&self.toasts.shifted, &self.toasts.queue[0]) {
+            (Some(shifted), _) => Some(&shifted[..]),
+            (None, Some(toast)) => Some(toast.line.row(0)),
+            (None, None) => None,
+        };
+        self.toasts.frame = line.map(|line| {
+            let mut frame = self.pending.clone();
+            frame.write_bytes(0, row, line);
+            frame
+        });
+        self.shown = *self.source() == self.front;
+    }
+}
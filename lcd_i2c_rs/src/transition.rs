@@ -0,0 +1,167 @@
+use crate::{AnimClock, Frame, Moment, RefreshScheduler};
+use std::time::Duration;
+
+/// An effect for switching from one screen to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transition {
+    /// Replace the screen at once.
+    #[default]
+    Cut,
+    /// Reveal the new screen column by column from the left.
+    Wipe,
+    /// Push the old screen out to the left, the new one following from the right.
+    SlideLeft,
+    /// Push the old screen out to the right, the new one following from the left.
+    SlideRight,
+    /// Replace the cells in a scattered order.
+    Dissolve,
+}
+
+impl Transition {
+    /// Starts the transition between two frames.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The screen shown now, usually [`RefreshScheduler::frame`].
+    /// * `to` - The screen to switch to.
+    /// * `steps` - The number of intermediate frames; [`Transition::Cut`] always takes one.
+    pub fn play(self, from: &Frame, to: &Frame, steps: u8) -> TransitionPlayer {
+        let steps = match self {
+            Transition::Cut => 1,
+            _ => steps.max(1),
+        };
+        TransitionPlayer {
+            transition: self,
+            from: from.clone(),
+            to: to.clone(),
+            steps,
+            step: 0,
+            duration: None,
+            started: None,
+        }
+    }
+}
+
+/// A transition in progress, advanced by [`tick`](Self::tick).
+///
+/// Each step is drawn into the scheduler's frame and presented, so only the cells that change
+/// from one step to the next are sent. The next step is only drawn once the scheduler has
+/// shown the previous one, so the transition runs at the scheduler's refresh rate. Given a
+/// [`with_duration`](TransitionPlayer::with_duration), it takes that long on the
+/// [`AnimClock`] instead, however often it is ticked, skipping steps the display had no time
+/// to show.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut player = Transition::SlideLeft.play(scheduler.frame(), &next_page, 8);
+/// while player.tick(&mut scheduler)? {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransitionPlayer {
+    transition: Transition,
+    from: Frame,
+    to: Frame,
+    steps: u8,
+    step: u8,
+    duration: Option<Duration>,
+    started: Option<Moment>,
+}
+
+impl TransitionPlayer {
+    /// Spreads the steps over `duration`, counted from the first tick.
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Draws the next step if the scheduler is ready for it, and lets the scheduler refresh.
+    ///
+    /// # Arguments
+    ///
+    /// * `scheduler` - The scheduler of the display.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `true` while the transition is still running.
+    /// * `Err(anyhow::Error)` - If there is an error while sending data.
+    pub fn tick(&mut self, scheduler: &mut RefreshScheduler) -> anyhow::Result<bool> {
+        if self.step < self.steps && scheduler.is_idle() {
+            let due = self.due_step();
+            if due > self.step {
+                self.step = due;
+                self.draw(scheduler.frame_mut());
+                scheduler.present();
+            }
+        }
+        scheduler.tick()?;
+        Ok(!self.is_finished() || !scheduler.is_idle())
+    }
+
+    /// Returns `true` once the last step has been drawn.
+    pub fn is_finished(&self) -> bool {
+        self.step >= self.steps
+    }
+
+    /// Returns the step due now: the next one, or with a duration the one its share of the
+    /// time has come for, starting with the first at once.
+    fn due_step(&mut self) -> u8 {
+        let Some(duration) = self.duration else {
+            return self.step + 1;
+        };
+        let started = *self.started.get_or_insert_with(AnimClock::now);
+        let elapsed = started.elapsed().as_millis();
+        let due = elapsed * self.steps as u128 / duration.as_millis().max(1);
+        due.clamp(1, self.steps as u128) as u8
+    }
+
+    /// Draws the current step into a frame.
+    fn draw(&self, frame: &mut Frame) {
+        let cols = self.to.cols();
+        let progress = |span: u8| (span as u16 * self.step as u16 / self.steps as u16) as u8;
+        for row in 0..self.to.rows() {
+            for col in 0..cols {
+                let code = match self.transition {
+                    Transition::Cut => self.to.get(col, row),
+                    Transition::Wipe => {
+                        if col < progress(cols) {
+                            self.to.get(col, row)
+                        } else {
+                            self.from.get(col, row)
+                        }
+                    }
+                    Transition::SlideLeft => {
+                        let shifted = col + progress(cols);
+                        if shifted < cols {
+                            self.from.get(shifted, row)
+                        } else {
+                            self.to.get(shifted - cols, row)
+                        }
+                    }
+                    Transition::SlideRight => {
+                        let offset = progress(cols);
+                        if col >= offset {
+                            self.from.get(col - offset, row)
+                        } else {
+                            self.to.get(cols - offset + col, row)
+                        }
+                    }
+                    Transition::Dissolve => {
+                        if dissolve_order(col, row) < progress(u8::MAX) {
+                            self.to.get(col, row)
+                        } else {
+                            self.from.get(col, row)
+                        }
+                    }
+                };
+                frame.set(col, row, code.unwrap_or(b' '));
+            }
+        }
+    }
+}
+
+/// Spreads the cells over `0..255` in a scattered but repeatable order.
+fn dissolve_order(col: u8, row: u8) -> u8 {
+    let cell = row as u16 * 40 + col as u16;
+    (cell.wrapping_mul(97) % 251) as u8
+}
@@ -0,0 +1,230 @@
+//! Units of measurement for readings, chosen once for the whole product.
+
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+
+/// The degree sign in the A00 character ROM.
+const DEGREE: u8 = 0xdf;
+
+/// What a reading measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    /// Temperature, kept in degrees Celsius.
+    Temperature,
+    /// Air pressure, kept in hectopascals.
+    Pressure,
+    /// Wind or vehicle speed, kept in metres per second.
+    Speed,
+}
+
+/// A unit a reading is shown in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    /// Degrees Celsius, the base unit of temperature.
+    Celsius,
+    /// Degrees Fahrenheit.
+    Fahrenheit,
+    /// Hectopascals, the base unit of pressure.
+    Hectopascal,
+    /// Inches of mercury.
+    InchOfMercury,
+    /// Metres per second, the base unit of speed.
+    MetrePerSecond,
+    /// Kilometres per hour.
+    KilometrePerHour,
+    /// Miles per hour.
+    MilePerHour,
+}
+
+/// Every unit, in declaration order and grouped by dimension.
+static ALL: [Unit; 7] = [
+    Unit::Celsius,
+    Unit::Fahrenheit,
+    Unit::Hectopascal,
+    Unit::InchOfMercury,
+    Unit::MetrePerSecond,
+    Unit::KilometrePerHour,
+    Unit::MilePerHour,
+];
+
+impl Unit {
+    /// Returns the units of a dimension, e.g. to offer them in a settings menu.
+    pub fn of(dimension: Dimension) -> &'static [Unit] {
+        match dimension {
+            Dimension::Temperature => &ALL[..2],
+            Dimension::Pressure => &ALL[2..4],
+            Dimension::Speed => &ALL[4..],
+        }
+    }
+
+    /// Returns what the unit measures.
+    pub const fn dimension(self) -> Dimension {
+        match self {
+            Unit::Celsius | Unit::Fahrenheit => Dimension::Temperature,
+            Unit::Hectopascal | Unit::InchOfMercury => Dimension::Pressure,
+            Unit::MetrePerSecond | Unit::KilometrePerHour | Unit::MilePerHour => Dimension::Speed,
+        }
+    }
+
+    /// Converts a value from the base unit of the dimension to this unit.
+    pub fn from_base(self, value: f32) -> f32 {
+        match self {
+            Unit::Celsius | Unit::Hectopascal | Unit::MetrePerSecond => value,
+            Unit::Fahrenheit => value * 9.0 / 5.0 + 32.0,
+            Unit::InchOfMercury => value / 33.863_887,
+            Unit::KilometrePerHour => value * 3.6,
+            Unit::MilePerHour => value / 0.447_04,
+        }
+    }
+
+    /// Converts a value in this unit to the base unit of the dimension.
+    pub fn to_base(self, value: f32) -> f32 {
+        match self {
+            Unit::Celsius | Unit::Hectopascal | Unit::MetrePerSecond => value,
+            Unit::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
+            Unit::InchOfMercury => value * 33.863_887,
+            Unit::KilometrePerHour => value / 3.6,
+            Unit::MilePerHour => value * 0.447_04,
+        }
+    }
+
+    /// Returns the number of decimals a reading in this unit is rounded to, fine enough to
+    /// follow a sensor without showing noise: `21.5°C` but `71°F`, `29.92inHg` but `1013hPa`.
+    pub const fn decimals(self) -> u8 {
+        match self {
+            Unit::Celsius | Unit::MetrePerSecond => 1,
+            Unit::InchOfMercury => 2,
+            Unit::Fahrenheit | Unit::Hectopascal | Unit::KilometrePerHour | Unit::MilePerHour => 0,
+        }
+    }
+
+    /// Returns the suffix shown after a value, as character codes of the A00 ROM.
+    pub const fn suffix(self) -> &'static [u8] {
+        match self {
+            Unit::Celsius => &[DEGREE, b'C'],
+            Unit::Fahrenheit => &[DEGREE, b'F'],
+            Unit::Hectopascal => b"hPa",
+            Unit::InchOfMercury => b"inHg",
+            Unit::MetrePerSecond => b"m/s",
+            Unit::KilometrePerHour => b"km/h",
+            Unit::MilePerHour => b"mph",
+        }
+    }
+
+    /// Returns the name of the unit in plain ASCII, e.g. for a settings menu.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Unit::Celsius => "Celsius",
+            Unit::Fahrenheit => "Fahrenheit",
+            Unit::Hectopascal => "hPa",
+            Unit::InchOfMercury => "inHg",
+            Unit::MetrePerSecond => "m/s",
+            Unit::KilometrePerHour => "km/h",
+            Unit::MilePerHour => "mph",
+        }
+    }
+
+    /// Converts a value from the base unit and rounds it to [`decimals`](Self::decimals), so
+    /// every field showing it agrees. A value that rounds to zero is a positive zero, never
+    /// shown as `-0`.
+    pub fn round_from_base(self, value: f32) -> f32 {
+        let scale = 10f32.powi(self.decimals() as i32);
+        let rounded = (self.from_base(value) * scale).round() / scale;
+        // Adding zero turns -0.0 into 0.0.
+        rounded + 0.0
+    }
+}
+
+/// The unit chosen for each [`Dimension`], such as metric or imperial, set from a settings
+/// menu and shared by every field that shows a reading.
+///
+/// Readings are kept in the base unit of their dimension (°C, hPa, m/s) and converted only
+/// when shown, so switching units never compounds rounding errors. Like a
+/// [`Catalog`](crate::Catalog), the preferences can be a `static` and are changed through
+/// `&self`; each change bumps a [`generation`](Self::generation) counter, and a
+/// [`Compositor`](crate::Compositor) given them with
+/// [`with_units`](crate::Compositor::with_units) redraws every widget when it changes.
+///
+/// # Example
+///
+/// ```ignore
+/// static UNITS: Units = Units::metric();
+///
+/// let mut outside = Readout::new(Dimension::Temperature, &UNITS);
+/// compositor.add(Rect::new(10, 0, 6, 1), &mut outside, Duration::from_secs(5))?;
+/// // Later, from the settings menu:
+/// UNITS.set(Unit::Fahrenheit);
+/// ```
+#[derive(Debug)]
+pub struct Units {
+    units: [AtomicU8; 3],
+    generation: AtomicU32,
+}
+
+impl Units {
+    /// Creates preferences for °C, hPa and m/s.
+    pub const fn metric() -> Self {
+        Self::new(Unit::Celsius, Unit::Hectopascal, Unit::MetrePerSecond)
+    }
+
+    /// Creates preferences for °F, inHg and mph.
+    pub const fn imperial() -> Self {
+        Self::new(Unit::Fahrenheit, Unit::InchOfMercury, Unit::MilePerHour)
+    }
+
+    /// Creates preferences with a unit for each dimension. A unit of the wrong dimension is
+    /// replaced by the base unit.
+    ///
+    /// # Arguments
+    ///
+    /// * `temperature` - The unit of temperature.
+    /// * `pressure` - The unit of pressure.
+    /// * `speed` - The unit of speed.
+    pub const fn new(temperature: Unit, pressure: Unit, speed: Unit) -> Self {
+        Self {
+            units: [
+                AtomicU8::new(Self::pick(temperature, Unit::Celsius)),
+                AtomicU8::new(Self::pick(pressure, Unit::Hectopascal)),
+                AtomicU8::new(Self::pick(speed, Unit::MetrePerSecond)),
+            ],
+            generation: AtomicU32::new(0),
+        }
+    }
+
+    /// Returns `unit` if it measures the same as `base`, otherwise `base`.
+    const fn pick(unit: Unit, base: Unit) -> u8 {
+        if unit.dimension() as u8 == base.dimension() as u8 {
+            unit as u8
+        } else {
+            base as u8
+        }
+    }
+
+    /// Returns the unit chosen for a dimension.
+    pub fn unit(&self, dimension: Dimension) -> Unit {
+        let index = self.units[dimension as usize].load(Ordering::Relaxed);
+        ALL[index as usize]
+    }
+
+    /// Chooses the unit for its dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `unit` - The unit to show readings of its dimension in.
+    pub fn set(&self, unit: Unit) {
+        let previous = self.units[unit.dimension() as usize].swap(unit as u8, Ordering::Relaxed);
+        if previous != unit as u8 {
+            self.generation.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns a counter that changes each time a unit does.
+    pub fn generation(&self) -> u32 {
+        self.generation.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Self::metric()
+    }
+}
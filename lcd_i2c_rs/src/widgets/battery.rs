@@ -0,0 +1,192 @@
+use crate::fmt::fmt_buf;
+use crate::glyphs::BATTERY_GLYPHS;
+use crate::{Col, Lcd, Row};
+
+/// The highest glyph level, a full battery.
+const FULL: u8 = BATTERY_GLYPHS.len() as u8 - 1;
+
+/// A battery gauge drawn as a single glyph, optionally followed by the charge in percent.
+///
+/// The charge is shown as one of five fill levels. While charging, the glyph fills step by
+/// step from the current level up to full, then starts over. The widget takes one CGRAM slot
+/// and animates by rewriting the glyph in it, so the animation costs no DDRAM writes.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut battery = BatteryWidget::new().with_slot(5).with_percentage();
+/// battery.set_level(72);
+/// battery.set_charging(true);
+/// loop {
+///     Ets::delay_ms(100);
+///     if battery.tick(100) {
+///         battery.render(&mut lcd, 11, 0)?;
+///     }
+/// }
+/// ```
+pub struct BatteryWidget {
+    percent: u8,
+    charging: bool,
+    percentage: bool,
+    slot: u8,
+    frame_ms: u32,
+    elapsed_ms: u32,
+    frame: u8,
+    loaded: Option<u8>,
+    dirty: bool,
+}
+
+impl BatteryWidget {
+    /// Creates an empty, discharging gauge using CGRAM slot 0.
+    pub const fn new() -> Self {
+        Self {
+            percent: 0,
+            charging: false,
+            percentage: false,
+            slot: 0,
+            frame_ms: 500,
+            elapsed_ms: 0,
+            frame: 0,
+            loaded: None,
+            dirty: true,
+        }
+    }
+
+    /// Sets the CGRAM slot (0-7) used for the battery glyph.
+    pub const fn with_slot(mut self, slot: u8) -> Self {
+        self.slot = slot;
+        self
+    }
+
+    /// Shows the charge as ` NNN%` after the glyph, taking five more cells.
+    pub const fn with_percentage(mut self) -> Self {
+        self.percentage = true;
+        self
+    }
+
+    /// Sets how long each frame of the charging animation is shown, 500 ms by default.
+    pub const fn with_frame_ms(mut self, frame_ms: u32) -> Self {
+        self.frame_ms = frame_ms;
+        self
+    }
+
+    /// Returns the charge in percent.
+    pub fn level(&self) -> u8 {
+        self.percent
+    }
+
+    /// Sets the charge.
+    ///
+    /// # Arguments
+    ///
+    /// * `percent` - The charge in percent, clamped to 100.
+    pub fn set_level(&mut self, percent: u8) {
+        let percent = percent.min(100);
+        if percent != self.percent {
+            self.percent = percent;
+            self.dirty |= self.percentage;
+        }
+    }
+
+    /// Returns `true` if the charging animation is running.
+    pub fn is_charging(&self) -> bool {
+        self.charging
+    }
+
+    /// Starts or stops the charging animation.
+    pub fn set_charging(&mut self, charging: bool) {
+        if charging != self.charging {
+            self.charging = charging;
+            self.frame = 0;
+            self.elapsed_ms = 0;
+        }
+    }
+
+    /// Advances the charging animation.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed_ms` - The time since the previous call, in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the gauge is out of date and should be rendered again.
+    pub fn tick(&mut self, elapsed_ms: u32) -> bool {
+        if self.charging && self.frame_ms > 0 {
+            self.elapsed_ms += elapsed_ms;
+            let steps = self.elapsed_ms / self.frame_ms;
+            self.elapsed_ms %= self.frame_ms;
+            let frames = (FULL - self.base_level()) as u32 + 1;
+            self.frame = ((self.frame as u32 + steps) % frames) as u8;
+        }
+        self.dirty || self.loaded != Some(self.shown_level())
+    }
+
+    /// Draws the gauge, sending only what changed since the last render at the same position.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `col` - The column of the battery glyph (0-indexed).
+    /// * `row` - The row to draw on (0-indexed).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the gauge is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the gauge does not fit on the display, or there is an error while sending data.
+    pub fn render(
+        &mut self,
+        lcd: &mut Lcd,
+        col: impl Into<Col>,
+        row: impl Into<Row>,
+    ) -> anyhow::Result<()> {
+        let (col, row) = (col.into().0, row.into().0);
+        let width = if self.percentage { 6 } else { 1 };
+        if col.saturating_add(width) > lcd.cols() || row >= lcd.rows() {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+
+        let level = self.shown_level();
+        if self.loaded != Some(level) {
+            lcd.create_custom_chars(self.slot, &BATTERY_GLYPHS[level as usize])?;
+            self.loaded = Some(level);
+        }
+
+        if !self.dirty {
+            return Ok(());
+        }
+        lcd.set_cursor(col, row)?;
+        lcd.write_byte(self.slot)?;
+        if self.percentage {
+            lcd.print_str(fmt_buf!(8, "{:>4}%", self.percent).as_str())?;
+        }
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Forces the next render to redraw the gauge and reload its glyph, for example after the
+    /// display was cleared or the CGRAM slot was reused.
+    pub fn invalidate(&mut self) {
+        self.loaded = None;
+        self.dirty = true;
+    }
+
+    /// Returns the glyph level for the charge, rounded to the nearest level.
+    fn base_level(&self) -> u8 {
+        ((self.percent as u16 * FULL as u16 + 50) / 100) as u8
+    }
+
+    fn shown_level(&self) -> u8 {
+        if self.charging {
+            (self.base_level() + self.frame).min(FULL)
+        } else {
+            self.base_level()
+        }
+    }
+}
+
+impl Default for BatteryWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,332 @@
+use core::fmt::Write;
+
+use crate::fmt::FmtBuf;
+use crate::glyphs::TREND_GLYPHS;
+use crate::i18n::Locale;
+use crate::Lcd;
+
+/// The degree sign in the A00 character ROM.
+const DEGREE: u8 = 0xdf;
+/// The widest row that is cached.
+const MAX_COLS: usize = 40;
+/// The most rows that are cached.
+const MAX_ROWS: usize = 4;
+
+/// A quantity shown by an [`EnvDashboard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    /// Temperature in degrees Celsius.
+    Temperature,
+    /// Relative humidity in percent.
+    Humidity,
+    /// Air pressure in hectopascals.
+    Pressure,
+}
+
+impl Quantity {
+    /// The change between readings below which the trend is shown as steady.
+    fn deadband(self) -> f32 {
+        match self {
+            Quantity::Temperature => 0.1,
+            Quantity::Humidity => 0.5,
+            Quantity::Pressure => 0.2,
+        }
+    }
+}
+
+/// Which way a quantity moved at its last update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trend {
+    Rising,
+    Falling,
+    Steady,
+}
+
+#[derive(Clone, Copy)]
+struct Reading {
+    value: f32,
+    min: f32,
+    max: f32,
+    trend: Trend,
+}
+
+impl Reading {
+    const NONE: Reading = Reading {
+        value: f32::NAN,
+        min: f32::NAN,
+        max: f32::NAN,
+        trend: Trend::Steady,
+    };
+
+    fn update(&mut self, value: f32, deadband: f32) {
+        if value.is_nan() {
+            self.value = value;
+            self.trend = Trend::Steady;
+            return;
+        }
+        let delta = value - self.value;
+        self.trend = if delta >= deadband {
+            Trend::Rising
+        } else if delta <= -deadband {
+            Trend::Falling
+        } else {
+            Trend::Steady
+        };
+        self.value = value;
+        // `f32::min` and `f32::max` ignore the NaN of the first reading.
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+}
+
+/// One row of text being composed, as character codes.
+struct Line {
+    buf: [u8; MAX_COLS],
+    len: usize,
+}
+
+impl Line {
+    fn new() -> Self {
+        Self {
+            buf: [b' '; MAX_COLS],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, code: u8) {
+        if self.len < MAX_COLS {
+            self.buf[self.len] = code;
+            self.len += 1;
+        }
+    }
+
+    fn push_str(&mut self, text: &str) {
+        text.bytes().for_each(|b| self.push(b));
+    }
+
+    /// Appends `value` right-aligned in `width` cells, or `--` if it is missing.
+    fn push_value(&mut self, value: f32, width: usize, precision: usize, locale: &Locale) {
+        let mut text = FmtBuf::<16>::new();
+        if value.is_nan() {
+            let _ = write!(text, "{:>width$}", "--");
+        } else {
+            let _ = write!(text, "{:>width$.precision$}", value);
+        }
+        let mut localized = FmtBuf::<16>::new();
+        let _ = locale.write_localized(&mut localized, text.as_str());
+        self.push_str(localized.as_str());
+    }
+}
+
+/// A screen of temperature, humidity and pressure readings with units, trend arrows and the
+/// lowest and highest values seen.
+///
+/// On displays with three or more rows each quantity gets its own row, and on displays at
+/// least 20 columns wide the row ends with the `min/max` range. Two-row displays show
+/// temperature and humidity side by side on the first row and pressure on the second. Sensors
+/// that do not measure a quantity (such as the DHT family and pressure) pass `f32::NAN`,
+/// shown as `--`.
+///
+/// Rendering compares the new screen with the one last drawn and only rewrites the cells that
+/// changed. The trend arrows take three CGRAM slots, 0 to 2 unless moved with
+/// [`with_slots`](EnvDashboard::with_slots), and the degree sign comes from the A00 ROM.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut dashboard = EnvDashboard::new();
+/// loop {
+///     let (t, h, p) = read_bme280()?;
+///     dashboard.update(t, h, p);
+///     dashboard.render(&mut lcd)?;
+///     Ets::delay_ms(2000);
+/// }
+/// ```
+pub struct EnvDashboard {
+    readings: [Reading; 3],
+    slot: u8,
+    glyphs_loaded: bool,
+    drawn: [[u8; MAX_COLS]; MAX_ROWS],
+    drawn_valid: bool,
+    locale: Locale,
+}
+
+impl EnvDashboard {
+    /// Creates a dashboard with no readings.
+    pub const fn new() -> Self {
+        Self {
+            readings: [Reading::NONE; 3],
+            slot: 0,
+            glyphs_loaded: false,
+            drawn: [[b' '; MAX_COLS]; MAX_ROWS],
+            drawn_valid: false,
+            locale: Locale::EN_GB,
+        }
+    }
+
+    /// Writes the readings with the decimal separator of `locale`, e.g. `21,5` for
+    /// [`Locale::DE`].
+    pub const fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Sets the first of the three CGRAM slots used for the trend arrows.
+    ///
+    /// # Arguments
+    ///
+    /// * `first` - The first slot (0-5).
+    pub const fn with_slots(mut self, first: u8) -> Self {
+        self.slot = first;
+        self
+    }
+
+    /// Records a new set of readings, updating the trends and the ranges.
+    ///
+    /// # Arguments
+    ///
+    /// * `temperature` - The temperature in degrees Celsius, or `f32::NAN` if unavailable.
+    /// * `humidity` - The relative humidity in percent, or `f32::NAN` if unavailable.
+    /// * `pressure` - The pressure in hectopascals, or `f32::NAN` if unavailable.
+    pub fn update(&mut self, temperature: f32, humidity: f32, pressure: f32) {
+        let quantities = [
+            Quantity::Temperature,
+            Quantity::Humidity,
+            Quantity::Pressure,
+        ];
+        for (i, value) in [temperature, humidity, pressure].into_iter().enumerate() {
+            self.readings[i].update(value, quantities[i].deadband());
+        }
+    }
+
+    /// Returns the latest reading of a quantity, if there is one.
+    pub fn value(&self, quantity: Quantity) -> Option<f32> {
+        let value = self.readings[quantity as usize].value;
+        (!value.is_nan()).then_some(value)
+    }
+
+    /// Returns the lowest and highest readings of a quantity since the last reset.
+    pub fn min_max(&self, quantity: Quantity) -> Option<(f32, f32)> {
+        let reading = &self.readings[quantity as usize];
+        (!reading.min.is_nan()).then_some((reading.min, reading.max))
+    }
+
+    /// Forgets the lowest and highest readings, starting the ranges again from the next update.
+    pub fn reset_min_max(&mut self) {
+        for reading in &mut self.readings {
+            reading.min = f32::NAN;
+            reading.max = f32::NAN;
+        }
+    }
+
+    /// Draws the dashboard, rewriting only the cells that changed since the last render.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the dashboard is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the display has fewer than two rows or 16 columns, or there is an error while sending data.
+    pub fn render(&mut self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        if lcd.rows() < 2 || lcd.cols() < 16 {
+            return Err(anyhow::anyhow!("Display too small for the dashboard"));
+        }
+        if !self.glyphs_loaded {
+            for (i, glyph) in TREND_GLYPHS.iter().enumerate() {
+                lcd.create_custom_chars(self.slot + i as u8, glyph)?;
+            }
+            self.glyphs_loaded = true;
+        }
+
+        let cols = (lcd.cols() as usize).min(MAX_COLS);
+        let rows = (lcd.rows() as usize).min(MAX_ROWS);
+        for row in 0..rows {
+            let line = self.compose(row, cols, rows);
+            for col in 0..cols {
+                let code = line.buf[col];
+                if self.drawn_valid && self.drawn[row][col] == code {
+                    continue;
+                }
+                lcd.set_cursor(col as u8, row as u8)?;
+                lcd.write_byte(code)?;
+                self.drawn[row][col] = code;
+            }
+        }
+        self.drawn_valid = true;
+        Ok(())
+    }
+
+    /// Forces the next render to redraw every cell and reload the arrow glyphs, for example
+    /// after the display was cleared or the CGRAM slots were reused.
+    pub fn invalidate(&mut self) {
+        self.glyphs_loaded = false;
+        self.drawn_valid = false;
+    }
+
+    fn compose(&self, row: usize, cols: usize, rows: usize) -> Line {
+        let mut line = Line::new();
+        if rows >= 3 {
+            if let Some(quantity) = [
+                Quantity::Temperature,
+                Quantity::Humidity,
+                Quantity::Pressure,
+            ]
+            .get(row)
+            {
+                self.push_full(&mut line, *quantity, cols >= 20);
+            }
+        } else if row == 0 {
+            let [temperature, humidity, _] = &self.readings;
+            line.push(b'T');
+            line.push_value(temperature.value, 5, 1, &self.locale);
+            line.push(DEGREE);
+            line.push(self.arrow(temperature));
+            line.push_str(" H");
+            line.push_value(humidity.value, 4, 0, &self.locale);
+            line.push(b'%');
+            line.push(self.arrow(humidity));
+        } else if row == 1 {
+            self.push_full(&mut line, Quantity::Pressure, false);
+        }
+        line
+    }
+
+    /// Appends a quantity as label, value, unit and arrow, followed by the range if `range`.
+    fn push_full(&self, line: &mut Line, quantity: Quantity, range: bool) {
+        let reading = &self.readings[quantity as usize];
+        let (label, unit): (u8, &[u8]) = match quantity {
+            Quantity::Temperature => (b'T', &[DEGREE, b'C', b' ']),
+            Quantity::Humidity => (b'H', b"%  "),
+            Quantity::Pressure => (b'P', b"hPa"),
+        };
+        line.push(label);
+        line.push_value(reading.value, 6, 1, &self.locale);
+        unit.iter().for_each(|&b| line.push(b));
+        line.push(self.arrow(reading));
+        if range && !reading.min.is_nan() {
+            let mut text = FmtBuf::<24>::new();
+            let _ = write!(text, "{:.0}/{:.0}", reading.min, reading.max);
+            for _ in text.as_str().len()..9 {
+                line.push(b' ');
+            }
+            line.push_str(text.as_str());
+        }
+    }
+
+    /// Returns the arrow code for a reading, or a space if it is missing.
+    fn arrow(&self, reading: &Reading) -> u8 {
+        if reading.value.is_nan() {
+            b' '
+        } else {
+            self.slot + reading.trend as u8
+        }
+    }
+}
+
+impl Default for EnvDashboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
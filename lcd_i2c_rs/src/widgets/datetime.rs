@@ -0,0 +1,482 @@
+use super::number::EditState;
+use crate::fmt::FmtBuf;
+use crate::i18n::{DateOrder, Locale};
+use crate::input::InputEvent;
+#[cfg(feature = "buffer")]
+use crate::{AnimClock, Canvas, Widget};
+use crate::{Col, Lcd, Row};
+
+/// The earliest year that can be set.
+const MIN_YEAR: u16 = 2000;
+/// The latest year that can be set.
+const MAX_YEAR: u16 = 2099;
+
+/// A calendar date and time of day, as kept by a real-time clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    /// The year, e.g. 2024.
+    pub year: u16,
+    /// The month, 1 to 12.
+    pub month: u8,
+    /// The day of the month, from 1.
+    pub day: u8,
+    /// The hour, 0 to 23.
+    pub hour: u8,
+    /// The minute, 0 to 59.
+    pub minute: u8,
+    /// The second, 0 to 59.
+    pub second: u8,
+}
+
+impl DateTime {
+    /// Creates a date and time. It is not checked, see [`is_valid`](Self::is_valid).
+    pub const fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Self {
+        Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }
+    }
+
+    /// Returns `true` if `year` is a leap year in the Gregorian calendar.
+    pub const fn is_leap_year(year: u16) -> bool {
+        match (year % 4, year % 100, year % 400) {
+            (_, _, 0) => true,
+            (_, 0, _) => false,
+            (0, _, _) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the number of days in a month, or 0 if `month` is not 1 to 12.
+    pub const fn days_in_month(year: u16, month: u8) -> u8 {
+        match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if Self::is_leap_year(year) => 29,
+            2 => 28,
+            _ => 0,
+        }
+    }
+
+    /// Returns `true` if every field is in range, including the day for its month.
+    pub const fn is_valid(&self) -> bool {
+        self.month >= 1
+            && self.month <= 12
+            && self.day >= 1
+            && self.day <= Self::days_in_month(self.year, self.month)
+            && self.hour < 24
+            && self.minute < 60
+            && self.second < 60
+    }
+
+    /// Returns the closest valid date and time, clamping each field into range.
+    pub fn normalized(mut self) -> Self {
+        self.year = self.year.clamp(MIN_YEAR, MAX_YEAR);
+        self.month = self.month.clamp(1, 12);
+        self.day = self
+            .day
+            .clamp(1, Self::days_in_month(self.year, self.month));
+        self.hour = self.hour.min(23);
+        self.minute = self.minute.min(59);
+        self.second = self.second.min(59);
+        self
+    }
+}
+
+impl Default for DateTime {
+    fn default() -> Self {
+        Self::new(MIN_YEAR, 1, 1, 0, 0, 0)
+    }
+}
+
+/// Which parts of a [`DateTime`] a [`DateTimeEditor`] shows and edits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DateTimeFields {
+    /// The date, `DD/MM/YYYY` by default, on one row and the time, `HH:MM:SS`, on the next.
+    Both,
+    /// The date only.
+    Date,
+    /// The time only.
+    Time,
+}
+
+/// One editable field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Day,
+    Month,
+    Year,
+    Hour,
+    Minute,
+    Second,
+}
+
+/// The fields in the order they are shown, for each [`DateOrder`].
+static DAY_MONTH_YEAR: [Field; 6] = [
+    Field::Day,
+    Field::Month,
+    Field::Year,
+    Field::Hour,
+    Field::Minute,
+    Field::Second,
+];
+static MONTH_DAY_YEAR: [Field; 6] = [
+    Field::Month,
+    Field::Day,
+    Field::Year,
+    Field::Hour,
+    Field::Minute,
+    Field::Second,
+];
+static YEAR_MONTH_DAY: [Field; 6] = [
+    Field::Year,
+    Field::Month,
+    Field::Day,
+    Field::Hour,
+    Field::Minute,
+    Field::Second,
+];
+
+impl Field {
+    const fn width(self) -> u8 {
+        match self {
+            Field::Year => 4,
+            _ => 2,
+        }
+    }
+
+    const fn is_date(self) -> bool {
+        matches!(self, Field::Day | Field::Month | Field::Year)
+    }
+}
+
+/// Edits a date and time field by field, for setting a real-time clock from a menu.
+///
+/// [`InputEvent::Left`] and [`Right`](InputEvent::Right) move between fields,
+/// [`Up`](InputEvent::Up) and [`Down`](InputEvent::Down) or an
+/// [`Encoder`](InputEvent::Encoder) change the edited field, wrapping around at its ends, and
+/// [`Char`](InputEvent::Char) digits type into it. [`Select`](InputEvent::Select) moves on to
+/// the next field and confirms on the last, and [`Back`](InputEvent::Back) cancels, restoring
+/// the value editing started from.
+///
+/// The value is kept valid throughout: the day wraps at the length of its month, and moving to
+/// a shorter month or out of a leap year pulls the day back, so 31/01 becomes 29/02 in a leap
+/// year. The edited field blinks.
+///
+/// The date and time are written in the order and with the separators of a [`Locale`], set
+/// with [`with_locale`](Self::with_locale). On the 12-hour clock `AM` or `PM` follows the
+/// time; the hour still runs through the whole day, and typing an hour keeps its half.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut editor = DateTimeEditor::new(rtc.now()?);
+/// while editor.state() == EditState::Editing {
+///     if let Some(event) = keypad.next_event()? {
+///         editor.handle(event);
+///     }
+///     editor.render(&mut lcd, 3, 0)?;
+/// }
+/// if editor.state() == EditState::Confirmed {
+///     rtc.set(editor.value())?;
+/// }
+/// ```
+pub struct DateTimeEditor {
+    value: DateTime,
+    initial: DateTime,
+    fields: DateTimeFields,
+    locale: Locale,
+    field: usize,
+    /// The number of digits typed into the edited field so far.
+    typed: u8,
+    /// The number the typed digits make.
+    entry: u16,
+    state: EditState,
+}
+
+impl DateTimeEditor {
+    /// Creates an editor for both date and time, starting on the first field.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The date and time to edit, normalized if out of range.
+    pub fn new(value: DateTime) -> Self {
+        let value = value.normalized();
+        Self {
+            value,
+            initial: value,
+            fields: DateTimeFields::Both,
+            locale: Locale::EN_GB,
+            field: 0,
+            typed: 0,
+            entry: 0,
+            state: EditState::Editing,
+        }
+    }
+
+    /// Sets which parts are shown and edited.
+    pub fn with_fields(mut self, fields: DateTimeFields) -> Self {
+        self.fields = fields;
+        self.field = 0;
+        self
+    }
+
+    /// Writes the date and time as `locale` does, e.g. `12/31/2024` and ` 9:30:00 PM` for
+    /// [`Locale::EN_US`].
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self.field = 0;
+        self
+    }
+
+    /// Returns the date and time, always valid.
+    pub fn value(&self) -> DateTime {
+        self.value
+    }
+
+    /// Returns whether the value is being edited, or was confirmed or cancelled.
+    pub fn state(&self) -> EditState {
+        self.state
+    }
+
+    /// Returns the number of rows the editor takes.
+    pub fn height(&self) -> u8 {
+        match self.fields {
+            DateTimeFields::Both => 2,
+            DateTimeFields::Date | DateTimeFields::Time => 1,
+        }
+    }
+
+    /// Reacts to an input event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the event was used. Events are ignored once editing has ended.
+    pub fn handle(&mut self, event: InputEvent) -> bool {
+        if self.state != EditState::Editing {
+            return false;
+        }
+        let count = self.field_list().len();
+        match event {
+            InputEvent::Up => self.adjust(1),
+            InputEvent::Down => self.adjust(-1),
+            InputEvent::Encoder(detents) => self.adjust(detents as i32),
+            InputEvent::Left => self.move_to(self.field.checked_sub(1).unwrap_or(count - 1)),
+            InputEvent::Right => self.move_to((self.field + 1) % count),
+            InputEvent::Select if self.field + 1 < count => self.move_to(self.field + 1),
+            InputEvent::Select => self.state = EditState::Confirmed,
+            InputEvent::Back => {
+                self.value = self.initial;
+                self.state = EditState::Cancelled;
+            }
+            InputEvent::Char(ch) => match ch.to_digit(10) {
+                Some(digit) => self.type_digit(digit as u8),
+                None => return false,
+            },
+        }
+        true
+    }
+
+    /// Draws the editor and places the blinking cursor on the edited field while editing.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `col` - The column of the first cell (0-indexed).
+    /// * `row` - The top row (0-indexed).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the editor is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the editor does not fit on the display or there is an error while sending data.
+    pub fn render(
+        &mut self,
+        lcd: &mut Lcd,
+        col: impl Into<Col>,
+        row: impl Into<Row>,
+    ) -> anyhow::Result<()> {
+        let (col, row) = (col.into().0, row.into().0);
+        let width = (0..self.height())
+            .map(|line| self.line(line).as_str().len() as u8)
+            .max()
+            .unwrap_or(0);
+        if col.saturating_add(width) > lcd.cols() || row.saturating_add(self.height()) > lcd.rows()
+        {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+        for line in 0..self.height() {
+            lcd.set_cursor(col, row + line)?;
+            lcd.print_str(self.line(line).as_str())?;
+        }
+        if self.state != EditState::Editing {
+            return lcd.blink(false);
+        }
+        let (line, start, width) = self.edited_span();
+        lcd.set_cursor(col + start + width - 1, row + line)?;
+        lcd.blink(true)
+    }
+
+    fn field_list(&self) -> &'static [Field] {
+        let all = match self.locale.date_order {
+            DateOrder::DayMonthYear => &DAY_MONTH_YEAR,
+            DateOrder::MonthDayYear => &MONTH_DAY_YEAR,
+            DateOrder::YearMonthDay => &YEAR_MONTH_DAY,
+        };
+        match self.fields {
+            DateTimeFields::Both => all,
+            DateTimeFields::Date => &all[..3],
+            DateTimeFields::Time => &all[3..],
+        }
+    }
+
+    /// Returns one row of text.
+    fn line(&self, line: u8) -> FmtBuf<16> {
+        let v = &self.value;
+        let date = self.fields != DateTimeFields::Time && line == 0;
+        let mut text = FmtBuf::new();
+        let _ = match date {
+            true => self.locale.write_date(&mut text, v.year, v.month, v.day),
+            false => self
+                .locale
+                .write_time(&mut text, v.hour, v.minute, Some(v.second)),
+        };
+        text
+    }
+
+    /// Returns the row, column and width of the edited field.
+    fn edited_span(&self) -> (u8, u8, u8) {
+        let fields = self.field_list();
+        let field = fields[self.field];
+        let line = u8::from(self.fields == DateTimeFields::Both && !field.is_date());
+        // Fields are separated by one character, and the date and time fields by a row.
+        let start = fields[..self.field]
+            .iter()
+            .filter(|other| other.is_date() == field.is_date())
+            .map(|other| other.width() + 1)
+            .sum();
+        (line, start, field.width())
+    }
+
+    fn move_to(&mut self, field: usize) {
+        self.field = field;
+        self.typed = 0;
+        self.entry = 0;
+    }
+
+    /// Returns the edited field's value and its range.
+    fn get(&self, field: Field) -> (u16, u16, u16) {
+        let v = &self.value;
+        match field {
+            Field::Day => (
+                v.day as u16,
+                1,
+                DateTime::days_in_month(v.year, v.month) as u16,
+            ),
+            Field::Month => (v.month as u16, 1, 12),
+            Field::Year => (v.year, MIN_YEAR, MAX_YEAR),
+            Field::Hour => (v.hour as u16, 0, 23),
+            Field::Minute => (v.minute as u16, 0, 59),
+            Field::Second => (v.second as u16, 0, 59),
+        }
+    }
+
+    fn set(&mut self, field: Field, value: u16) {
+        let v = &mut self.value;
+        match field {
+            Field::Day => v.day = value as u8,
+            Field::Month => v.month = value as u8,
+            Field::Year => v.year = value,
+            Field::Hour => v.hour = value as u8,
+            Field::Minute => v.minute = value as u8,
+            Field::Second => v.second = value as u8,
+        }
+        self.value = self.value.normalized();
+    }
+
+    fn adjust(&mut self, steps: i32) {
+        let field = self.field_list()[self.field];
+        let (value, low, high) = self.get(field);
+        let span = (high - low + 1) as i32;
+        let wrapped = (value as i32 - low as i32 + steps).rem_euclid(span) + low as i32;
+        self.set(field, wrapped as u16);
+        self.typed = 0;
+        self.entry = 0;
+    }
+
+    /// Types a digit into the edited field, moving on once the field is full.
+    fn type_digit(&mut self, digit: u8) {
+        let field = self.field_list()[self.field];
+        let (_, low, high) = self.get(field);
+        let width = field.width();
+        if self.typed >= width {
+            // The last field stays selected once full, so further digits type it anew.
+            self.typed = 0;
+            self.entry = 0;
+        }
+        self.entry = self.entry * 10 + digit as u16;
+        if field == Field::Hour && self.locale.hour12 {
+            // The typed hour is on the 12-hour clock, in the half of the day shown.
+            let pm = if self.value.hour >= 12 { 12 } else { 0 };
+            self.set(field, self.entry.clamp(1, 12) % 12 + pm);
+        } else {
+            self.set(field, self.entry.clamp(low, high));
+        }
+        self.typed += 1;
+        if self.typed >= width && self.field + 1 < self.field_list().len() {
+            self.move_to(self.field + 1);
+        }
+    }
+}
+
+/// Draws the editor from the top left of its rectangle. The edited field blinks in the frame
+/// itself, in step with [`AnimClock::blink_on`].
+#[cfg(feature = "buffer")]
+impl Widget for DateTimeEditor {
+    fn render(&mut self, canvas: &mut Canvas) -> anyhow::Result<()> {
+        for line in 0..self.height() {
+            canvas.print(0, line, self.line(line).as_str())?;
+        }
+        let hidden = !AnimClock::blink_on();
+        if self.state == EditState::Editing && hidden {
+            let (line, start, width) = self.edited_span();
+            for col in start..start + width {
+                canvas.set(col, line, b' ');
+            }
+        }
+        Ok(())
+    }
+
+    fn blinks(&self) -> bool {
+        self.state == EditState::Editing
+    }
+
+    fn handle(&mut self, event: InputEvent) -> bool {
+        DateTimeEditor::handle(self, event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typing_past_the_last_field_starts_it_again() {
+        let mut editor = DateTimeEditor::new(DateTime::new(2024, 1, 1, 12, 0, 0))
+            .with_fields(DateTimeFields::Time);
+        for _ in 0..20 {
+            editor.handle(InputEvent::Char('9'));
+        }
+        editor.handle(InputEvent::Char('4'));
+        editor.handle(InputEvent::Char('2'));
+        assert_eq!(editor.value().second, 42);
+        assert_eq!(editor.state(), EditState::Editing);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::fmt::{fmt_buf, uptime, FmtBuf};
+use crate::Lcd;
+use esp_idf_hal::sys;
+
+/// The number of lines on the diagnostics screen.
+const LINES: usize = 4;
+
+/// Why the chip last reset, as reported by ESP-IDF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetReason {
+    /// The reason could not be determined.
+    Unknown,
+    /// Power-on.
+    PowerOn,
+    /// The external reset pin.
+    External,
+    /// A software reset, e.g. `esp_restart`.
+    Software,
+    /// A panic or exception.
+    Panic,
+    /// The interrupt watchdog.
+    InterruptWatchdog,
+    /// The task watchdog.
+    TaskWatchdog,
+    /// Another watchdog.
+    Watchdog,
+    /// Waking from deep sleep.
+    DeepSleep,
+    /// A brownout.
+    Brownout,
+    /// A reset over SDIO.
+    Sdio,
+}
+
+impl ResetReason {
+    /// Returns the reason for the last reset.
+    pub fn read() -> Self {
+        // SAFETY: `esp_reset_reason` only reads a value stored at startup.
+        match unsafe { sys::esp_reset_reason() } {
+            sys::esp_reset_reason_t_ESP_RST_POWERON => ResetReason::PowerOn,
+            sys::esp_reset_reason_t_ESP_RST_EXT => ResetReason::External,
+            sys::esp_reset_reason_t_ESP_RST_SW => ResetReason::Software,
+            sys::esp_reset_reason_t_ESP_RST_PANIC => ResetReason::Panic,
+            sys::esp_reset_reason_t_ESP_RST_INT_WDT => ResetReason::InterruptWatchdog,
+            sys::esp_reset_reason_t_ESP_RST_TASK_WDT => ResetReason::TaskWatchdog,
+            sys::esp_reset_reason_t_ESP_RST_WDT => ResetReason::Watchdog,
+            sys::esp_reset_reason_t_ESP_RST_DEEPSLEEP => ResetReason::DeepSleep,
+            sys::esp_reset_reason_t_ESP_RST_BROWNOUT => ResetReason::Brownout,
+            sys::esp_reset_reason_t_ESP_RST_SDIO => ResetReason::Sdio,
+            _ => ResetReason::Unknown,
+        }
+    }
+
+    /// Returns a label of at most 8 characters.
+    pub fn label(self) -> &'static str {
+        match self {
+            ResetReason::Unknown => "Unknown",
+            ResetReason::PowerOn => "PowerOn",
+            ResetReason::External => "External",
+            ResetReason::Software => "Software",
+            ResetReason::Panic => "Panic",
+            ResetReason::InterruptWatchdog => "IntWdt",
+            ResetReason::TaskWatchdog => "TaskWdt",
+            ResetReason::Watchdog => "Wdt",
+            ResetReason::DeepSleep => "Sleep",
+            ResetReason::Brownout => "Brownout",
+            ResetReason::Sdio => "SDIO",
+        }
+    }
+}
+
+/// A snapshot of the system health figures shown by [`DiagnosticsScreen`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// The free heap in bytes.
+    pub free_heap: u32,
+    /// The lowest the free heap has been since boot, in bytes.
+    pub min_free_heap: u32,
+    /// The time since boot in seconds.
+    pub uptime_secs: u32,
+    /// Why the chip last reset.
+    pub reset_reason: ResetReason,
+    /// The number of FreeRTOS tasks.
+    pub tasks: u32,
+}
+
+impl Diagnostics {
+    /// Reads the current figures from ESP-IDF.
+    pub fn read() -> Self {
+        // SAFETY: these functions only read counters kept by ESP-IDF and FreeRTOS.
+        unsafe {
+            Self {
+                free_heap: sys::esp_get_free_heap_size(),
+                min_free_heap: sys::esp_get_minimum_free_heap_size(),
+                uptime_secs: (sys::esp_timer_get_time() / 1_000_000) as u32,
+                reset_reason: ResetReason::read(),
+                tasks: sys::uxTaskGetNumberOfTasks(),
+            }
+        }
+    }
+
+    fn line(&self, index: usize) -> FmtBuf<24> {
+        match index {
+            0 => fmt_buf!(24, "Heap {:>7}", self.free_heap),
+            1 => fmt_buf!(24, "Min  {:>7}", self.min_free_heap),
+            2 => fmt_buf!(24, "Up {}", uptime(self.uptime_secs).as_str()),
+            _ => fmt_buf!(24, "Rst {:<8}T{:>3}", self.reset_reason.label(), self.tasks),
+        }
+    }
+}
+
+/// A support page showing free heap, lowest free heap, uptime, reset reason and task count.
+///
+/// The figures are read again at every refresh interval. Displays with fewer than four rows
+/// page through the lines, moving on at each refresh.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut diagnostics = DiagnosticsScreen::new(1000);
+/// diagnostics.render(&mut lcd)?;
+/// loop {
+///     Ets::delay_ms(100);
+///     if diagnostics.tick(100) {
+///         diagnostics.render(&mut lcd)?;
+///     }
+/// }
+/// ```
+pub struct DiagnosticsScreen {
+    interval_ms: u32,
+    elapsed_ms: u32,
+    page: usize,
+    snapshot: Option<Diagnostics>,
+}
+
+impl DiagnosticsScreen {
+    /// Creates a diagnostics page.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval_ms` - How often the figures are read again, in milliseconds.
+    pub const fn new(interval_ms: u32) -> Self {
+        Self {
+            interval_ms,
+            elapsed_ms: 0,
+            page: 0,
+            snapshot: None,
+        }
+    }
+
+    /// Returns the figures last shown, if the page has been rendered.
+    pub fn snapshot(&self) -> Option<Diagnostics> {
+        self.snapshot
+    }
+
+    /// Advances the refresh timer.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed_ms` - The time since the previous call, in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the figures are due to be read and rendered again.
+    pub fn tick(&mut self, elapsed_ms: u32) -> bool {
+        self.elapsed_ms = self.elapsed_ms.saturating_add(elapsed_ms);
+        if self.elapsed_ms < self.interval_ms {
+            return false;
+        }
+        self.elapsed_ms = 0;
+        self.page += 1;
+        true
+    }
+
+    /// Reads the figures and draws the page.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the page is successfully drawn.
+    /// * `Err(anyhow::Error)` - If there is an error while sending data.
+    pub fn render(&mut self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        let diagnostics = Diagnostics::read();
+        self.snapshot = Some(diagnostics);
+
+        let rows = (lcd.rows() as usize).clamp(1, LINES);
+        let pages = LINES.div_ceil(rows);
+        self.page %= pages;
+        let cols = lcd.cols() as usize;
+        for row in 0..rows {
+            let index = self.page * rows + row;
+            let line = if index < LINES {
+                diagnostics.line(index)
+            } else {
+                FmtBuf::new()
+            };
+            let (text, len) = lcd.charmap().truncate(line.as_str(), cols);
+            lcd.set_cursor(0, row as u8)?;
+            lcd.print_str(text)?;
+            for _ in len..cols {
+                lcd.print(' ')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for DiagnosticsScreen {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
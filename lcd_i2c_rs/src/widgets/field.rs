@@ -0,0 +1,290 @@
+use super::number::EditState;
+use crate::input::InputEvent;
+use crate::{AnimClock, Col, Lcd, Moment, Row};
+#[cfg(feature = "buffer")]
+use crate::{Canvas, CursorStyle, Widget};
+use std::time::Duration;
+
+/// How long a masked field shows the character just typed before hiding it.
+const REVEAL: Duration = Duration::from_millis(1000);
+/// The character shown in place of each hidden character of a masked field.
+const MASK: u8 = b'*';
+/// The first character [`InputEvent::Up`] cycles through.
+const FIRST: u8 = b' ';
+/// The last character [`InputEvent::Up`] cycles through.
+const LAST: u8 = b'~';
+/// The character a new cell starts from when cycled.
+const START: u8 = b'a';
+/// The backspace character, deleting before the cursor when sent as [`InputEvent::Char`].
+pub const BACKSPACE: char = '\u{8}';
+
+/// Enters up to `N` characters of ASCII text with a few buttons, such as a password or a
+/// hostname.
+///
+/// [`InputEvent::Up`] and [`Down`](InputEvent::Down) or an [`Encoder`](InputEvent::Encoder)
+/// cycle the character under the cursor through printable ASCII, and
+/// [`Right`](InputEvent::Right) and [`Left`](InputEvent::Left) move the cursor, one past the
+/// end to add a character. Keypads can type with [`Char`](InputEvent::Char) instead, and
+/// [`BACKSPACE`] deletes the character before the cursor. [`Select`](InputEvent::Select)
+/// confirms and [`Back`](InputEvent::Back) cancels, putting back the text editing started
+/// from.
+///
+/// Text longer than the field's width scrolls to keep the cursor in view. A field made with
+/// [`with_mask`](Self::with_mask) hides what is entered behind `*`, showing only the character
+/// just typed for a second, and [`reveal`](Self::reveal) shows the whole text on request.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut field: InputField<32> = InputField::new();
+/// while field.state() == EditState::Editing {
+///     if let Some(event) = keypad.next_event()? {
+///         field.handle(event);
+///     }
+///     field.render(&mut lcd, 0, 1, 16)?;
+/// }
+/// mqtt.set_host(field.text());
+/// ```
+pub struct InputField<const N: usize> {
+    text: [u8; N],
+    len: usize,
+    initial: ([u8; N], usize),
+    cursor: usize,
+    scroll: usize,
+    state: EditState,
+    masked: bool,
+    revealed: bool,
+    typed: Option<(usize, Moment)>,
+}
+
+impl<const N: usize> InputField<N> {
+    /// Creates an empty field.
+    pub fn new() -> Self {
+        Self {
+            text: [0; N],
+            len: 0,
+            initial: ([0; N], 0),
+            cursor: 0,
+            scroll: 0,
+            state: EditState::Editing,
+            masked: false,
+            revealed: false,
+            typed: None,
+        }
+    }
+
+    /// Masks the text, for passwords: each character is shown as `*`, except the one just
+    /// typed or cycled, which stays visible for a second or until the cursor moves.
+    pub fn with_mask(mut self) -> Self {
+        self.masked = true;
+        self
+    }
+
+    /// Shows or hides again the whole text of a masked field, e.g. from a "show password"
+    /// button.
+    pub fn reveal(&mut self) {
+        self.revealed = !self.revealed;
+    }
+
+    /// Returns `true` if the text of a masked field is shown.
+    pub fn is_revealed(&self) -> bool {
+        self.revealed
+    }
+
+    /// Returns the text entered.
+    pub fn text(&self) -> &str {
+        // Only printable ASCII is ever stored, see `put`.
+        core::str::from_utf8(&self.text[..self.len]).unwrap_or("")
+    }
+
+    /// Sets the text, keeping the printable ASCII characters that fit, and starts editing from
+    /// it with the cursor at the end.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to edit.
+    pub fn set_text(&mut self, text: &str) {
+        self.len = 0;
+        for byte in text.bytes().filter(|byte| (FIRST..=LAST).contains(byte)) {
+            if self.len == N {
+                break;
+            }
+            self.text[self.len] = byte;
+            self.len += 1;
+        }
+        self.initial = (self.text, self.len);
+        self.cursor = self.len.min(N.saturating_sub(1));
+        self.typed = None;
+        self.state = EditState::Editing;
+    }
+
+    /// Returns whether the text is being edited, or was confirmed or cancelled.
+    pub fn state(&self) -> EditState {
+        self.state
+    }
+
+    /// Reacts to an input event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the event was used. Events are ignored once editing has ended.
+    pub fn handle(&mut self, event: InputEvent) -> bool {
+        if self.state != EditState::Editing || N == 0 {
+            return false;
+        }
+        // Only the character typed by this event stays visible in a masked field.
+        self.typed = None;
+        match event {
+            InputEvent::Up => self.cycle(1),
+            InputEvent::Down => self.cycle(-1),
+            InputEvent::Encoder(detents) => self.cycle(detents as i16),
+            InputEvent::Left => self.cursor = self.cursor.saturating_sub(1),
+            InputEvent::Right => self.cursor = (self.cursor + 1).min(self.len).min(N - 1),
+            InputEvent::Char(BACKSPACE) => self.backspace(),
+            InputEvent::Char(ch) if ch.is_ascii() && (FIRST..=LAST).contains(&(ch as u8)) => {
+                self.put(ch as u8);
+                self.cursor = (self.cursor + 1).min(N - 1);
+            }
+            InputEvent::Char(_) => return false,
+            InputEvent::Select => self.state = EditState::Confirmed,
+            InputEvent::Back => {
+                (self.text, self.len) = self.initial;
+                self.state = EditState::Cancelled;
+            }
+        }
+        true
+    }
+
+    /// Draws the visible part of the text and places the blinking cursor while editing.
+    /// A masked field hides the character just typed on the first render after it expires,
+    /// so keep rendering while editing.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `col` - The column of the first cell (0-indexed).
+    /// * `row` - The row to draw on (0-indexed).
+    /// * `width` - The number of cells the field takes.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the field is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the field does not fit on the display or there is an error while sending data.
+    pub fn render(
+        &mut self,
+        lcd: &mut Lcd,
+        col: impl Into<Col>,
+        row: impl Into<Row>,
+        width: u8,
+    ) -> anyhow::Result<()> {
+        let (col, row) = (col.into().0, row.into().0);
+        if width == 0 || col.saturating_add(width) > lcd.cols() || row >= lcd.rows() {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+        self.scroll_to_cursor(width as usize);
+        lcd.set_cursor(col, row)?;
+        for i in 0..width as usize {
+            lcd.write_byte(self.visible(i))?;
+        }
+        if self.state != EditState::Editing {
+            return lcd.blink(false);
+        }
+        lcd.set_cursor(col + (self.cursor - self.scroll) as u8, row)?;
+        lcd.blink(true)
+    }
+
+    /// Returns the code shown in a cell of the field.
+    fn visible(&self, cell: usize) -> u8 {
+        let index = self.scroll + cell;
+        if index >= self.len {
+            return b' ';
+        }
+        let just_typed = self
+            .typed
+            .is_some_and(|(at, when)| at == index && when.elapsed() < REVEAL);
+        match self.masked && !self.revealed && !just_typed {
+            true => MASK,
+            false => self.text[index],
+        }
+    }
+
+    fn scroll_to_cursor(&mut self, width: usize) {
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + width {
+            self.scroll = self.cursor + 1 - width;
+        }
+    }
+
+    /// Writes a character at the cursor, adding it if the cursor is past the end.
+    fn put(&mut self, byte: u8) {
+        if self.cursor < self.len {
+            self.text[self.cursor] = byte;
+            self.typed = Some((self.cursor, AnimClock::now()));
+        } else if self.len < N {
+            self.text[self.len] = byte;
+            self.typed = Some((self.len, AnimClock::now()));
+            self.len += 1;
+        }
+    }
+
+    fn cycle(&mut self, steps: i16) {
+        // A new cell lands on `START` with the first step either way.
+        let current = match self.cursor < self.len {
+            true => self.text[self.cursor] as i16,
+            false => START as i16 - steps.signum(),
+        };
+        let span = (LAST - FIRST + 1) as i16;
+        let next = (current - FIRST as i16 + steps).rem_euclid(span) + FIRST as i16;
+        self.put(next as u8);
+    }
+
+    fn backspace(&mut self) {
+        let at = match self.cursor {
+            // At the end of a full field the cursor sits on the last character.
+            cursor if cursor == self.len.saturating_sub(1) && self.len == N => cursor,
+            0 => return,
+            cursor => cursor - 1,
+        };
+        if at >= self.len {
+            return;
+        }
+        self.text.copy_within(at + 1..self.len, at);
+        self.len -= 1;
+        self.cursor = at;
+    }
+}
+
+impl<const N: usize> Default for InputField<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Draws the field across the top row of its rectangle, with the compositor's software cursor
+/// on the cursor cell while editing.
+#[cfg(feature = "buffer")]
+impl<const N: usize> Widget for InputField<N> {
+    fn render(&mut self, canvas: &mut Canvas) -> anyhow::Result<()> {
+        let width = canvas.width() as usize;
+        if width == 0 {
+            return Ok(());
+        }
+        self.scroll_to_cursor(width);
+        for i in 0..width {
+            canvas.set(i as u8, 0, self.visible(i));
+        }
+        if self.state == EditState::Editing {
+            canvas.set_cursor((self.cursor - self.scroll) as u8, 0, CursorStyle::Underline);
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, event: InputEvent) -> bool {
+        InputField::handle(self, event)
+    }
+}
@@ -0,0 +1,232 @@
+use super::field::{InputField, BACKSPACE};
+use super::number::EditState;
+use crate::input::InputEvent;
+use crate::Lcd;
+#[cfg(feature = "buffer")]
+use crate::{AnimClock, Canvas, Rect, Widget};
+
+/// The key deleting the character before the cursor, drawn as the A00 ROM's left arrow.
+const DELETE: u8 = 0x7f;
+/// The key confirming the text, drawn as the A00 ROM's right arrow.
+const DONE: u8 = 0x7e;
+/// The rows of keys, each followed by [`DELETE`] and [`DONE`].
+const ROWS: [&[u8]; 4] = [
+    b"abcdefghijklmnopqrstuvwxyz",
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+    b"0123456789.-_:/@ ",
+    b"!\"#$%&'()*+,;<=>?[\\]^`{|}~",
+];
+
+/// Enters text by picking characters from rows of keys, for longer strings such as MQTT
+/// broker hostnames where cycling each cell through the character set would take too long.
+///
+/// The top row shows the text being entered, in an [`InputField`] of up to `N` characters,
+/// and the rows below show a window of the keyboard: lowercase, uppercase, digits with the
+/// punctuation of hostnames and URLs, then the remaining symbols. Each row ends with `←`,
+/// deleting the last character, and `→`, confirming the text.
+///
+/// [`InputEvent::Left`] and [`Right`](InputEvent::Right) or an [`Encoder`](InputEvent::Encoder)
+/// move along a row, scrolling it across the display and wrapping at the ends, and
+/// [`Up`](InputEvent::Up) and [`Down`](InputEvent::Down) switch rows.
+/// [`Select`](InputEvent::Select) presses the key under the blinking cursor. A
+/// [`Char`](InputEvent::Char) from a keypad goes straight to the field, and
+/// [`Back`](InputEvent::Back) cancels, putting back the text editing started from.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut keyboard: SoftKeyboard<64> = SoftKeyboard::new();
+/// keyboard.set_text("mqtt.local");
+/// while keyboard.state() == EditState::Editing {
+///     if let Some(event) = encoder.next_event()? {
+///         keyboard.handle(event);
+///     }
+///     keyboard.render(&mut lcd)?;
+/// }
+/// ```
+pub struct SoftKeyboard<const N: usize> {
+    field: InputField<N>,
+    row: usize,
+    key: usize,
+    scroll: usize,
+}
+
+impl<const N: usize> SoftKeyboard<N> {
+    /// Creates a keyboard with an empty field and the cursor on `a`.
+    pub fn new() -> Self {
+        Self {
+            field: InputField::new(),
+            row: 0,
+            key: 0,
+            scroll: 0,
+        }
+    }
+
+    /// Masks the text, for passwords, see [`InputField::with_mask`].
+    pub fn with_mask(mut self) -> Self {
+        self.field = self.field.with_mask();
+        self
+    }
+
+    /// Shows or hides again the whole text of a masked keyboard.
+    pub fn reveal(&mut self) {
+        self.field.reveal();
+    }
+
+    /// Returns the text entered.
+    pub fn text(&self) -> &str {
+        self.field.text()
+    }
+
+    /// Sets the text and starts editing from it, see [`InputField::set_text`].
+    pub fn set_text(&mut self, text: &str) {
+        self.field.set_text(text);
+    }
+
+    /// Returns whether the text is being edited, or was confirmed or cancelled.
+    pub fn state(&self) -> EditState {
+        self.field.state()
+    }
+
+    /// Reacts to an input event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the event was used. Events are ignored once editing has ended.
+    pub fn handle(&mut self, event: InputEvent) -> bool {
+        if self.state() != EditState::Editing {
+            return false;
+        }
+        let keys = Self::keys(self.row) as isize;
+        match event {
+            InputEvent::Left => self.key = (self.key as isize - 1).rem_euclid(keys) as usize,
+            InputEvent::Right => self.key = (self.key + 1) % keys as usize,
+            InputEvent::Encoder(detents) => {
+                self.key = (self.key as isize + detents as isize).rem_euclid(keys) as usize;
+            }
+            InputEvent::Up => self.switch_row(ROWS.len() - 1),
+            InputEvent::Down => self.switch_row(1),
+            InputEvent::Select => {
+                let press = match self.code(self.row, self.key) {
+                    DELETE => InputEvent::Char(BACKSPACE),
+                    DONE => InputEvent::Select,
+                    code => InputEvent::Char(code as char),
+                };
+                self.field.handle(press);
+            }
+            InputEvent::Char(_) | InputEvent::Back => return self.field.handle(event),
+        }
+        true
+    }
+
+    /// Draws the field on the top row and a window of the keyboard on the rows below, with
+    /// the blinking cursor on the selected key.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on, at least two rows.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the keyboard is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the display has fewer than two rows or there is an error while sending data.
+    pub fn render(&mut self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        if lcd.rows() < 2 {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+        self.field.render(lcd, 0, 0, lcd.cols())?;
+        let width = lcd.cols() as usize;
+        self.scroll_to_key(width);
+        for line in 1..lcd.rows() {
+            lcd.set_cursor(0, line)?;
+            for cell in 0..width {
+                lcd.write_byte(self.visible(line as usize - 1, cell))?;
+            }
+        }
+        if self.state() != EditState::Editing {
+            return lcd.blink(false);
+        }
+        lcd.set_cursor((self.key - self.scroll) as u8, 1)?;
+        lcd.blink(true)
+    }
+
+    /// Returns the number of keys in a row, including [`DELETE`] and [`DONE`].
+    fn keys(row: usize) -> usize {
+        ROWS[row].len() + 2
+    }
+
+    /// Returns the code of a key.
+    fn code(&self, row: usize, key: usize) -> u8 {
+        let chars = ROWS[row];
+        match key.checked_sub(chars.len()) {
+            None => chars[key],
+            Some(0) => DELETE,
+            Some(_) => DONE,
+        }
+    }
+
+    /// Returns the code shown in a cell of a keyboard line, counted down from the selected
+    /// row.
+    fn visible(&self, line: usize, cell: usize) -> u8 {
+        let row = (self.row + line) % ROWS.len();
+        let key = self.scroll + cell;
+        if key < Self::keys(row) {
+            self.code(row, key)
+        } else {
+            b' '
+        }
+    }
+
+    /// Moves down `by` rows, wrapping around, keeping the cursor within the new row.
+    fn switch_row(&mut self, by: usize) {
+        self.row = (self.row + by) % ROWS.len();
+        self.key = self.key.min(Self::keys(self.row) - 1);
+    }
+
+    fn scroll_to_key(&mut self, width: usize) {
+        if self.key < self.scroll {
+            self.scroll = self.key;
+        } else if self.key >= self.scroll + width {
+            self.scroll = self.key + 1 - width;
+        }
+    }
+}
+
+impl<const N: usize> Default for SoftKeyboard<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Draws the field on the top row of its rectangle and the keyboard below it. The selected
+/// key blinks in the frame itself, in step with [`AnimClock::blink_on`].
+#[cfg(feature = "buffer")]
+impl<const N: usize> Widget for SoftKeyboard<N> {
+    fn render(&mut self, canvas: &mut Canvas) -> anyhow::Result<()> {
+        let (width, height) = (canvas.width(), canvas.height());
+        Widget::render(&mut self.field, &mut canvas.sub(Rect::new(0, 0, width, 1)))?;
+        self.scroll_to_key(width as usize);
+        for line in 1..height {
+            for cell in 0..width {
+                canvas.set(cell, line, self.visible(line as usize - 1, cell as usize));
+            }
+        }
+        let hidden = !AnimClock::blink_on();
+        if self.state() == EditState::Editing && hidden && height > 1 {
+            canvas.set((self.key - self.scroll) as u8, 1, b' ');
+        }
+        Ok(())
+    }
+
+    fn blinks(&self) -> bool {
+        self.state() == EditState::Editing
+    }
+
+    fn handle(&mut self, event: InputEvent) -> bool {
+        SoftKeyboard::handle(self, event)
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{Lcd, ScrollUp};
+
+/// A scrolling log of the last `R` lines, each up to `W` characters wide.
+///
+/// New lines are added at the bottom and older lines move up. The move is animated with a
+/// [`ScrollUp`] driven by [`tick`](Self::tick): with a font set (see
+/// [`with_smooth`](Self::with_smooth)) the lines slide up a pixel row at a time through custom
+/// characters, otherwise, or when a frame needs more CGRAM slots than there are, they move up
+/// a whole row.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut log: Logger<2, 16> = Logger::new(0).with_smooth(font, 0);
+/// log.log("Connecting...");
+/// while log.tick(&mut lcd)? {
+///     Ets::delay_ms(30);
+/// }
+/// ```
+pub struct Logger<const R: usize, const W: usize> {
+    lines: [[char; W]; R],
+    scrolled_out: [char; W],
+    first_row: u8,
+    scroll: ScrollUp,
+}
+
+impl<const R: usize, const W: usize> Logger<R, W> {
+    /// Creates an empty log occupying `R` rows starting at `first_row`.
+    pub const fn new(first_row: u8) -> Self {
+        Self {
+            lines: [[' '; W]; R],
+            scrolled_out: [' '; W],
+            first_row,
+            scroll: ScrollUp::new(4),
+        }
+    }
+
+    /// Sets the number of frames a smooth scroll takes; 0 or 1 disables the animation.
+    pub const fn with_scroll_steps(mut self, steps: u8) -> Self {
+        self.scroll = self.scroll.with_steps(steps);
+        self
+    }
+
+    /// Slides the lines up a pixel row at a time, see [`ScrollUp::with_smooth`].
+    ///
+    /// # Arguments
+    ///
+    /// * `font` - Returns the 5x8 bitmap of a character code as the display shows it.
+    /// * `first_slot` - The first CGRAM location the log may use; it uses every one from there to 7.
+    pub const fn with_smooth(mut self, font: fn(u8) -> Option<[u8; 8]>, first_slot: u8) -> Self {
+        self.scroll = self.scroll.with_smooth(font, first_slot);
+        self
+    }
+
+    /// Appends a line at the bottom, truncating it to `W` characters.
+    ///
+    /// The display is not touched until the next [`tick`](Self::tick) or [`render`](Self::render).
+    pub fn log(&mut self, text: &str) {
+        if R == 0 {
+            return;
+        }
+        self.scrolled_out = self.lines[0];
+        self.lines.rotate_left(1);
+        let line = &mut self.lines[R - 1];
+        line.fill(' ');
+        for (cell, ch) in line.iter_mut().zip(text.chars()) {
+            *cell = ch;
+        }
+        self.scroll.start();
+    }
+
+    /// Renders the next frame of a running scroll.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - If more frames are left and `tick` should be called again.
+    /// * `Ok(false)` - If the log is fully drawn.
+    /// * `Err(anyhow::Error)` - If there is an error while creating the characters, printing or setting the cursor.
+    pub fn tick(&mut self, lcd: &mut Lcd) -> anyhow::Result<bool> {
+        if !self.scroll.is_running() {
+            return Ok(false);
+        }
+        let offset = self.scroll.advance();
+        if self.scroll.is_running() && self.render_shifted(lcd, offset)? {
+            return Ok(true);
+        }
+        self.render(lcd)?;
+        Ok(false)
+    }
+
+    /// Draws the log in its final state, skipping any running scroll.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the log is successfully drawn.
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character or setting the cursor.
+    pub fn render(&mut self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        self.scroll.finish();
+        for (row, line) in self.lines.iter().enumerate() {
+            lcd.set_cursor(0, self.first_row + row as u8)?;
+            for &ch in line {
+                lcd.print(ch)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws every row moved up by `offset` pixel rows over the top of the row below, the
+    /// line that scrolled out above the first.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `false` if nothing was drawn because there is no font, a character has no bitmap or the frame needs more CGRAM slots than the log may use.
+    /// * `Err(anyhow::Error)` - If a character cannot be shown, or there is an error while creating the characters or printing.
+    fn render_shifted(&self, lcd: &mut Lcd, offset: u8) -> anyhow::Result<bool> {
+        let Some(mut glyphs) = self.scroll.glyphs(offset) else {
+            return Ok(false);
+        };
+        let charmap = *lcd.charmap();
+        let encode = |ch: char| Ok::<_, anyhow::Error>(charmap.encode(ch)?.unwrap_or(b' '));
+        let mut codes = [[b' '; W]; R];
+        for (row, codes) in codes.iter_mut().enumerate() {
+            let upper = if row == 0 {
+                &self.scrolled_out
+            } else {
+                &self.lines[row - 1]
+            };
+            for ((code, &above), &below) in codes.iter_mut().zip(upper).zip(&self.lines[row]) {
+                match glyphs.cell(encode(above)?, encode(below)?) {
+                    Some(cell) => *code = cell,
+                    None => return Ok(false),
+                }
+            }
+        }
+        glyphs.upload(lcd)?;
+        for (row, codes) in codes.iter().enumerate() {
+            lcd.write_cells(0, self.first_row + row as u8, codes)?;
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{glyphs, Lcd};
+#[cfg(feature = "buffer")]
+use crate::{Canvas, Widget};
+use core::iter;
+
+/// The width of a character cell in pixels, and so the number of smooth steps per cell.
+const CELL_PIXELS: u8 = 5;
+
+/// Returns the bitmap of a character as the display's ROM draws it.
+type Font = fn(char) -> Option<[u8; 8]>;
+
+/// Text that scrolls from right to left, one cell per step, and starts over after a gap.
+///
+/// By default the text runs along one row. In ticker tape mode (see
+/// [`with_tape`](Self::with_tape)) the rows are joined into one long line, the right edge of
+/// each row running into the left edge of the next: a 16x2 display becomes a 32-cell window,
+/// which suits news and price tickers. The text keeps scrolling even when it would fit.
+///
+/// A single-row marquee can also scroll pixel by pixel (see [`with_smooth`](Self::with_smooth)):
+/// each cell then shows the right part of one character and the left part of the next, drawn
+/// as custom characters. That takes one CGRAM slot per distinct cell in view, so it suits short
+/// texts with few distinct characters, such as a clock or a price; whenever the view needs more
+/// slots than there are, the marquee falls back to moving a whole cell at a time.
+///
+/// In a [`Compositor`](crate::Compositor) the marquee steps once per render, so the interval
+/// it is added with sets the speed.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut ticker = Marquee::new("BTC 64,210 +1.2%  ETH 3,105 -0.4%", 250).with_tape();
+/// loop {
+///     Ets::delay_ms(50);
+///     if ticker.tick(50) {
+///         ticker.render(&mut lcd, 0)?;
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Marquee<'t> {
+    text: &'t str,
+    gap: usize,
+    tape: bool,
+    interval_ms: u32,
+    elapsed_ms: u32,
+    offset: usize,
+    font: Option<Font>,
+    first_slot: u8,
+    pixel: u8,
+}
+
+impl<'t> Marquee<'t> {
+    /// Creates a marquee on one row with a gap of 4 spaces, starting with the text at the
+    /// left edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to scroll.
+    /// * `interval_ms` - How long each step is shown when driven by [`tick`](Self::tick), in milliseconds.
+    pub const fn new(text: &'t str, interval_ms: u32) -> Self {
+        Self {
+            text,
+            gap: 4,
+            tape: false,
+            interval_ms,
+            elapsed_ms: 0,
+            offset: 0,
+            font: None,
+            first_slot: 0,
+            pixel: 0,
+        }
+    }
+
+    /// Joins the rows into one ticker tape instead of scrolling along a single row.
+    pub const fn with_tape(mut self) -> Self {
+        self.tape = true;
+        self
+    }
+
+    /// Sets the number of spaces between the end of the text and its next start.
+    pub const fn with_gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Scrolls one pixel at a time instead of one cell, in single-row mode and when drawn with
+    /// [`render`](Self::render).
+    ///
+    /// The controller's character ROM cannot be read, so the bitmaps of the characters come
+    /// from `font`; a character it has no bitmap for makes that step fall back to moving by
+    /// whole cells. A cell takes five steps, and [`tick`](Self::tick) makes each one a fifth
+    /// of the interval, so the text moves as fast as it would cell by cell.
+    ///
+    /// # Arguments
+    ///
+    /// * `font` - Returns the 5x8 bitmap of a character as the display shows it, e.g. built with [`glyphs`](crate::glyphs).
+    /// * `first_slot` - The first CGRAM location the marquee may use; it uses every one from there to 7.
+    pub const fn with_smooth(mut self, font: Font, first_slot: u8) -> Self {
+        self.font = Some(font);
+        self.first_slot = first_slot;
+        self
+    }
+
+    /// Returns `true` in ticker tape mode.
+    pub fn is_tape(&self) -> bool {
+        self.tape
+    }
+
+    /// Replaces the text and starts it again from the left edge.
+    pub fn set_text(&mut self, text: &'t str) {
+        self.text = text;
+        self.offset = 0;
+        self.pixel = 0;
+    }
+
+    /// Moves the text one cell to the left, or one pixel when scrolling smoothly.
+    pub fn step(&mut self) {
+        if self.is_smooth() {
+            self.pixel = (self.pixel + 1) % CELL_PIXELS;
+            if self.pixel != 0 {
+                return;
+            }
+        }
+        self.step_cell();
+    }
+
+    /// Moves the text one cell to the left.
+    fn step_cell(&mut self) {
+        let period = self.text.chars().count() + self.gap;
+        self.offset = (self.offset + 1) % period.max(1);
+    }
+
+    /// Advances the scroll timer.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed_ms` - The time since the previous call, in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the text moved and the marquee should be rendered again.
+    pub fn tick(&mut self, elapsed_ms: u32) -> bool {
+        self.elapsed_ms = self.elapsed_ms.saturating_add(elapsed_ms);
+        let interval_ms = if self.is_smooth() {
+            self.interval_ms / CELL_PIXELS as u32
+        } else {
+            self.interval_ms
+        };
+        if self.elapsed_ms < interval_ms.max(1) {
+            return false;
+        }
+        self.elapsed_ms = 0;
+        self.step();
+        true
+    }
+
+    /// Draws the marquee across the full width of the display.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `row` - The row to draw on (0-indexed); in ticker tape mode, the first row of the tape,
+    ///   which runs on to the last row of the display.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the marquee is successfully drawn.
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character or setting the cursor.
+    pub fn render(&self, lcd: &mut Lcd, row: u8) -> anyhow::Result<()> {
+        if self.pixel != 0 && self.render_smooth(lcd, row)? {
+            return Ok(());
+        }
+        let cols = lcd.cols();
+        let rows = if self.tape {
+            lcd.rows().saturating_sub(row)
+        } else {
+            1
+        };
+        let mut cells = self.window();
+        for r in row..row + rows {
+            lcd.set_cursor(0, r)?;
+            for ch in (&mut cells).chain(iter::repeat(' ')).take(cols.into()) {
+                lcd.print(ch)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws the row with every cell straddling two characters, as custom characters.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - `false` if nothing was drawn because a character has no bitmap or the view needs more CGRAM slots than the marquee may use.
+    /// * `Err(anyhow::Error)` - If there is an error while creating the characters or printing.
+    fn render_smooth(&self, lcd: &mut Lcd, row: u8) -> anyhow::Result<bool> {
+        let Some(font) = self.font else {
+            return Ok(false);
+        };
+        let bitmap = |ch: char| if ch == ' ' { Some([0; 8]) } else { font(ch) };
+        let capacity = 8usize.saturating_sub(self.first_slot.into());
+        let mut slots = [[0u8; 8]; 8];
+        let mut used = 0;
+        let mut codes = [b' '; 40];
+        let cols = (lcd.cols() as usize).min(codes.len());
+        let mut cells = self.window();
+        let mut left = cells.next().unwrap_or(' ');
+        for code in &mut codes[..cols] {
+            let right = cells.next().unwrap_or(' ');
+            let pair = (left, right);
+            left = right;
+            if pair == (' ', ' ') {
+                continue;
+            }
+            let (Some(a), Some(b)) = (bitmap(pair.0), bitmap(pair.1)) else {
+                return Ok(false);
+            };
+            let shift = self.pixel as i8;
+            let glyph = glyphs::overlay(
+                glyphs::shift(a, -shift, 0),
+                glyphs::shift(b, CELL_PIXELS as i8 - shift, 0),
+            );
+            if glyph == [0; 8] {
+                continue;
+            }
+            let slot = match slots[..used].iter().position(|slot| *slot == glyph) {
+                Some(slot) => slot,
+                None if used < capacity => {
+                    slots[used] = glyph;
+                    used += 1;
+                    used - 1
+                }
+                None => return Ok(false),
+            };
+            *code = self.first_slot + slot as u8;
+        }
+        // Slots keep their place from step to step, so only the rows that moved are uploaded.
+        for (slot, glyph) in slots[..used].iter().enumerate() {
+            lcd.create_custom_chars(self.first_slot + slot as u8, glyph)?;
+        }
+        lcd.set_cursor(0, row)?;
+        for &code in &codes[..cols] {
+            lcd.write_byte(code)?;
+        }
+        Ok(true)
+    }
+
+    /// Returns `true` if the marquee moves a pixel at a time.
+    fn is_smooth(&self) -> bool {
+        self.font.is_some() && !self.tape
+    }
+
+    /// Returns the characters from the left edge of the window on, with the text repeating
+    /// after each gap.
+    fn window(&self) -> impl Iterator<Item = char> + 't {
+        let gap = iter::repeat(' ').take(self.gap);
+        self.text.chars().chain(gap).cycle().skip(self.offset)
+    }
+}
+
+/// Draws the marquee into the widget's rectangle, then steps it a whole cell, since a
+/// [`Canvas`] cannot create custom characters.
+#[cfg(feature = "buffer")]
+impl Widget for Marquee<'_> {
+    fn render(&mut self, canvas: &mut Canvas) -> anyhow::Result<()> {
+        let rows = if self.tape { canvas.height() } else { 1 };
+        let mut cells = self.window();
+        let mut buf = [0; 4];
+        for row in 0..rows {
+            for col in 0..canvas.width() {
+                let ch = cells.next().unwrap_or(' ');
+                canvas.print(col, row, ch.encode_utf8(&mut buf))?;
+            }
+        }
+        self.pixel = 0;
+        self.step_cell();
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+// Part of the `alloc` layer, which trades static sizing for owned items.
+#![allow(clippy::disallowed_types, clippy::disallowed_methods)]
+
+use super::number::EditState;
+use super::picker::Picker;
+use crate::input::InputEvent;
+#[cfg(feature = "buffer")]
+use crate::{Canvas, Widget};
+use crate::{Col, Lcd, Row};
+
+/// A [`Picker`] that owns its items, for menus built at run time, such as a list of files or
+/// of devices found on the network (`alloc` feature).
+///
+/// Items can be added and removed while the menu is shown; the selection stays on the same
+/// index, moved back if the list got shorter. It reacts to input and draws exactly like a
+/// [`Picker`].
+///
+/// # Example
+///
+/// ```ignore
+/// let mut menu: Menu = scan()?.iter().map(|device| device.name.clone()).collect();
+/// menu.push("Rescan");
+/// while menu.state() == EditState::Editing {
+///     if let Some(event) = keypad.next_event()? {
+///         menu.handle(event);
+///     }
+///     menu.render(&mut lcd, 0, 0, 2)?;
+/// }
+/// if let Some(choice) = menu.selected_item() {
+///     lcd.print_fmt(format_args!("Using {choice}"))?;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Menu {
+    items: Vec<String>,
+    /// The selection, first item in view and window height of the picker.
+    view: (usize, usize, usize),
+    initial: usize,
+    wrap: bool,
+    state: EditState,
+}
+
+impl Menu {
+    /// Creates an empty menu with wraparound on.
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            view: (0, 0, 1),
+            initial: 0,
+            wrap: true,
+            state: EditState::Editing,
+        }
+    }
+
+    /// Stops the selection at the first and last items instead of wrapping around.
+    pub fn without_wraparound(mut self) -> Self {
+        self.wrap = false;
+        self
+    }
+
+    /// Adds an item at the end.
+    pub fn push(&mut self, item: impl Into<String>) {
+        self.items.push(item.into());
+    }
+
+    /// Inserts an item before `index`, or at the end if `index` is past it.
+    pub fn insert(&mut self, index: usize, item: impl Into<String>) {
+        self.items.insert(index.min(self.items.len()), item.into());
+    }
+
+    /// Removes and returns the item at `index`, if there is one.
+    pub fn remove(&mut self, index: usize) -> Option<String> {
+        (index < self.items.len()).then(|| self.items.remove(index))
+    }
+
+    /// Removes every item.
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Returns the items.
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    /// Returns the index of the selected item.
+    pub fn selected(&self) -> usize {
+        self.view.0.min(self.items.len().saturating_sub(1))
+    }
+
+    /// Returns the selected item, or `None` if the menu is empty.
+    pub fn selected_item(&self) -> Option<&str> {
+        self.items.get(self.selected()).map(String::as_str)
+    }
+
+    /// Selects an item and starts picking from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The item to select, clamped to the list.
+    pub fn select(&mut self, index: usize) {
+        self.with_picker(|picker| picker.select(index));
+        self.initial = self.view.0;
+        self.state = EditState::Editing;
+    }
+
+    /// Returns whether an item is being picked, or was confirmed or cancelled.
+    pub fn state(&self) -> EditState {
+        self.state
+    }
+
+    /// Reacts to an input event, see [`Picker::handle`].
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the event was used.
+    pub fn handle(&mut self, event: InputEvent) -> bool {
+        if self.state != EditState::Editing {
+            return false;
+        }
+        let (used, state) = self.with_picker(|picker| (picker.handle(event), picker.state()));
+        if state == EditState::Cancelled {
+            // The picker only knows the selection it was rebuilt with.
+            self.view.0 = self.initial;
+        }
+        self.state = state;
+        used
+    }
+
+    /// Draws a window of items, see [`Picker::render`].
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `col` - The column of the first cell (0-indexed).
+    /// * `row` - The top row (0-indexed).
+    /// * `height` - The number of rows to use.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the list is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the window does not fit on the display or there is an error while sending data.
+    pub fn render(
+        &mut self,
+        lcd: &mut Lcd,
+        col: impl Into<Col>,
+        row: impl Into<Row>,
+        height: u8,
+    ) -> anyhow::Result<()> {
+        let (col, row) = (col.into(), row.into());
+        self.with_picker(|picker| picker.render(lcd, col, row, height))
+    }
+
+    /// Runs `f` on a picker over the items, keeping the selection and scroll position it
+    /// leaves.
+    fn with_picker<R>(&mut self, f: impl FnOnce(&mut Picker) -> R) -> R {
+        let items: Vec<&str> = self.items.iter().map(String::as_str).collect();
+        let mut picker = Picker::new(&items);
+        if !self.wrap {
+            picker = picker.without_wraparound();
+        }
+        picker.restore(self.view);
+        let result = f(&mut picker);
+        self.view = picker.view();
+        result
+    }
+}
+
+impl Default for Menu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Menu {
+    fn from_iter<I: IntoIterator<Item = S>>(items: I) -> Self {
+        Self {
+            items: items.into_iter().map(Into::into).collect(),
+            ..Self::new()
+        }
+    }
+}
+
+impl<S: Into<String>> Extend<S> for Menu {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, items: I) {
+        self.items.extend(items.into_iter().map(Into::into));
+    }
+}
+
+/// Draws a window of items filling the widget's rectangle.
+#[cfg(feature = "buffer")]
+impl Widget for Menu {
+    fn render(&mut self, canvas: &mut Canvas) -> anyhow::Result<()> {
+        self.with_picker(|picker| Widget::render(picker, canvas))
+    }
+
+    fn handle(&mut self, event: InputEvent) -> bool {
+        Menu::handle(self, event)
+    }
+}
@@ -0,0 +1,87 @@
+//! Higher-level display widgets built on top of [`Lcd`](crate::Lcd).
+
+mod battery;
+mod dashboard;
+#[cfg(feature = "input")]
+mod datetime;
+mod diagnostics;
+#[cfg(feature = "input")]
+mod field;
+#[cfg(feature = "input")]
+mod keyboard;
+mod logger;
+mod marquee;
+#[cfg(all(feature = "alloc", feature = "input"))]
+mod menu;
+mod netinfo;
+#[cfg(feature = "input")]
+mod number;
+mod ota;
+#[cfg(feature = "alloc")]
+mod pages;
+mod pairing;
+#[cfg(feature = "input")]
+mod picker;
+mod reader;
+mod readout;
+#[cfg(feature = "input")]
+mod settings;
+mod sparkline;
+mod statusbar;
+#[cfg(feature = "input")]
+mod store;
+mod table;
+mod wifi;
+#[cfg(feature = "input")]
+mod wifisetup;
+#[cfg(feature = "input")]
+mod wizard;
+
+pub use battery::BatteryWidget;
+pub use dashboard::{EnvDashboard, Quantity};
+#[cfg(feature = "input")]
+pub use datetime::{DateTime, DateTimeEditor, DateTimeFields};
+pub use diagnostics::{Diagnostics, DiagnosticsScreen, ResetReason};
+#[cfg(feature = "input")]
+pub use field::{InputField, BACKSPACE};
+#[cfg(feature = "input")]
+pub use keyboard::SoftKeyboard;
+pub use logger::Logger;
+pub use marquee::Marquee;
+#[cfg(all(feature = "alloc", feature = "input"))]
+pub use menu::Menu;
+pub use netinfo::NetInfoRow;
+#[cfg(feature = "input")]
+pub use number::{EditState, NumberEditor};
+pub use ota::{OtaPhase, OtaScreen};
+#[cfg(feature = "alloc")]
+pub use pages::Pages;
+pub use pairing::PairingCode;
+#[cfg(feature = "input")]
+pub use picker::Picker;
+pub use reader::Reader;
+pub use readout::{Alert, Readout, Threshold};
+#[cfg(feature = "input")]
+pub use settings::{NumberSetting, Setting, SettingsScreen};
+pub use sparkline::Sparkline;
+pub use statusbar::{Icon, StatusBar, StatusSlot};
+#[cfg(feature = "input")]
+pub use store::{NvsStore, SettingsStore};
+pub use table::{Align, Table};
+pub use wifi::{WifiState, WifiStatusWidget};
+#[cfg(feature = "input")]
+pub use wifisetup::{Credentials, SetupStep, WifiBackend, WifiSetupFlow};
+#[cfg(feature = "input")]
+pub use wizard::{StepInput, StepValue, Validation, Wizard, WizardStep};
+
+/// Prints a whole row of text from the first column, truncated or padded with spaces to the
+/// width of the display.
+fn print_line(lcd: &mut crate::Lcd, row: u8, text: &str) -> anyhow::Result<()> {
+    let (text, len) = lcd.charmap().truncate(text, lcd.cols() as usize);
+    lcd.set_cursor(0, row)?;
+    lcd.print_str(text)?;
+    for _ in len..lcd.cols() as usize {
+        lcd.print(' ')?;
+    }
+    Ok(())
+}
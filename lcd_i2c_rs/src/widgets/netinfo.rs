@@ -0,0 +1,232 @@
+use std::net::Ipv4Addr;
+
+use crate::fmt::{fmt_buf, uptime, FmtBuf};
+use crate::Lcd;
+
+/// The longest hostname that is stored.
+const MAX_HOSTNAME: usize = 32;
+
+/// The fields a [`NetInfoRow`] cycles through, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Ip,
+    Hostname,
+    Mac,
+    Uptime,
+}
+
+const FIELDS: [Field; 4] = [Field::Ip, Field::Hostname, Field::Mac, Field::Uptime];
+
+/// A single status row that cycles between the IP address, hostname, MAC address and uptime.
+///
+/// Fields that have not been set are skipped. Each field is shown with a short label, padded
+/// to the full row and truncated if it does not fit; the MAC address drops its colons on
+/// displays narrower than 21 columns.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut net = NetInfoRow::new(3000);
+/// net.set_ip(Some(Ipv4Addr::new(192, 168, 1, 20)));
+/// net.set_hostname("esp32-shed")?;
+/// net.set_mac(Some(mac));
+/// loop {
+///     Ets::delay_ms(100);
+///     net.set_uptime(uptime_secs());
+///     if net.tick(100) {
+///         net.render(&mut lcd, 3)?;
+///     }
+/// }
+/// ```
+pub struct NetInfoRow {
+    ip: Option<Ipv4Addr>,
+    hostname: [u8; MAX_HOSTNAME],
+    hostname_len: usize,
+    mac: Option<[u8; 6]>,
+    uptime_secs: Option<u32>,
+    interval_ms: u32,
+    elapsed_ms: u32,
+    current: usize,
+    dirty: bool,
+}
+
+impl NetInfoRow {
+    /// Creates a row with no fields set.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval_ms` - How long each field is shown before moving to the next, in milliseconds.
+    pub const fn new(interval_ms: u32) -> Self {
+        Self {
+            ip: None,
+            hostname: [0; MAX_HOSTNAME],
+            hostname_len: 0,
+            mac: None,
+            uptime_secs: None,
+            interval_ms,
+            elapsed_ms: 0,
+            current: 0,
+            dirty: true,
+        }
+    }
+
+    /// Sets the IP address, or hides it with `None`.
+    pub fn set_ip(&mut self, ip: Option<Ipv4Addr>) {
+        if ip != self.ip {
+            self.ip = ip;
+            self.touch(Field::Ip);
+        }
+    }
+
+    /// Sets the hostname, or hides it with an empty string.
+    ///
+    /// # Arguments
+    ///
+    /// * `hostname` - The hostname.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the hostname is stored.
+    /// * `Err(anyhow::Error)` - If the hostname is longer than 32 bytes.
+    pub fn set_hostname(&mut self, hostname: &str) -> anyhow::Result<()> {
+        if hostname.len() > MAX_HOSTNAME {
+            return Err(anyhow::anyhow!("Hostname too long"));
+        }
+        if hostname.as_bytes() != &self.hostname[..self.hostname_len] {
+            self.hostname[..hostname.len()].copy_from_slice(hostname.as_bytes());
+            self.hostname_len = hostname.len();
+            self.touch(Field::Hostname);
+        }
+        Ok(())
+    }
+
+    /// Sets the MAC address, or hides it with `None`.
+    pub fn set_mac(&mut self, mac: Option<[u8; 6]>) {
+        if mac != self.mac {
+            self.mac = mac;
+            self.touch(Field::Mac);
+        }
+    }
+
+    /// Sets the uptime in seconds, or hides it with `None`.
+    pub fn set_uptime(&mut self, uptime_secs: Option<u32>) {
+        if uptime_secs != self.uptime_secs {
+            self.uptime_secs = uptime_secs;
+            self.touch(Field::Uptime);
+        }
+    }
+
+    /// Advances the rotation.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed_ms` - The time since the previous call, in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the row is out of date and should be rendered again.
+    pub fn tick(&mut self, elapsed_ms: u32) -> bool {
+        self.elapsed_ms = self.elapsed_ms.saturating_add(elapsed_ms);
+        if self.interval_ms > 0 && self.elapsed_ms >= self.interval_ms {
+            self.elapsed_ms = 0;
+            let next = self.next_set(self.current + 1);
+            if next != self.current {
+                self.current = next;
+                self.dirty = true;
+            }
+        }
+        self.dirty
+    }
+
+    /// Draws the current field across a row.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `row` - The row to draw on (0-indexed).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the row is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the row is out of bounds or there is an error while sending data.
+    pub fn render(&mut self, lcd: &mut Lcd, row: u8) -> anyhow::Result<()> {
+        self.current = self.next_set(self.current);
+        let text = self.format(FIELDS[self.current], lcd.cols());
+        let cols = lcd.cols() as usize;
+        let (text, len) = lcd.charmap().truncate(text.as_str(), cols);
+        lcd.set_cursor(0, row)?;
+        lcd.print_str(text)?;
+        for _ in len..cols {
+            lcd.print(' ')?;
+        }
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Marks the row for redrawing if `field` is the one being shown.
+    fn touch(&mut self, field: Field) {
+        if FIELDS[self.current] == field || !self.is_set(FIELDS[self.current]) {
+            self.dirty = true;
+        }
+    }
+
+    fn is_set(&self, field: Field) -> bool {
+        match field {
+            Field::Ip => self.ip.is_some(),
+            Field::Hostname => self.hostname_len > 0,
+            Field::Mac => self.mac.is_some(),
+            Field::Uptime => self.uptime_secs.is_some(),
+        }
+    }
+
+    /// Returns the first field from `start` onwards, wrapping around, that has a value. If none
+    /// has, returns `start` wrapped into range.
+    fn next_set(&self, start: usize) -> usize {
+        (0..FIELDS.len())
+            .map(|i| (start + i) % FIELDS.len())
+            .find(|&i| self.is_set(FIELDS[i]))
+            .unwrap_or(start % FIELDS.len())
+    }
+
+    fn format(&self, field: Field, cols: u8) -> FmtBuf<48> {
+        match field {
+            Field::Ip => match self.ip {
+                Some(ip) => fmt_buf!(48, "IP {}", ip),
+                None => FmtBuf::new(),
+            },
+            Field::Hostname => {
+                // Only whole strings are copied in, see `set_hostname`.
+                let hostname =
+                    core::str::from_utf8(&self.hostname[..self.hostname_len]).unwrap_or("");
+                fmt_buf!(48, "Host {}", hostname)
+            }
+            Field::Mac => match self.mac {
+                Some([a, b, c, d, e, f]) if cols >= 21 => fmt_buf!(
+                    48,
+                    "MAC {:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+                    a,
+                    b,
+                    c,
+                    d,
+                    e,
+                    f
+                ),
+                Some([a, b, c, d, e, f]) => fmt_buf!(
+                    48,
+                    "MAC {:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+                    a,
+                    b,
+                    c,
+                    d,
+                    e,
+                    f
+                ),
+                None => FmtBuf::new(),
+            },
+            Field::Uptime => match self.uptime_secs {
+                Some(secs) => fmt_buf!(48, "Up {}", uptime(secs).as_str()),
+                None => FmtBuf::new(),
+            },
+        }
+    }
+}
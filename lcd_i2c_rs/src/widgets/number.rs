@@ -0,0 +1,337 @@
+use crate::fmt::{fmt_buf, FmtBuf};
+use crate::i18n::Locale;
+use crate::input::InputEvent;
+#[cfg(feature = "buffer")]
+use crate::{AnimClock, Canvas, Widget};
+use crate::{Col, Lcd, Row};
+use std::time::{Duration, Instant};
+
+/// Changes closer together than this count as one hold and speed up.
+const STREAK_GAP: Duration = Duration::from_millis(300);
+/// The number of quick changes after which the step grows tenfold.
+const STREAK_PER_DECADE: u8 = 8;
+
+/// Where an editor is in its life.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EditState {
+    /// The value is being edited.
+    Editing,
+    /// The value was accepted with [`InputEvent::Select`].
+    Confirmed,
+    /// Editing was abandoned with [`InputEvent::Back`] and the value put back.
+    Cancelled,
+}
+
+/// Edits a number between bounds, such as a thermostat setpoint.
+///
+/// [`InputEvent::Up`] and [`Right`](InputEvent::Right) add a step,
+/// [`Down`](InputEvent::Down) and [`Left`](InputEvent::Left) take one away, and
+/// [`Encoder`](InputEvent::Encoder) moves by a step per detent. When changes come in quick
+/// succession, as from a held button that repeats, the step grows tenfold every few changes
+/// so large ranges can be crossed quickly. [`Select`](InputEvent::Select) confirms the value and
+/// [`Back`](InputEvent::Back) cancels, restoring the value editing started from.
+///
+/// The value is right-aligned in the width of the widest bound, followed by the unit, and the
+/// digit that the next step changes blinks.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut editor = NumberEditor::new(5.0, 30.0, 0.5).with_decimals(1).with_unit("C");
+/// editor.set_value(21.0);
+/// while editor.state() == EditState::Editing {
+///     if let Some(event) = keypad.next_event()? {
+///         editor.handle(event);
+///     }
+///     editor.render(&mut lcd, 0, 1)?;
+/// }
+/// ```
+pub struct NumberEditor<'u> {
+    value: i32,
+    initial: i32,
+    min: i32,
+    max: i32,
+    step: i32,
+    bounds: (f32, f32, f32),
+    decimals: u8,
+    unit: &'u str,
+    locale: Locale,
+    accelerate: bool,
+    streak: u8,
+    last_change: Option<Instant>,
+    state: EditState,
+}
+
+impl<'u> NumberEditor<'u> {
+    /// Creates an editor without decimals or unit, starting at `min`.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The smallest value.
+    /// * `max` - The largest value.
+    /// * `step` - How much one step changes the value.
+    pub fn new(min: f32, max: f32, step: f32) -> Self {
+        let mut editor = Self {
+            value: 0,
+            initial: 0,
+            min: 0,
+            max: 0,
+            step: 1,
+            bounds: (min, max, step),
+            decimals: 0,
+            unit: "",
+            locale: Locale::EN_GB,
+            accelerate: true,
+            streak: 0,
+            last_change: None,
+            state: EditState::Editing,
+        };
+        editor.scale_bounds();
+        editor.value = editor.min;
+        editor.initial = editor.min;
+        editor
+    }
+
+    /// Shows `decimals` places after the decimal point, at most 6. Set it before the value.
+    pub fn with_decimals(mut self, decimals: u8) -> Self {
+        let value = self.value();
+        self.decimals = decimals.min(6);
+        self.scale_bounds();
+        self.value = self.clamp(self.scale(value));
+        self.initial = self.value;
+        self
+    }
+
+    /// Shows `unit` after the value, e.g. `"C"` or `"%"`.
+    pub fn with_unit(mut self, unit: &'u str) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Writes the value with the decimal separator of `locale`, e.g. `21,5` for [`Locale::DE`].
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Keeps the step the same however quickly changes come.
+    pub fn without_acceleration(mut self) -> Self {
+        self.accelerate = false;
+        self
+    }
+
+    /// Returns the value.
+    pub fn value(&self) -> f32 {
+        self.to_f32(self.value)
+    }
+
+    /// Sets the value, clamped to the bounds and rounded to the decimals, and starts editing
+    /// from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to edit.
+    pub fn set_value(&mut self, value: f32) {
+        self.value = self.clamp(self.scale(value));
+        self.initial = self.value;
+        self.state = EditState::Editing;
+        self.streak = 0;
+    }
+
+    /// Returns whether the value is being edited, or was confirmed or cancelled.
+    pub fn state(&self) -> EditState {
+        self.state
+    }
+
+    /// Reacts to an input event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the event was used. Events are ignored once editing has ended.
+    pub fn handle(&mut self, event: InputEvent) -> bool {
+        if self.state != EditState::Editing {
+            return false;
+        }
+        match event {
+            InputEvent::Up | InputEvent::Right => self.nudge(1),
+            InputEvent::Down | InputEvent::Left => self.nudge(-1),
+            InputEvent::Encoder(detents) => self.nudge(detents as i32),
+            InputEvent::Select => self.state = EditState::Confirmed,
+            InputEvent::Back => {
+                self.value = self.initial;
+                self.state = EditState::Cancelled;
+            }
+            InputEvent::Char(_) => return false,
+        }
+        true
+    }
+
+    /// Draws the value and places the blinking cursor on the edited digit while editing.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `col` - The column of the first cell (0-indexed).
+    /// * `row` - The row to draw on (0-indexed).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the value is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the widget does not fit on the display or there is an error while sending data.
+    pub fn render(
+        &mut self,
+        lcd: &mut Lcd,
+        col: impl Into<Col>,
+        row: impl Into<Row>,
+    ) -> anyhow::Result<()> {
+        let (col, row) = (col.into().0, row.into().0);
+        let text = self.text();
+        let width = text.as_str().chars().count() as u8;
+        if col.saturating_add(width) > lcd.cols() || row >= lcd.rows() {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+        lcd.set_cursor(col, row)?;
+        lcd.print_str(text.as_str())?;
+        if self.state == EditState::Editing {
+            lcd.set_cursor(col + self.edited_index() as u8, row)?;
+            lcd.blink(true)
+        } else {
+            lcd.blink(false)
+        }
+    }
+
+    /// Returns the value and unit as drawn.
+    pub(super) fn text(&self) -> FmtBuf<32> {
+        let number = self.number(self.value);
+        let width = self.number_width();
+        fmt_buf!(32, "{:>width$}{}", number.as_str(), self.unit)
+    }
+
+    /// Returns the index within [`text`](Self::text) of the digit the next step changes.
+    fn edited_index(&self) -> usize {
+        let mut place = 0;
+        let mut step = self.effective_step();
+        while step % 10 == 0 && step > 0 {
+            step /= 10;
+            place += 1;
+        }
+        let point = usize::from(self.decimals > 0 && place >= self.decimals as usize);
+        let from_right = place + point;
+        self.number_width().saturating_sub(from_right + 1)
+    }
+
+    fn nudge(&mut self, steps: i32) {
+        let now = Instant::now();
+        let quick = self
+            .last_change
+            .is_some_and(|last| now.duration_since(last) < STREAK_GAP);
+        self.streak = if quick {
+            self.streak.saturating_add(1)
+        } else {
+            0
+        };
+        self.last_change = Some(now);
+        let delta = self.effective_step().saturating_mul(steps);
+        self.value = self.clamp(self.value.saturating_add(delta));
+    }
+
+    /// Returns the step, grown by the current streak of quick changes.
+    fn effective_step(&self) -> i32 {
+        if !self.accelerate {
+            return self.step;
+        }
+        // Once changes stop the streak is over, even before the next change resets it.
+        let held = self
+            .last_change
+            .is_some_and(|last| last.elapsed() < STREAK_GAP);
+        if !held {
+            return self.step;
+        }
+        let limit = ((self.max - self.min) / 4).max(self.step);
+        let mut step = self.step;
+        for _ in 0..self.streak / STREAK_PER_DECADE {
+            if step.saturating_mul(10) > limit {
+                break;
+            }
+            step *= 10;
+        }
+        step
+    }
+
+    fn scale_bounds(&mut self) {
+        let (min, max, step) = self.bounds;
+        let (min, max) = (self.scale(min), self.scale(max));
+        self.min = min.min(max);
+        self.max = max.max(min);
+        self.step = self.scale(step).abs().max(1);
+    }
+
+    fn clamp(&self, value: i32) -> i32 {
+        value.clamp(self.min, self.max)
+    }
+
+    fn unit_scale(&self) -> i32 {
+        10i32.pow(self.decimals as u32)
+    }
+
+    fn scale(&self, value: f32) -> i32 {
+        (value * self.unit_scale() as f32).round() as i32
+    }
+
+    fn to_f32(&self, value: i32) -> f32 {
+        value as f32 / self.unit_scale() as f32
+    }
+
+    /// Formats a scaled value with its decimals.
+    fn number(&self, value: i32) -> FmtBuf<16> {
+        let scale = self.unit_scale().unsigned_abs();
+        let sign = if value < 0 { "-" } else { "" };
+        let magnitude = value.unsigned_abs();
+        match self.decimals {
+            0 => fmt_buf!(16, "{}{}", sign, magnitude),
+            decimals => fmt_buf!(
+                16,
+                "{}{}{}{:0width$}",
+                sign,
+                magnitude / scale,
+                self.locale.decimal,
+                magnitude % scale,
+                width = decimals as usize
+            ),
+        }
+    }
+
+    /// Returns the width of the widest value.
+    fn number_width(&self) -> usize {
+        let min = self.number(self.min).as_str().len();
+        let max = self.number(self.max).as_str().len();
+        min.max(max)
+    }
+}
+
+/// Draws the value on the top row of its rectangle. The edited digit blinks in the frame
+/// itself, in step with [`AnimClock::blink_on`].
+#[cfg(feature = "buffer")]
+impl Widget for NumberEditor<'_> {
+    fn render(&mut self, canvas: &mut Canvas) -> anyhow::Result<()> {
+        let text = self.text();
+        canvas.print(0, 0, text.as_str())?;
+        let hidden = !AnimClock::blink_on();
+        if self.state == EditState::Editing && hidden {
+            canvas.set(self.edited_index() as u8, 0, b' ');
+        }
+        Ok(())
+    }
+
+    fn blinks(&self) -> bool {
+        self.state == EditState::Editing
+    }
+
+    fn handle(&mut self, event: InputEvent) -> bool {
+        NumberEditor::handle(self, event)
+    }
+}
@@ -0,0 +1,286 @@
+use super::print_line;
+use crate::fmt::{fmt_buf, FmtBuf};
+use crate::{AnimClock, Lcd, Moment};
+use std::time::{Duration, Instant};
+
+/// How long each of the two alternating top lines shows on a two-row display.
+const ALTERNATE: Duration = Duration::from_secs(2);
+/// The shortest time between two transfer rate samples.
+const RATE_WINDOW: Duration = Duration::from_millis(500);
+/// The warning shown while the flash is being written.
+const WARNING: &str = "DO NOT POWER OFF";
+
+/// The stage of a firmware update shown by an [`OtaScreen`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OtaPhase {
+    /// Receiving the image and writing it to the update partition.
+    #[default]
+    Downloading,
+    /// Checking the written image.
+    Verifying,
+    /// The image is in place and the device is about to restart.
+    Rebooting,
+    /// The update failed and the running firmware is unchanged.
+    Failed,
+    /// The new firmware failed to start and the previous one was restored.
+    RolledBack,
+}
+
+impl OtaPhase {
+    /// Returns `true` while cutting the power could leave a half-written image.
+    pub fn is_critical(self) -> bool {
+        matches!(self, OtaPhase::Downloading | OtaPhase::Verifying)
+    }
+}
+
+/// A full-screen progress display for over-the-air firmware updates.
+///
+/// Call [`progress`](Self::progress) from the loop that writes the image to the update
+/// partition, e.g. after each `EspOtaUpdate::write` from esp-idf-svc or in the progress
+/// callback of an HTTP client, and [`render`](Self::render) as often as the display should
+/// follow. The screen shows the phase, the percentage and a bar filling a fifth of a cell at a
+/// time, the transfer rate, and a "DO NOT POWER OFF" warning while the flash is being written.
+/// On displays with three or more rows the warning keeps its own top row; on two rows it takes
+/// turns with the status line every two seconds.
+///
+/// A failed update or a rollback after a bad boot replaces the progress with a message until
+/// the next update begins.
+///
+/// The bar takes one CGRAM slot for its partly filled cell, slot 0 by default.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut screen = OtaScreen::new();
+/// screen.begin(Some(image_len));
+/// while let Some(chunk) = download.next_chunk()? {
+///     update.write(chunk)?;
+///     screen.progress(written);
+///     screen.render(&mut lcd)?;
+/// }
+/// screen.set_phase(OtaPhase::Verifying);
+/// screen.render(&mut lcd)?;
+/// ```
+pub struct OtaScreen {
+    phase: OtaPhase,
+    written: usize,
+    total: Option<usize>,
+    started: Moment,
+    sample: (Instant, usize),
+    rate: f32,
+    message: FmtBuf<64>,
+    slot: u8,
+    loaded: Option<u8>,
+}
+
+impl OtaScreen {
+    /// Creates a screen for an update of unknown size, using CGRAM slot 0.
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            phase: OtaPhase::Downloading,
+            written: 0,
+            total: None,
+            started: AnimClock::now(),
+            sample: (now, 0),
+            rate: 0.0,
+            message: FmtBuf::new(),
+            slot: 0,
+            loaded: None,
+        }
+    }
+
+    /// Sets the CGRAM slot (0-7) for the partly filled cell of the bar.
+    pub fn with_slot(mut self, slot: u8) -> Self {
+        self.slot = slot & 0x07;
+        self
+    }
+
+    /// Starts showing a new update from zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `total` - The size of the image in bytes, or `None` if the server did not say.
+    pub fn begin(&mut self, total: Option<usize>) {
+        let now = Instant::now();
+        self.phase = OtaPhase::Downloading;
+        self.written = 0;
+        self.total = total.filter(|&total| total > 0);
+        self.started = AnimClock::now();
+        self.sample = (now, 0);
+        self.rate = 0.0;
+        self.message = FmtBuf::new();
+    }
+
+    /// Records how much of the image has been written so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `written` - The number of bytes written since [`begin`](Self::begin).
+    pub fn progress(&mut self, written: usize) {
+        self.written = written;
+        let (at, bytes) = self.sample;
+        let elapsed = at.elapsed();
+        if elapsed >= RATE_WINDOW {
+            let rate = written.saturating_sub(bytes) as f32 / elapsed.as_secs_f32();
+            // Smooth out bursty network reads so the figure is readable.
+            self.rate = match self.rate {
+                0.0 => rate,
+                previous => previous * 0.7 + rate * 0.3,
+            };
+            self.sample = (Instant::now(), written);
+        }
+    }
+
+    /// Moves on to another phase, e.g. [`OtaPhase::Verifying`] once the image is written.
+    pub fn set_phase(&mut self, phase: OtaPhase) {
+        self.phase = phase;
+    }
+
+    /// Shows that the update failed, with the reason.
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` - The reason, shown truncated to the display width.
+    pub fn fail(&mut self, reason: &str) {
+        self.phase = OtaPhase::Failed;
+        self.message = fmt_buf!(64, "{}", reason);
+    }
+
+    /// Shows that the new firmware was rolled back, e.g. when booting with the image marked
+    /// invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` - The reason, or the version now running.
+    pub fn rolled_back(&mut self, reason: &str) {
+        self.phase = OtaPhase::RolledBack;
+        self.message = fmt_buf!(64, "{}", reason);
+    }
+
+    /// Returns the phase.
+    pub fn phase(&self) -> OtaPhase {
+        self.phase
+    }
+
+    /// Returns how much of the image has been written, 0 to 100, if its size is known.
+    pub fn percent(&self) -> Option<u8> {
+        self.total
+            .map(|total| (self.written.min(total) as u64 * 100 / total as u64) as u8)
+    }
+
+    /// Returns the smoothed transfer rate in bytes per second.
+    pub fn rate(&self) -> f32 {
+        self.rate
+    }
+
+    /// Draws the screen across the whole display.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on, at least two rows.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the screen is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the display has fewer than two rows or there is an error while sending data.
+    pub fn render(&mut self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        let rows = lcd.rows();
+        if rows < 2 {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+        let title = match self.phase {
+            OtaPhase::Downloading => "Updating",
+            OtaPhase::Verifying => "Verifying",
+            OtaPhase::Rebooting => "Rebooting...",
+            OtaPhase::Failed => "Update failed",
+            OtaPhase::RolledBack => "Rolled back",
+        };
+        if matches!(self.phase, OtaPhase::Failed | OtaPhase::RolledBack) {
+            print_line(lcd, 0, title)?;
+            print_line(lcd, 1, self.message.as_str())?;
+            for row in 2..rows {
+                print_line(lcd, row, "")?;
+            }
+            return Ok(());
+        }
+
+        let status = self.status(title);
+        let warn = self.phase.is_critical();
+        let bar_row = rows.min(3) - 1;
+        if rows >= 3 {
+            print_line(lcd, 0, if warn { WARNING } else { "" })?;
+            print_line(lcd, 1, status.as_str())?;
+        } else {
+            let turn = self.started.elapsed().as_millis() / ALTERNATE.as_millis();
+            let top = if !warn || turn % 2 == 1 {
+                status.as_str()
+            } else {
+                WARNING
+            };
+            print_line(lcd, 0, top)?;
+        }
+        self.render_bar(lcd, bar_row)?;
+        for row in bar_row + 1..rows {
+            print_line(lcd, row, "")?;
+        }
+        Ok(())
+    }
+
+    /// Returns the phase title followed by the transfer rate while downloading.
+    fn status(&self, title: &str) -> FmtBuf<32> {
+        if self.phase != OtaPhase::Downloading || self.rate <= 0.0 {
+            return fmt_buf!(32, "{}", title);
+        }
+        let rate = self.rate;
+        match rate {
+            _ if rate >= 1_000_000.0 => fmt_buf!(32, "{} {:.1}MB/s", title, rate / 1_000_000.0),
+            _ if rate >= 1000.0 => fmt_buf!(32, "{} {:.0}kB/s", title, rate / 1000.0),
+            _ => fmt_buf!(32, "{} {:.0}B/s", title, rate),
+        }
+    }
+
+    /// Draws the bar and the percentage on a row, or the bytes received if the size is
+    /// unknown.
+    fn render_bar(&mut self, lcd: &mut Lcd, row: u8) -> anyhow::Result<()> {
+        let Some(percent) = self.percent() else {
+            let kb = fmt_buf!(32, "{} kB received", self.written / 1000);
+            return print_line(lcd, row, kb.as_str());
+        };
+        let label = fmt_buf!(8, "{:>4}%", percent);
+        let width = lcd.cols().saturating_sub(5) as usize;
+        let pixels = width * 5 * percent as usize / 100;
+        let (full, part) = (pixels / 5, (pixels % 5) as u8);
+        if part > 0 && self.loaded != Some(part) {
+            lcd.create_custom_chars(self.slot, &partial_cell(part))?;
+            self.loaded = Some(part);
+        }
+        lcd.set_cursor(0, row)?;
+        for cell in 0..width {
+            lcd.write_byte(match cell {
+                _ if cell < full => 0xff,
+                _ if cell == full && part > 0 => self.slot,
+                _ => b' ',
+            })?;
+        }
+        lcd.print_str(label.as_str())
+    }
+
+    /// Forces the partly filled cell to be reloaded on the next render, e.g. after its CGRAM
+    /// slot was reused.
+    pub fn invalidate(&mut self) {
+        self.loaded = None;
+    }
+}
+
+impl Default for OtaScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns a cell with the left `columns` pixel columns lit.
+const fn partial_cell(columns: u8) -> [u8; 8] {
+    let line = (0x1f << (5 - columns)) & 0x1f;
+    [line; 8]
+}
@@ -0,0 +1,146 @@
+// Part of the `alloc` layer, which trades static sizing for owned text.
+#![allow(clippy::disallowed_types, clippy::disallowed_methods)]
+
+use super::reader::Reader;
+use crate::wrap::WrapMode;
+use crate::Lcd;
+use core::fmt;
+
+/// A [`Reader`] that owns its text, for pages put together at run time, such as a help screen
+/// with the current settings filled in or a log that keeps growing (`alloc` feature).
+///
+/// The text can be replaced or appended to at any time; the reader stays on the same page,
+/// moved back if the text got shorter. Pages are worked out again from the text on every
+/// call, like the [`Reader`] does.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut help = Pages::new(format!("Firmware {VERSION}. Hold OK to reset."), 16, 2);
+/// help.push_fmt(format_args!(" Sensor at {:#04x}.", sensor.address()));
+/// help.render(&mut lcd)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pages {
+    text: String,
+    mode: WrapMode,
+    cols: u8,
+    rows: u8,
+    auto_advance_ms: Option<u32>,
+    /// The page and auto-advance time of the reader.
+    view: (usize, u32),
+}
+
+impl Pages {
+    /// Creates pages for a display of the given size, showing the first page.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to page through.
+    /// * `cols` - The number of columns of the display.
+    /// * `rows` - The number of rows of the display.
+    pub fn new(text: impl Into<String>, cols: u8, rows: u8) -> Self {
+        Self {
+            text: text.into(),
+            mode: WrapMode::Word,
+            cols,
+            rows,
+            auto_advance_ms: None,
+            view: (0, 0),
+        }
+    }
+
+    /// Sets how lines are broken, [`WrapMode::Word`] by default.
+    pub fn with_wrap_mode(mut self, mode: WrapMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Advances to the next page every `interval_ms` milliseconds of [`tick`](Self::tick) time,
+    /// returning to the first page after the last.
+    pub fn with_auto_advance(mut self, interval_ms: u32) -> Self {
+        self.auto_advance_ms = Some(interval_ms);
+        self
+    }
+
+    /// Returns the text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+    }
+
+    /// Appends to the text.
+    pub fn push_str(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    /// Appends formatted text, e.g. `push_fmt(format_args!("{:.1}°C", reading))`.
+    pub fn push_fmt(&mut self, args: fmt::Arguments) {
+        let _ = fmt::Write::write_fmt(&mut self.text, args);
+    }
+
+    /// Returns the index of the current page (0-indexed).
+    pub fn page(&self) -> usize {
+        self.reader().page()
+    }
+
+    /// Returns the total number of pages.
+    pub fn page_count(&self) -> usize {
+        self.reader().page_count()
+    }
+
+    /// Moves to the next page, returning `false` if already on the last page.
+    pub fn next_page(&mut self) -> bool {
+        self.update(|reader| reader.next_page())
+    }
+
+    /// Moves to the previous page, returning `false` if already on the first page.
+    pub fn prev_page(&mut self) -> bool {
+        self.update(|reader| reader.prev_page())
+    }
+
+    /// Advances the auto-advance timer.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed_ms` - The time since the previous call, in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the page changed and the pages should be rendered again.
+    pub fn tick(&mut self, elapsed_ms: u32) -> bool {
+        self.update(|reader| reader.tick(elapsed_ms))
+    }
+
+    /// Draws the current page, filling every cell of the display.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the page is successfully drawn.
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character or setting the cursor.
+    pub fn render(&self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        self.reader().render(lcd)
+    }
+
+    /// Returns a reader over the text on the current page.
+    fn reader(&self) -> Reader<'_> {
+        let mut reader = Reader::new(&self.text, self.cols, self.rows).with_wrap_mode(self.mode);
+        if let Some(interval) = self.auto_advance_ms {
+            reader = reader.with_auto_advance(interval);
+        }
+        reader.restore(self.view);
+        reader
+    }
+
+    /// Runs `f` on a reader over the text, keeping the page it leaves.
+    fn update(&mut self, f: impl FnOnce(&mut Reader) -> bool) -> bool {
+        let mut reader = self.reader();
+        let changed = f(&mut reader);
+        self.view = reader.view();
+        changed
+    }
+}
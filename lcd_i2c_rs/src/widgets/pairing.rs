@@ -0,0 +1,268 @@
+use crate::fmt::{fmt_buf, FmtBuf};
+use crate::{AnimClock, DoubleHeight, Lcd};
+
+/// The longest code that can be shown.
+const MAX_LEN: usize = 8;
+
+/// A pairing or setup code shown in large digits with a countdown to its expiry.
+///
+/// The code is printed with [`Lcd::print_large`] on the top two rows. Displays with more rows
+/// show a countdown bar across the last row; on two-row displays the code is left-aligned and
+/// the seconds left are printed to its right instead. For the last seconds before expiry the
+/// code blinks, and once it expires or is [`invalidate`](PairingCode::invalidate)d it is
+/// replaced by a short message until a new code is set with
+/// [`refresh`](PairingCode::refresh).
+///
+/// Like [`print_large`](Lcd::print_large), the widget uses CGRAM slots 0 to 6 on controllers
+/// without double height.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut pairing = PairingCode::new(120_000);
+/// pairing.refresh("4821-07")?;
+/// pairing.render(&mut lcd)?;
+/// loop {
+///     Ets::delay_ms(100);
+///     if pairing.tick(100) {
+///         pairing.render(&mut lcd)?;
+///     }
+///     if pairing.is_expired() {
+///         pairing.refresh(new_code())?;
+///     }
+/// }
+/// ```
+pub struct PairingCode<'t> {
+    code: [u8; MAX_LEN],
+    len: usize,
+    valid: bool,
+    lifetime_ms: u32,
+    elapsed_ms: u32,
+    emphasis_ms: u32,
+    expired_text: &'t str,
+    digits_shown: Option<bool>,
+    last: Option<View>,
+}
+
+/// What the display shows for a given moment.
+#[derive(Clone, Copy, PartialEq)]
+enum View {
+    Expired,
+    Code { visible: bool, seconds: u32 },
+}
+
+impl<'t> PairingCode<'t> {
+    /// Creates a widget with no code set, showing the expired message until
+    /// [`refresh`](PairingCode::refresh) is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `lifetime_ms` - How long each code stays valid, in milliseconds.
+    pub fn new(lifetime_ms: u32) -> Self {
+        Self {
+            code: [b' '; MAX_LEN],
+            len: 0,
+            valid: false,
+            lifetime_ms: lifetime_ms.max(1),
+            elapsed_ms: 0,
+            emphasis_ms: 10_000,
+            expired_text: "Code expired",
+            digits_shown: None,
+            last: None,
+        }
+    }
+
+    /// Sets how long before expiry the code starts blinking, 10 seconds by default.
+    pub fn with_emphasis(mut self, emphasis_ms: u32) -> Self {
+        self.emphasis_ms = emphasis_ms;
+        self
+    }
+
+    /// Sets the message shown once the code has expired or been invalidated.
+    pub fn with_expired_text(mut self, text: &'t str) -> Self {
+        self.expired_text = text;
+        self
+    }
+
+    /// Replaces the code and restarts its countdown.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The new code, made of digits, `-` and spaces.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the code is accepted.
+    /// * `Err(anyhow::Error)` - If the code is longer than 8 characters or contains any other character.
+    pub fn refresh(&mut self, code: &str) -> anyhow::Result<()> {
+        if code.len() > MAX_LEN {
+            return Err(anyhow::anyhow!("Pairing code too long"));
+        }
+        if !code
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b == b'-' || b == b' ')
+        {
+            return Err(anyhow::anyhow!("Invalid character in pairing code"));
+        }
+        self.code[..code.len()].copy_from_slice(code.as_bytes());
+        self.len = code.len();
+        self.valid = true;
+        self.elapsed_ms = 0;
+        self.digits_shown = None;
+        Ok(())
+    }
+
+    /// Withdraws the current code so the expired message is shown on the next render.
+    pub fn invalidate(&mut self) {
+        self.valid = false;
+    }
+
+    /// Returns `true` if there is no code, or the current one has expired.
+    pub fn is_expired(&self) -> bool {
+        !self.valid || self.elapsed_ms >= self.lifetime_ms
+    }
+
+    /// Returns how long the current code remains valid, in milliseconds.
+    pub fn remaining_ms(&self) -> u32 {
+        if self.valid {
+            self.lifetime_ms.saturating_sub(self.elapsed_ms)
+        } else {
+            0
+        }
+    }
+
+    /// Advances the countdown.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed_ms` - The time since the previous call, in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the display is out of date and should be rendered again.
+    pub fn tick(&mut self, elapsed_ms: u32) -> bool {
+        self.elapsed_ms = self.elapsed_ms.saturating_add(elapsed_ms);
+        self.last != Some(self.view())
+    }
+
+    /// Draws the code and countdown, or the expired message.
+    ///
+    /// The large digits are only redrawn when the code changes or blinks; otherwise only the
+    /// countdown is updated.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the widget is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the display has fewer than two rows, or there is an error while sending data.
+    pub fn render(&mut self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        let view = self.view();
+        match view {
+            View::Expired => {
+                if self.last != Some(View::Expired) {
+                    if lcd.profile().extended_instructions {
+                        lcd.set_double_height(DoubleHeight::Off)?;
+                    }
+                    lcd.clear()?;
+                    lcd.print_str(self.expired_text)?;
+                    self.digits_shown = None;
+                }
+            }
+            View::Code { visible, seconds } => {
+                if self.digits_shown != Some(visible) {
+                    if self.digits_shown.is_none() {
+                        lcd.clear()?;
+                    }
+                    self.draw_digits(lcd, visible)?;
+                    self.digits_shown = Some(visible);
+                }
+                self.draw_countdown(lcd, seconds)?;
+            }
+        }
+        self.last = Some(view);
+        Ok(())
+    }
+
+    fn view(&self) -> View {
+        if self.is_expired() {
+            return View::Expired;
+        }
+        let remaining = self.remaining_ms();
+        View::Code {
+            visible: remaining > self.emphasis_ms || AnimClock::blink_on(),
+            seconds: remaining.div_ceil(1000),
+        }
+    }
+
+    fn code(&self) -> &str {
+        // Only ASCII is accepted by `refresh`.
+        core::str::from_utf8(&self.code[..self.len]).unwrap_or("")
+    }
+
+    /// Returns the number of columns the code takes up once printed large.
+    fn width(&self, lcd: &Lcd) -> u8 {
+        if lcd.profile().extended_instructions {
+            return self.len as u8;
+        }
+        self.code[..self.len]
+            .iter()
+            .map(|&b| match b {
+                b'0'..=b'9' => 3,
+                b'-' => 2,
+                _ => 1,
+            })
+            .sum()
+    }
+
+    /// Returns `true` if there is a spare row below the code for the countdown bar.
+    fn has_bar_row(lcd: &Lcd) -> bool {
+        lcd.rows() > 2
+    }
+
+    fn draw_digits(&self, lcd: &mut Lcd, visible: bool) -> anyhow::Result<()> {
+        let width = self.width(lcd);
+        let pad = if Self::has_bar_row(lcd) {
+            lcd.cols().saturating_sub(width) / 2
+        } else {
+            0
+        };
+
+        let mut text = FmtBuf::<32>::new();
+        for _ in 0..pad {
+            let _ = core::fmt::Write::write_char(&mut text, ' ');
+        }
+        if visible {
+            let _ = core::fmt::Write::write_str(&mut text, self.code());
+        } else {
+            for _ in 0..width {
+                let _ = core::fmt::Write::write_char(&mut text, ' ');
+            }
+        }
+        lcd.print_large(0, text.as_str())
+    }
+
+    fn draw_countdown(&self, lcd: &mut Lcd, seconds: u32) -> anyhow::Result<()> {
+        let cols = lcd.cols();
+        let row = lcd.rows() - 1;
+        if Self::has_bar_row(lcd) {
+            let filled =
+                (self.remaining_ms() as u64 * cols as u64).div_ceil(self.lifetime_ms as u64);
+            lcd.set_cursor(0, row)?;
+            for col in 0..cols as u64 {
+                lcd.write_byte(if col < filled { 0xff } else { b' ' })?;
+            }
+            return Ok(());
+        }
+
+        let text = fmt_buf!(8, "{:>4}s", seconds.min(9999));
+        let len = text.as_str().len() as u8;
+        if self.width(lcd) + len < cols {
+            lcd.set_cursor(cols - len, row)?;
+            lcd.print_str(text.as_str())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use super::number::EditState;
+use crate::input::InputEvent;
+#[cfg(feature = "buffer")]
+use crate::{Canvas, Widget};
+use crate::{Col, Lcd, Row};
+
+/// Picks one item from a list longer than the display, such as scanned WiFi networks or
+/// presets.
+///
+/// The list shows a window of items, one per row, with `>` in front of the selected one, and
+/// scrolls to keep the selection in view. [`InputEvent::Up`] and [`Down`](InputEvent::Down)
+/// or an [`Encoder`](InputEvent::Encoder) move the selection, wrapping around at the ends
+/// unless turned off, and [`Left`](InputEvent::Left) and [`Right`](InputEvent::Right) move by
+/// a window at a time. A [`Char`](InputEvent::Char) jumps to the next item starting with that
+/// letter, ignoring case. [`Select`](InputEvent::Select) confirms and
+/// [`Back`](InputEvent::Back) cancels, putting back the selection picking started from.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut picker = Picker::new(&ssids);
+/// while picker.state() == EditState::Editing {
+///     if let Some(event) = keypad.next_event()? {
+///         picker.handle(event);
+///     }
+///     picker.render(&mut lcd, 0, 0, 2)?;
+/// }
+/// if picker.state() == EditState::Confirmed {
+///     connect(ssids[picker.selected()])?;
+/// }
+/// ```
+pub struct Picker<'i> {
+    items: &'i [&'i str],
+    selected: usize,
+    initial: usize,
+    top: usize,
+    window: usize,
+    wrap: bool,
+    state: EditState,
+}
+
+impl<'i> Picker<'i> {
+    /// Creates a picker with the first item selected and wraparound on.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The items to pick from.
+    pub fn new(items: &'i [&'i str]) -> Self {
+        Self {
+            items,
+            selected: 0,
+            initial: 0,
+            top: 0,
+            window: 1,
+            wrap: true,
+            state: EditState::Editing,
+        }
+    }
+
+    /// Stops the selection at the first and last items instead of wrapping around.
+    pub fn without_wraparound(mut self) -> Self {
+        self.wrap = false;
+        self
+    }
+
+    /// Returns the index of the selected item.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Returns the selected item, or an empty string if there are none.
+    pub(super) fn selected_item(&self) -> &'i str {
+        self.items.get(self.selected).copied().unwrap_or("")
+    }
+
+    /// Selects an item and starts picking from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The item to select, clamped to the list.
+    pub fn select(&mut self, index: usize) {
+        self.selected = index.min(self.items.len().saturating_sub(1));
+        self.initial = self.selected;
+        self.state = EditState::Editing;
+        self.scroll_into_view();
+    }
+
+    /// Returns the selected item, the first item in view and the window height, to rebuild
+    /// the picker later with [`restore`](Self::restore).
+    pub(super) fn view(&self) -> (usize, usize, usize) {
+        (self.selected, self.top, self.window)
+    }
+
+    /// Puts back a selection and scroll position taken with [`view`](Self::view).
+    pub(super) fn restore(&mut self, (selected, top, window): (usize, usize, usize)) {
+        self.select(selected);
+        self.top = top;
+        self.window = window;
+        self.scroll_into_view();
+    }
+
+    /// Returns whether an item is being picked, or was confirmed or cancelled.
+    pub fn state(&self) -> EditState {
+        self.state
+    }
+
+    /// Reacts to an input event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the event was used. Events are ignored once picking has ended, and by an
+    /// empty list.
+    pub fn handle(&mut self, event: InputEvent) -> bool {
+        if self.state != EditState::Editing || self.items.is_empty() {
+            return false;
+        }
+        match event {
+            InputEvent::Up => self.step(-1),
+            InputEvent::Down => self.step(1),
+            InputEvent::Encoder(detents) => self.step(detents as isize),
+            InputEvent::Left => self.page(-1),
+            InputEvent::Right => self.page(1),
+            InputEvent::Char(letter) => return self.jump_to(letter),
+            InputEvent::Select => self.state = EditState::Confirmed,
+            InputEvent::Back => {
+                self.selected = self.initial;
+                self.state = EditState::Cancelled;
+            }
+        }
+        self.scroll_into_view();
+        true
+    }
+
+    /// Draws a window of items, one per row, from a column to the right edge of the display.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `col` - The column of the first cell (0-indexed).
+    /// * `row` - The top row (0-indexed).
+    /// * `height` - The number of rows to use.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the list is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the window does not fit on the display or there is an error while sending data.
+    pub fn render(
+        &mut self,
+        lcd: &mut Lcd,
+        col: impl Into<Col>,
+        row: impl Into<Row>,
+        height: u8,
+    ) -> anyhow::Result<()> {
+        let (col, row) = (col.into().0, row.into().0);
+        if col >= lcd.cols() || row.saturating_add(height) > lcd.rows() {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+        self.set_window(height);
+        let width = (lcd.cols() - col) as usize;
+        for line in 0..height {
+            lcd.set_cursor(col, row + line)?;
+            let (marker, text) = self.line(line);
+            lcd.print(marker)?;
+            let mut shown = 1;
+            for ch in text.chars().take(width - 1) {
+                lcd.print(ch)?;
+                shown += 1;
+            }
+            for _ in shown..width {
+                lcd.print(' ')?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the marker and text of one row of the window.
+    fn line(&self, line: u8) -> (char, &'i str) {
+        let index = self.top + line as usize;
+        match self.items.get(index) {
+            Some(item) if index == self.selected => ('>', item),
+            Some(item) => (' ', item),
+            None => (' ', ""),
+        }
+    }
+
+    fn set_window(&mut self, height: u8) {
+        self.window = (height as usize).max(1);
+        self.scroll_into_view();
+    }
+
+    fn step(&mut self, by: isize) {
+        let len = self.items.len() as isize;
+        let target = self.selected as isize + by;
+        self.selected = match self.wrap {
+            true => target.rem_euclid(len),
+            false => target.clamp(0, len - 1),
+        } as usize;
+    }
+
+    /// Moves a window at a time, stopping at the ends rather than wrapping.
+    fn page(&mut self, pages: isize) {
+        let target = self.selected as isize + pages * self.window as isize;
+        self.selected = target.clamp(0, self.items.len() as isize - 1) as usize;
+    }
+
+    /// Selects the next item after the selected one starting with `letter`.
+    fn jump_to(&mut self, letter: char) -> bool {
+        let len = self.items.len();
+        let starts = |item: &str| {
+            item.chars()
+                .next()
+                .is_some_and(|first| first.to_lowercase().eq(letter.to_lowercase()))
+        };
+        let found = (1..=len)
+            .map(|offset| (self.selected + offset) % len)
+            .find(|&index| starts(self.items[index]));
+        match found {
+            Some(index) => {
+                self.selected = index;
+                self.scroll_into_view();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn scroll_into_view(&mut self) {
+        if self.selected < self.top {
+            self.top = self.selected;
+        } else if self.selected >= self.top + self.window {
+            self.top = self.selected + 1 - self.window;
+        }
+        let last_top = self.items.len().saturating_sub(self.window);
+        self.top = self.top.min(last_top);
+    }
+}
+
+/// Draws a window of items filling the widget's rectangle.
+#[cfg(feature = "buffer")]
+impl Widget for Picker<'_> {
+    fn render(&mut self, canvas: &mut Canvas) -> anyhow::Result<()> {
+        self.set_window(canvas.height());
+        for line in 0..canvas.height() {
+            let (marker, text) = self.line(line);
+            let mut buf = [0; 4];
+            canvas.print(0, line, marker.encode_utf8(&mut buf))?;
+            canvas.print(1, line, text)?;
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, event: InputEvent) -> bool {
+        Picker::handle(self, event)
+    }
+}
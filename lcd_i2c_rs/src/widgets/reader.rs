@@ -0,0 +1,234 @@
+use crate::fmt::fmt_buf;
+use crate::wrap::{WrapMode, WrappedLines};
+use crate::Lcd;
+
+/// A paginated viewer for text longer than one screen.
+///
+/// The text is word-wrapped into screen-sized pages. When there is more than one page, a
+/// `page/total` indicator is shown in the bottom-right corner and the last row wraps short
+/// of it. Pages are recomputed from the text on demand, so the reader needs no buffer of its own.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut reader = Reader::new(LICENSE, lcd.cols(), lcd.rows()).with_auto_advance(3000);
+/// reader.render(&mut lcd)?;
+/// loop {
+///     Ets::delay_ms(100);
+///     if reader.tick(100) {
+///         reader.render(&mut lcd)?;
+///     }
+/// }
+/// ```
+pub struct Reader<'t> {
+    text: &'t str,
+    mode: WrapMode,
+    cols: u8,
+    rows: u8,
+    indicator_width: u8,
+    page: usize,
+    page_count: usize,
+    auto_advance_ms: Option<u32>,
+    elapsed_ms: u32,
+}
+
+impl<'t> Reader<'t> {
+    /// Creates a reader for a display of the given size, showing the first page.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to page through.
+    /// * `cols` - The number of columns of the display.
+    /// * `rows` - The number of rows of the display.
+    pub fn new(text: &'t str, cols: u8, rows: u8) -> Self {
+        let mut reader = Self {
+            text,
+            mode: WrapMode::Word,
+            cols,
+            rows,
+            indicator_width: 0,
+            page: 0,
+            page_count: 0,
+            auto_advance_ms: None,
+            elapsed_ms: 0,
+        };
+        reader.paginate();
+        reader
+    }
+
+    /// Sets how lines are broken, [`WrapMode::Word`] by default.
+    pub fn with_wrap_mode(mut self, mode: WrapMode) -> Self {
+        self.mode = mode;
+        self.paginate();
+        self
+    }
+
+    /// Advances to the next page every `interval_ms` milliseconds of [`tick`](Self::tick) time,
+    /// returning to the first page after the last.
+    pub fn with_auto_advance(mut self, interval_ms: u32) -> Self {
+        self.auto_advance_ms = Some(interval_ms);
+        self
+    }
+
+    /// Returns the index of the current page (0-indexed).
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    /// Returns the total number of pages.
+    pub fn page_count(&self) -> usize {
+        self.page_count
+    }
+
+    /// Returns the page and auto-advance time, to rebuild the reader later with
+    /// [`restore`](Self::restore).
+    #[cfg(feature = "alloc")]
+    pub(super) fn view(&self) -> (usize, u32) {
+        (self.page, self.elapsed_ms)
+    }
+
+    /// Puts back a page and auto-advance time taken with [`view`](Self::view), clamped to the
+    /// pages of the text.
+    #[cfg(feature = "alloc")]
+    pub(super) fn restore(&mut self, (page, elapsed_ms): (usize, u32)) {
+        self.page = page.min(self.page_count.saturating_sub(1));
+        self.elapsed_ms = elapsed_ms;
+    }
+
+    /// Moves to the next page, returning `false` if already on the last page.
+    pub fn next_page(&mut self) -> bool {
+        self.elapsed_ms = 0;
+        if self.page + 1 < self.page_count {
+            self.page += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves to the previous page, returning `false` if already on the first page.
+    pub fn prev_page(&mut self) -> bool {
+        self.elapsed_ms = 0;
+        if self.page > 0 {
+            self.page -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Advances the auto-advance timer.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed_ms` - The time since the previous call, in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the page changed and the reader should be rendered again.
+    pub fn tick(&mut self, elapsed_ms: u32) -> bool {
+        let Some(interval) = self.auto_advance_ms else {
+            return false;
+        };
+        self.elapsed_ms = self.elapsed_ms.saturating_add(elapsed_ms);
+        if self.elapsed_ms < interval || self.page_count < 2 {
+            return false;
+        }
+        self.elapsed_ms = 0;
+        self.page = (self.page + 1) % self.page_count;
+        true
+    }
+
+    /// Draws the current page, filling every cell of the display.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the page is successfully drawn.
+    /// * `Err(anyhow::Error)` - If there is an error while printing any character or setting the cursor.
+    pub fn render(&self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        let mut lines = self.lines();
+        // Earlier pages are wrapped the way `count_pages` wrapped them, last row narrowed.
+        for _ in 0..self.page {
+            for row in 0..self.rows {
+                lines.set_width(self.row_width(row));
+                lines.next();
+            }
+        }
+
+        for row in 0..self.rows {
+            let width = self.row_width(row);
+            lines.set_width(width);
+            lcd.set_cursor(0, row)?;
+            let mut len = 0;
+            if let Some(line) = lines.next() {
+                for ch in line.chars() {
+                    lcd.print(ch)?;
+                }
+                len = line.len();
+            }
+            for _ in len..self.cols as usize {
+                lcd.print(' ')?;
+            }
+        }
+
+        if self.indicator_width > 0 {
+            let indicator = fmt_buf!(12, "{}/{}", self.page + 1, self.page_count);
+            let indicator = indicator.as_str();
+            let col = self.cols.saturating_sub(indicator.len() as u8);
+            lcd.set_cursor(col, self.rows - 1)?;
+            lcd.print_str(indicator)?;
+        }
+        Ok(())
+    }
+
+    fn lines(&self) -> WrappedLines<'t> {
+        WrappedLines::new(self.text, self.cols, self.mode)
+    }
+
+    fn row_width(&self, row: u8) -> u8 {
+        if row + 1 == self.rows && self.indicator_width > 0 {
+            // Keep one blank cell between the text and the indicator.
+            self.cols.saturating_sub(self.indicator_width + 1)
+        } else {
+            self.cols
+        }
+    }
+
+    fn count_pages(&self) -> usize {
+        let mut lines = self.lines();
+        let mut pages = 0;
+        loop {
+            for row in 0..self.rows {
+                lines.set_width(self.row_width(row));
+                if lines.next().is_none() {
+                    return pages + usize::from(row > 0);
+                }
+            }
+            pages += 1;
+            if lines.is_exhausted() {
+                return pages;
+            }
+        }
+    }
+
+    /// Splits the text into pages, reserving room for the indicator when it is needed.
+    fn paginate(&mut self) {
+        self.indicator_width = 0;
+        if self.rows == 0 {
+            self.page_count = 0;
+            return;
+        }
+        let mut pages = self.count_pages().max(1);
+        while pages > 1 {
+            let digits = (pages.ilog10() + 1) as u8;
+            let width = 2 * digits + 1;
+            if width == self.indicator_width {
+                break;
+            }
+            self.indicator_width = width;
+            pages = self.count_pages().max(1);
+        }
+        self.page_count = pages;
+        self.page = self.page.min(pages - 1);
+    }
+}
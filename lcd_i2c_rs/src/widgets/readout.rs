@@ -0,0 +1,401 @@
+use crate::fmt::FmtBuf;
+use crate::i18n::Locale;
+use crate::units::{Dimension, Unit, Units};
+use crate::{AnimClock, Col, Lcd, Moment, Row};
+#[cfg(feature = "buffer")]
+use crate::{Canvas, Widget};
+use crate::{Color, Observable};
+use core::fmt::Write;
+use std::time::Duration;
+
+/// The widest readout that is composed.
+const MAX_WIDTH: usize = 20;
+/// The most values shown in turn with the reading.
+const MAX_ALTERNATES: usize = 3;
+/// The most thresholds a readout checks.
+const MAX_THRESHOLDS: usize = 4;
+
+/// What a [`Readout`] does while one of its [`Threshold`]s is crossed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Alert {
+    /// Hides and shows the value every half second.
+    Blink,
+    /// Shows a character, such as `!` or a warning glyph in CGRAM, in the first cell in place
+    /// of the label.
+    Marker(u8),
+    /// Asks for a backlight color, read with [`Readout::alert_color`] to drive a
+    /// [`StatusLight`](crate::StatusLight).
+    Backlight(Color),
+}
+
+/// A limit on a reading and the [`Alert`] raised while the reading is past it.
+///
+/// The limit is in the base unit of the dimension, like the reading, so it holds whatever
+/// unit the reading is shown in. A hysteresis keeps a reading hovering at the limit from
+/// raising and clearing the alert on every update: the alert clears only once the reading is
+/// back by more than the hysteresis.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut freezer = Readout::new(Dimension::Temperature, &UNITS)
+///     .with_threshold(Threshold::above(-15.0, Alert::Blink).with_hysteresis(0.5))
+///     .with_threshold(Threshold::above(-15.0, Alert::Backlight(Color::RED)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Threshold {
+    limit: f32,
+    above: bool,
+    hysteresis: f32,
+    alert: Alert,
+}
+
+impl Threshold {
+    /// Creates a threshold raising `alert` while the reading is above `limit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The limit, in the base unit of the dimension.
+    /// * `alert` - What the readout does while the reading is above it.
+    pub const fn above(limit: f32, alert: Alert) -> Self {
+        Self {
+            limit,
+            above: true,
+            hysteresis: 0.0,
+            alert,
+        }
+    }
+
+    /// Creates a threshold raising `alert` while the reading is below `limit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The limit, in the base unit of the dimension.
+    /// * `alert` - What the readout does while the reading is below it.
+    pub const fn below(limit: f32, alert: Alert) -> Self {
+        Self {
+            limit,
+            above: false,
+            hysteresis: 0.0,
+            alert,
+        }
+    }
+
+    /// Keeps the alert until the reading is back past the limit by more than `hysteresis`,
+    /// in the base unit of the dimension. There is none by default.
+    pub const fn with_hysteresis(mut self, hysteresis: f32) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// Returns the alert raised while the threshold is crossed.
+    pub const fn alert(&self) -> Alert {
+        self.alert
+    }
+
+    /// Returns whether the threshold is crossed at `value`, given whether it was before. A
+    /// missing reading crosses nothing.
+    fn crossed(&self, value: f32, was: bool) -> bool {
+        let margin = if was { self.hysteresis } else { 0.0 };
+        match self.above {
+            true => value > self.limit - margin,
+            false => value < self.limit + margin,
+        }
+    }
+}
+
+/// A reading such as a temperature, shown right-aligned in the unit chosen in a [`Units`],
+/// rounded for that unit and followed by its suffix, e.g. ` 21.5°C` or `   71°F`.
+///
+/// The reading is set in the base unit of its dimension and converted each time it is drawn,
+/// so every readout follows [`Units::set`] on its next render. A missing reading, set as
+/// `f32::NAN`, shows as `--`.
+///
+/// Instead of being set, the reading can be [`bind`](Self::bind)ed to an [`Observable`]
+/// updated by a sensor task; a [`Compositor`](crate::Compositor) then redraws the readout as
+/// soon as the value changes. Further values, such as the lowest and highest reading kept by
+/// a [`Tracker`](crate::Tracker), can be shown in turn with the reading through
+/// [`with_alternate`](Self::with_alternate), each with a short label on the left.
+///
+/// [`Threshold`]s added with [`with_threshold`](Self::with_threshold) turn the readout into a
+/// simple alarm: the reading is checked against them each time it is drawn, which for a bound
+/// readout in a [`Compositor`](crate::Compositor) is on every update of the observable, and
+/// while one is crossed the value blinks, a marker replaces the label or
+/// [`alert_color`](Self::alert_color) asks for a backlight color.
+///
+/// # Example
+///
+/// ```ignore
+/// static UNITS: Units = Units::metric();
+///
+/// let mut pressure = Readout::new(Dimension::Pressure, &UNITS);
+/// pressure.set(bme280.pressure_hpa()?);
+/// pressure.render(&mut lcd, 8, 1, 8)?;
+/// ```
+pub struct Readout<'u> {
+    dimension: Dimension,
+    units: &'u Units,
+    locale: Locale,
+    value: f32,
+    source: Option<&'u Observable<f32>>,
+    alternates: [Option<(&'u str, &'u Observable<f32>)>; MAX_ALTERNATES],
+    period: Duration,
+    started: Option<Moment>,
+    /// The value shown at the last render, 0 for the reading, and its version.
+    drawn: (usize, u32),
+    /// Each threshold and whether it was crossed at the last check.
+    thresholds: [Option<(Threshold, bool)>; MAX_THRESHOLDS],
+    /// The version of the reading at the last check, and whether the value was hidden by a
+    /// blink at the last render.
+    checked: (u32, bool),
+}
+
+impl<'u> Readout<'u> {
+    /// Creates a readout without a reading.
+    ///
+    /// # Arguments
+    ///
+    /// * `dimension` - What the reading measures.
+    /// * `units` - The unit preferences to show it in.
+    pub const fn new(dimension: Dimension, units: &'u Units) -> Self {
+        Self {
+            dimension,
+            units,
+            locale: Locale::EN_GB,
+            value: f32::NAN,
+            source: None,
+            alternates: [None; MAX_ALTERNATES],
+            period: Duration::from_secs(3),
+            started: None,
+            drawn: (0, 0),
+            thresholds: [None; MAX_THRESHOLDS],
+            checked: (0, false),
+        }
+    }
+
+    /// Checks the reading against `threshold` each time it is drawn. Up to four thresholds
+    /// can be added; more are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The limit and the alert raised past it.
+    pub fn with_threshold(mut self, threshold: Threshold) -> Self {
+        if let Some(free) = self.thresholds.iter_mut().find(|slot| slot.is_none()) {
+            *free = Some((threshold, false));
+        }
+        self
+    }
+
+    /// Returns `true` if any threshold was crossed when the reading was last drawn.
+    pub fn is_alerting(&self) -> bool {
+        self.alerts().next().is_some()
+    }
+
+    /// Returns the backlight color asked for by the first crossed threshold with an
+    /// [`Alert::Backlight`], as of the last time the reading was drawn, or `None` if there is
+    /// none and the backlight can go back to its normal color.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// compositor.tick()?;
+    /// light.set(freezer.alert_color().unwrap_or(Color::GREEN));
+    /// light.tick()?;
+    /// ```
+    pub fn alert_color(&self) -> Option<Color> {
+        self.alerts().find_map(|alert| match alert {
+            Alert::Backlight(color) => Some(color),
+            _ => None,
+        })
+    }
+
+    /// Returns the alerts of the crossed thresholds.
+    fn alerts(&self) -> impl Iterator<Item = Alert> + '_ {
+        self.thresholds
+            .iter()
+            .flatten()
+            .filter(|(_, crossed)| *crossed)
+            .map(|(threshold, _)| threshold.alert)
+    }
+
+    /// Checks the reading against the thresholds.
+    fn check(&mut self) {
+        let value = self.value();
+        for (threshold, crossed) in self.thresholds.iter_mut().flatten() {
+            *crossed = !value.is_nan() && threshold.crossed(value, *crossed);
+        }
+        self.checked.0 = self.source.map_or(0, Observable::version);
+    }
+
+    /// Returns `true` if a blinking value is due to be hidden.
+    fn blanked(&self) -> bool {
+        self.alerts().any(|alert| alert == Alert::Blink) && !AnimClock::blink_on()
+    }
+
+    /// Shows another value in turn with the reading, with a label on the left, e.g.
+    /// `"lo"` and the minimum of a [`Tracker`](crate::Tracker). Up to three alternates can be
+    /// added; more are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label, ASCII, shown before the value.
+    /// * `source` - The value, in the base unit of the dimension.
+    pub fn with_alternate(mut self, label: &'u str, source: &'u Observable<f32>) -> Self {
+        if let Some(free) = self.alternates.iter_mut().find(|slot| slot.is_none()) {
+            *free = Some((label, source));
+        }
+        self
+    }
+
+    /// Sets how long each value is shown when alternating, 3 seconds by default.
+    pub fn with_period(mut self, period: Duration) -> Self {
+        self.period = period.max(Duration::from_millis(1));
+        self
+    }
+
+    /// Writes the reading with the decimal separator of `locale`.
+    pub const fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Sets the reading, in the base unit of the dimension (°C, hPa or m/s), or `f32::NAN` if
+    /// it is missing. This also unbinds the readout from its [`Observable`].
+    pub fn set(&mut self, value: f32) {
+        self.value = value;
+        self.source = None;
+    }
+
+    /// Shows the value of `source`, in the base unit of the dimension, from now on.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The observable reading.
+    pub fn bind(&mut self, source: &'u Observable<f32>) {
+        self.source = Some(source);
+        // Draw the bound value on the next tick.
+        self.drawn = (usize::MAX, 0);
+    }
+
+    /// Returns the reading in the base unit of the dimension.
+    pub fn value(&self) -> f32 {
+        self.source.map_or(self.value, Observable::get)
+    }
+
+    /// Returns the unit the reading is shown in.
+    pub fn unit(&self) -> Unit {
+        self.units.unit(self.dimension)
+    }
+
+    /// Draws the reading right-aligned in `width` cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `col` - The column of the first cell (0-indexed).
+    /// * `row` - The row to draw on (0-indexed).
+    /// * `width` - The number of cells the readout takes, with its suffix.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the readout is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the readout does not fit on the display or there is an error while sending data.
+    pub fn render(
+        &mut self,
+        lcd: &mut Lcd,
+        col: impl Into<Col>,
+        row: impl Into<Row>,
+        width: u8,
+    ) -> anyhow::Result<()> {
+        let (col, row) = (col.into().0, row.into().0);
+        if col.saturating_add(width) > lcd.cols() || row >= lcd.rows() {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+        let (cells, len) = self.compose(width as usize);
+        lcd.set_cursor(col, row)?;
+        for &code in &cells[..len] {
+            lcd.write_byte(code)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the index of the value due to be shown, 0 for the reading, with its label,
+    /// value and version.
+    fn shown(&self) -> (usize, &'u str, f32, u32) {
+        let count = self.alternates.iter().flatten().count();
+        let index = match (self.started, count) {
+            (Some(started), 1..) => {
+                let turns = started.elapsed().as_millis() / self.period.as_millis();
+                (turns % (count as u128 + 1)) as usize
+            }
+            _ => 0,
+        };
+        match self.alternates.iter().flatten().nth(index.wrapping_sub(1)) {
+            Some((label, source)) => (index, label, source.get(), source.version()),
+            None => (
+                0,
+                "",
+                self.value(),
+                self.source.map_or(0, Observable::version),
+            ),
+        }
+    }
+
+    /// Returns the cells of the readout, right-aligned in `width`.
+    fn compose(&mut self, width: usize) -> ([u8; MAX_WIDTH], usize) {
+        self.started.get_or_insert_with(AnimClock::now);
+        self.check();
+        let (index, label, value, version) = self.shown();
+        self.drawn = (index, version);
+        self.checked.1 = self.blanked();
+        let unit = self.unit();
+        let mut number = FmtBuf::<16>::new();
+        let _ = match value.is_nan() {
+            true => number.write_str("--"),
+            false => {
+                self.locale
+                    .write_number(&mut number, unit.round_from_base(value), unit.decimals())
+            }
+        };
+        let width = width.min(MAX_WIDTH);
+        let text = number.as_str().bytes().chain(unit.suffix().iter().copied());
+        let len = text.clone().count();
+        let mut cells = [b' '; MAX_WIDTH];
+        for (cell, code) in cells[..width].iter_mut().zip(label.bytes()) {
+            *cell = code;
+        }
+        // Keep the end, with the suffix, when the readout is too narrow.
+        let shown = if self.checked.1 { 0 } else { len };
+        for (cell, code) in cells[width.saturating_sub(shown)..width]
+            .iter_mut()
+            .zip(text.skip(len.saturating_sub(width)))
+        {
+            *cell = code;
+        }
+        let marker = self.alerts().find_map(|alert| match alert {
+            Alert::Marker(code) => Some(code),
+            _ => None,
+        });
+        if let (Some(code), 1..) = (marker, width) {
+            cells[0] = code;
+        }
+        (cells, width)
+    }
+}
+
+/// Draws the reading right-aligned on the top row of its rectangle.
+#[cfg(feature = "buffer")]
+impl Widget for Readout<'_> {
+    fn render(&mut self, canvas: &mut Canvas) -> anyhow::Result<()> {
+        let (cells, len) = self.compose(canvas.width() as usize);
+        canvas.write_bytes(0, 0, &cells[..len]);
+        Ok(())
+    }
+
+    fn needs_redraw(&self) -> bool {
+        let (index, _, _, version) = self.shown();
+        self.drawn != (index, version)
+            || self.source.map_or(0, Observable::version) != self.checked.0
+            || self.blanked() != self.checked.1
+    }
+}
@@ -0,0 +1,561 @@
+use super::number::{EditState, NumberEditor};
+use super::picker::Picker;
+use super::print_line;
+use super::store::SettingsStore;
+use crate::fmt::{fmt_buf, FmtBuf};
+use crate::input::InputEvent;
+use crate::Lcd;
+#[cfg(feature = "buffer")]
+use crate::{Canvas, Rect, Widget};
+
+/// The rows after the settings, in order.
+const ACTIONS: [&str; 2] = ["Save", "Cancel"];
+
+/// The key under which a [`SettingsStore`] keeps the version of the stored values.
+const VERSION_KEY: &str = "_version";
+
+/// The longest key a setting gets from its label.
+const MAX_KEY: usize = 15;
+
+/// A numeric field of a settings struct, edited as an `f32` by a [`NumberEditor`].
+///
+/// Implemented for the float and integer types; integers are rounded to the nearest value
+/// and saturate at the bounds of their type.
+pub trait NumberSetting {
+    /// Returns the value as an `f32`.
+    fn get(&self) -> f32;
+
+    /// Stores a value edited as an `f32`.
+    fn set(&mut self, value: f32);
+}
+
+impl NumberSetting for f32 {
+    fn get(&self) -> f32 {
+        *self
+    }
+
+    fn set(&mut self, value: f32) {
+        *self = value;
+    }
+}
+
+impl NumberSetting for f64 {
+    fn get(&self) -> f32 {
+        *self as f32
+    }
+
+    fn set(&mut self, value: f32) {
+        *self = value.into();
+    }
+}
+
+macro_rules! integer_setting {
+    ($($ty:ty),*) => {$(
+        impl NumberSetting for $ty {
+            fn get(&self) -> f32 {
+                *self as f32
+            }
+
+            fn set(&mut self, value: f32) {
+                *self = value.round() as $ty;
+            }
+        }
+    )*};
+}
+
+integer_setting!(u8, u16, u32, i8, i16, i32);
+
+/// A field of a settings struct and the edit it is going through.
+enum Field<'s> {
+    Toggle(&'s mut bool, bool),
+    Number(&'s mut dyn NumberSetting, NumberEditor<'s>),
+    Choice(&'s mut usize, Picker<'s>),
+}
+
+/// One row of a [`SettingsScreen`]: a label and the field of a settings struct it edits.
+pub struct Setting<'s> {
+    label: &'s str,
+    key: &'s str,
+    field: Field<'s>,
+}
+
+impl<'s> Setting<'s> {
+    /// Creates a setting switched on and off with Select, Left or Right, shown as `On` or
+    /// `Off`.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The name shown on the left of the row.
+    /// * `value` - The field to edit.
+    pub fn toggle(label: &'s str, value: &'s mut bool) -> Self {
+        let draft = *value;
+        Self {
+            label,
+            key: default_key(label),
+            field: Field::Toggle(value, draft),
+        }
+    }
+
+    /// Creates a numeric setting, edited with `editor`, which sets its bounds, step, decimals
+    /// and unit.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The name shown on the left of the row.
+    /// * `value` - The field to edit, of any type implementing [`NumberSetting`].
+    /// * `editor` - The editor, e.g. `NumberEditor::new(5.0, 30.0, 0.5).with_decimals(1)`.
+    pub fn number(
+        label: &'s str,
+        value: &'s mut dyn NumberSetting,
+        mut editor: NumberEditor<'s>,
+    ) -> Self {
+        editor.set_value(value.get());
+        Self {
+            label,
+            key: default_key(label),
+            field: Field::Number(value, editor),
+        }
+    }
+
+    /// Creates a setting holding one of a list of options, picked with a [`Picker`].
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The name shown on the left of the row.
+    /// * `index` - The field holding the index of the option.
+    /// * `options` - The options.
+    pub fn choice(label: &'s str, index: &'s mut usize, options: &'s [&'s str]) -> Self {
+        let mut picker = Picker::new(options);
+        picker.select(*index);
+        Self {
+            label,
+            key: default_key(label),
+            field: Field::Choice(index, picker),
+        }
+    }
+
+    /// Sets the key the value is kept under in a [`SettingsStore`], instead of the label cut
+    /// to 15 bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key, at most 15 bytes and unique within the screen.
+    pub fn with_key(mut self, key: &'s str) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Returns the value as shown on the right of the row.
+    fn value(&self) -> FmtBuf<32> {
+        match &self.field {
+            Field::Toggle(_, true) => fmt_buf!(32, "On"),
+            Field::Toggle(_, false) => fmt_buf!(32, "Off"),
+            Field::Number(_, editor) => fmt_buf!(32, "{}", editor.text().as_str().trim_start()),
+            Field::Choice(_, picker) => fmt_buf!(32, "{}", picker.selected_item()),
+        }
+    }
+
+    /// Writes the edited value into the field.
+    fn save(&mut self) {
+        match &mut self.field {
+            Field::Toggle(value, draft) => **value = *draft,
+            Field::Number(value, editor) => value.set(editor.value()),
+            Field::Choice(index, picker) => **index = picker.selected(),
+        }
+    }
+
+    /// Returns the edited value as kept in a [`SettingsStore`]: a toggle as 0 or 1, a number
+    /// as the bits of its `f32` and a choice as its index.
+    fn encode(&self) -> u32 {
+        match &self.field {
+            Field::Toggle(_, draft) => *draft as u32,
+            Field::Number(_, editor) => editor.value().to_bits(),
+            Field::Choice(_, picker) => picker.selected() as u32,
+        }
+    }
+
+    /// Writes a value read from a [`SettingsStore`] into the field and the edit, within the
+    /// bounds of the editor or the list of options.
+    fn decode(&mut self, stored: u32) {
+        match &mut self.field {
+            Field::Toggle(value, draft) => {
+                *draft = stored != 0;
+                **value = *draft;
+            }
+            Field::Number(value, editor) => {
+                let number = f32::from_bits(stored);
+                if number.is_finite() {
+                    editor.set_value(number);
+                    value.set(editor.value());
+                }
+            }
+            Field::Choice(index, picker) => {
+                picker.select(stored as usize);
+                **index = picker.selected();
+            }
+        }
+    }
+}
+
+/// Returns the label cut to at most [`MAX_KEY`] bytes on a character boundary.
+fn default_key(label: &str) -> &str {
+    let mut end = label.len().min(MAX_KEY);
+    while !label.is_char_boundary(end) {
+        end -= 1;
+    }
+    &label[..end]
+}
+
+/// A settings page generated from the fields of a config struct, with Save and Cancel.
+///
+/// Each [`Setting`] takes a label and a `&mut` to one field, and becomes a row showing the
+/// label on the left and the value on the right, followed by a Save and a Cancel row.
+/// [`InputEvent::Up`] and [`Down`](InputEvent::Down) or an [`Encoder`](InputEvent::Encoder)
+/// move between the rows. Select flips a toggle, or opens the editor of a number or a choice
+/// with the label on the top row and the editor below; Select there keeps the new value and
+/// Back the old one. Nothing is written to the struct until Save is selected, so Cancel or
+/// [`Back`](InputEvent::Back) on the list leaves it as it was.
+///
+/// Built [`with_store`](Self::with_store), the screen loads the fields from a
+/// [`SettingsStore`] such as an [`NvsStore`](super::NvsStore) and keeps them there on Save,
+/// so the values chosen in the menu survive a reboot without further code.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut config = Config::default();
+/// let mut store = NvsStore::new("config")?;
+/// let mut screen = SettingsScreen::new([
+///     Setting::toggle("Backlight", &mut config.backlight),
+///     Setting::number("Setpoint", &mut config.setpoint,
+///         NumberEditor::new(5.0, 30.0, 0.5).with_decimals(1).with_unit("C")),
+///     Setting::number("Timeout", &mut config.timeout_s,
+///         NumberEditor::new(10.0, 600.0, 10.0).with_unit("s")),
+///     Setting::choice("Units", &mut config.units, &["Metric", "Imperial"]),
+/// ])
+/// .with_store(&mut store, 1)?;
+/// while screen.state() == EditState::Editing {
+///     if let Some(event) = keypad.next_event()? {
+///         screen.handle(event);
+///     }
+///     screen.render(&mut lcd)?;
+/// }
+/// ```
+pub struct SettingsScreen<'s, const N: usize> {
+    settings: [Setting<'s>; N],
+    selected: usize,
+    top: usize,
+    window: usize,
+    editing: bool,
+    state: EditState,
+    drawn: bool,
+    store: Option<(&'s mut dyn SettingsStore, u32)>,
+    error: Option<&'static str>,
+}
+
+impl<'s, const N: usize> SettingsScreen<'s, N> {
+    /// Creates a screen with the first setting selected.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - The settings, in the order they are listed.
+    pub fn new(settings: [Setting<'s>; N]) -> Self {
+        Self {
+            settings,
+            selected: 0,
+            top: 0,
+            window: 1,
+            editing: false,
+            state: EditState::Editing,
+            drawn: false,
+            store: None,
+            error: None,
+        }
+    }
+
+    /// Loads the settings from a store and keeps them there whenever Save is selected.
+    ///
+    /// Each setting is kept under its key next to a version number, stored under the reserved
+    /// key `_version`. The stored values are
+    /// only loaded if they were saved with the same `version`; on first boot, or after the
+    /// version was raised because the settings changed meaning, the fields keep the values
+    /// they had, and the store is overwritten by the next Save.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - The store, e.g. an [`NvsStore`](super::NvsStore) opened on the namespace of this screen.
+    /// * `version` - The version of the settings layout.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SettingsScreen)` - The screen with the stored values loaded.
+    /// * `Err(anyhow::Error)` - If two settings share a key, e.g. labels alike in their first 15 bytes, which [`Setting::with_key`] tells apart, or the store cannot be read.
+    pub fn with_store(
+        mut self,
+        store: &'s mut dyn SettingsStore,
+        version: u32,
+    ) -> anyhow::Result<Self> {
+        for (index, setting) in self.settings.iter().enumerate() {
+            let taken = self.settings[..index]
+                .iter()
+                .find(|other| other.key == setting.key);
+            if let Some(other) = taken {
+                return Err(anyhow::anyhow!(
+                    "Settings \"{}\" and \"{}\" share the key \"{}\", set another with with_key",
+                    other.label,
+                    setting.label,
+                    setting.key
+                ));
+            }
+            if setting.key == VERSION_KEY {
+                return Err(anyhow::anyhow!(
+                    "Key \"{VERSION_KEY}\" of setting \"{}\" is reserved, set another with with_key",
+                    setting.label
+                ));
+            }
+        }
+        if store.load(VERSION_KEY)? == Some(version) {
+            for setting in &mut self.settings {
+                if let Some(stored) = store.load(setting.key)? {
+                    setting.decode(stored);
+                }
+            }
+        }
+        self.store = Some((store, version));
+        Ok(self)
+    }
+
+    /// Returns the index of the selected row: a setting, then `N` for Save and `N + 1` for
+    /// Cancel.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Returns whether the settings are being edited, or were saved or cancelled.
+    pub fn state(&self) -> EditState {
+        self.state
+    }
+
+    /// Reacts to an input event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the event was used. Events are ignored once the screen is saved or cancelled.
+    pub fn handle(&mut self, event: InputEvent) -> bool {
+        if self.state != EditState::Editing {
+            return false;
+        }
+        if self.error.take().is_some() {
+            self.drawn = false;
+        }
+        if self.editing {
+            let (used, state) = match &mut self.settings[self.selected].field {
+                Field::Toggle(..) => (false, EditState::Confirmed),
+                Field::Number(_, editor) => (editor.handle(event), editor.state()),
+                Field::Choice(_, picker) => (picker.handle(event), picker.state()),
+            };
+            if state != EditState::Editing {
+                self.editing = false;
+                self.drawn = false;
+            }
+            return used;
+        }
+        let last = N + ACTIONS.len() - 1;
+        match event {
+            InputEvent::Up => self.selected = self.selected.saturating_sub(1),
+            InputEvent::Down => self.selected = (self.selected + 1).min(last),
+            InputEvent::Encoder(detents) => {
+                let target = self.selected as isize + detents as isize;
+                self.selected = target.clamp(0, last as isize) as usize;
+            }
+            InputEvent::Left | InputEvent::Right => match self.settings.get_mut(self.selected) {
+                Some(Setting {
+                    field: Field::Toggle(_, draft),
+                    ..
+                }) => *draft = !*draft,
+                _ => return false,
+            },
+            InputEvent::Select => self.select(),
+            InputEvent::Back => self.state = EditState::Cancelled,
+            InputEvent::Char(_) => return false,
+        }
+        self.scroll_into_view();
+        self.drawn = false;
+        true
+    }
+
+    /// Draws the list of settings, or the setting being edited, across the whole display.
+    ///
+    /// The list is only sent again after it changed; an open editor is drawn on every call so
+    /// its digit can blink.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on, at least two rows.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the screen is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the display has fewer than two rows or there is an error while sending data.
+    pub fn render(&mut self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        if lcd.rows() < 2 {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+        if self.state != EditState::Editing {
+            return lcd.blink(false);
+        }
+        let rows = lcd.rows();
+        if !self.drawn {
+            self.drawn = true;
+            lcd.blink(false)?;
+            if self.editing {
+                print_line(lcd, 0, self.settings[self.selected].label)?;
+                for row in 1..rows {
+                    print_line(lcd, row, "")?;
+                }
+            } else {
+                self.set_window(rows);
+                for line in 0..rows {
+                    let text = self.line(line, lcd.cols());
+                    print_line(lcd, line, text.as_str())?;
+                }
+            }
+        }
+        if !self.editing {
+            return Ok(());
+        }
+        match &mut self.settings[self.selected].field {
+            Field::Toggle(..) => Ok(()),
+            Field::Number(_, editor) => editor.render(lcd, 0, 1),
+            Field::Choice(_, picker) => picker.render(lcd, 0, 1, rows - 1),
+        }
+    }
+
+    /// Acts on Select in the list: flips a toggle, opens an editor, saves or cancels.
+    fn select(&mut self) {
+        let Some(setting) = self.settings.get_mut(self.selected) else {
+            if self.selected == N {
+                // The store is written first, so a failed write leaves the fields untouched.
+                if self.persist().is_err() {
+                    self.error = Some("Save failed");
+                    return;
+                }
+                self.settings.iter_mut().for_each(Setting::save);
+                self.state = EditState::Confirmed;
+            } else {
+                self.state = EditState::Cancelled;
+            }
+            return;
+        };
+        match &mut setting.field {
+            Field::Toggle(_, draft) => *draft = !*draft,
+            // Editing restarts from the value kept so far, which Back then returns to.
+            Field::Number(_, editor) => {
+                editor.set_value(editor.value());
+                self.editing = true;
+            }
+            Field::Choice(_, picker) => {
+                picker.select(picker.selected());
+                self.editing = true;
+            }
+        }
+    }
+
+    /// Writes the edited values and the version to the store, if there is one, and commits
+    /// them.
+    fn persist(&mut self) -> anyhow::Result<()> {
+        let Some((store, version)) = &mut self.store else {
+            return Ok(());
+        };
+        for setting in &self.settings {
+            store.store(setting.key, setting.encode())?;
+        }
+        store.store(VERSION_KEY, *version)?;
+        store.commit()
+    }
+
+    /// Returns one row of the list: the marker, the label and the value against the right
+    /// edge of `cols` cells.
+    fn line(&self, line: u8, cols: u8) -> FmtBuf<48> {
+        let index = self.top + line as usize;
+        let marker = if index == self.selected { '>' } else { ' ' };
+        match self.settings.get(index) {
+            Some(setting) => {
+                let value = setting.value();
+                let width = (cols as usize)
+                    .saturating_sub(1 + value.as_str().chars().count())
+                    .max(1);
+                // The label is cut one cell short so it never touches the value.
+                let label = width - 1;
+                fmt_buf!(
+                    48,
+                    "{marker}{:<width$.label$}{}",
+                    setting.label,
+                    value.as_str()
+                )
+            }
+            None => match ACTIONS.get(index - N) {
+                // A failed Save shows on its row until the next event.
+                Some(action) => {
+                    let text = self.error.filter(|_| index == N).unwrap_or(action);
+                    fmt_buf!(48, "{marker}{text}")
+                }
+                None => FmtBuf::new(),
+            },
+        }
+    }
+
+    fn set_window(&mut self, height: u8) {
+        self.window = (height as usize).max(1);
+        self.scroll_into_view();
+    }
+
+    fn scroll_into_view(&mut self) {
+        if self.selected < self.top {
+            self.top = self.selected;
+        } else if self.selected >= self.top + self.window {
+            self.top = self.selected + 1 - self.window;
+        }
+        let last_top = (N + ACTIONS.len()).saturating_sub(self.window);
+        self.top = self.top.min(last_top);
+    }
+}
+
+/// Draws the list of settings filling the widget's rectangle, or the label of the setting
+/// being edited on its top row and the editor below.
+#[cfg(feature = "buffer")]
+impl<const N: usize> Widget for SettingsScreen<'_, N> {
+    fn render(&mut self, canvas: &mut Canvas) -> anyhow::Result<()> {
+        if self.state != EditState::Editing {
+            return Ok(());
+        }
+        if !self.editing {
+            self.set_window(canvas.height());
+            for line in 0..canvas.height() {
+                canvas.print(0, line, self.line(line, canvas.width()).as_str())?;
+            }
+            return Ok(());
+        }
+        let setting = &mut self.settings[self.selected];
+        canvas.print(0, 0, setting.label)?;
+        let rect = Rect::new(0, 1, canvas.width(), canvas.height().saturating_sub(1));
+        let mut below = canvas.sub(rect);
+        match &mut setting.field {
+            Field::Toggle(..) => Ok(()),
+            Field::Number(_, editor) => Widget::render(editor, &mut below),
+            Field::Choice(_, picker) => Widget::render(picker, &mut below),
+        }
+    }
+
+    fn blinks(&self) -> bool {
+        self.editing
+    }
+
+    fn handle(&mut self, event: InputEvent) -> bool {
+        SettingsScreen::handle(self, event)
+    }
+}
@@ -0,0 +1,161 @@
+use crate::glyphs::BAR_GLYPHS;
+use crate::Lcd;
+#[cfg(feature = "buffer")]
+use crate::{Canvas, Widget};
+
+/// A mini chart of recent samples drawn across one row with the bar glyphs.
+///
+/// The sparkline keeps the last `N` samples in a ring buffer and scales them between the
+/// smallest and largest stored value, so each cell shows one of eight bar heights. The newest
+/// sample is drawn in the rightmost column.
+///
+/// The widget loads [`BAR_GLYPHS`] into all eight CGRAM slots on its first render, so it
+/// cannot share the display with other custom characters.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut spark: Sparkline<16> = Sparkline::new();
+/// spark.push(21.5);
+/// spark.push(22.0);
+/// spark.render(&mut lcd, 1)?;
+/// ```
+pub struct Sparkline<const N: usize> {
+    samples: [f32; N],
+    head: usize,
+    len: usize,
+    glyphs_loaded: bool,
+}
+
+impl<const N: usize> Sparkline<N> {
+    /// Creates an empty sparkline.
+    pub const fn new() -> Self {
+        Self {
+            samples: [0.0; N],
+            head: 0,
+            len: 0,
+            glyphs_loaded: false,
+        }
+    }
+
+    /// Records a new sample, discarding the oldest one once `N` samples are stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample` - The sample value to record.
+    pub fn push(&mut self, sample: f32) {
+        if N == 0 {
+            return;
+        }
+        self.samples[self.head] = sample;
+        self.head = (self.head + 1) % N;
+        if self.len < N {
+            self.len += 1;
+        }
+    }
+
+    /// Removes all stored samples.
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Returns the number of stored samples.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no samples have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Draws the chart across the given row.
+    ///
+    /// The most recent `min(N, cols)` samples are drawn right-aligned; columns without a
+    /// sample are left blank.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `row` - The row to draw on (0-indexed).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the chart is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the row is out of bounds or there is an error while sending data.
+    pub fn render(&mut self, lcd: &mut Lcd, row: u8) -> anyhow::Result<()> {
+        if !self.glyphs_loaded {
+            for (location, glyph) in BAR_GLYPHS.iter().enumerate() {
+                lcd.create_custom_chars(location as u8, glyph)?;
+            }
+            self.glyphs_loaded = true;
+        }
+
+        let width = (lcd.cols() as usize).min(N);
+        let shown = self.len.min(width);
+        let (min, max) = self.range(shown);
+
+        lcd.set_cursor(0, row)?;
+        for _ in shown..width {
+            lcd.print(' ')?;
+        }
+        for i in (0..shown).rev() {
+            let level = Self::level(self.nth_newest(i), min, max);
+            lcd.write_byte(level - 1)?;
+        }
+        Ok(())
+    }
+
+    fn nth_newest(&self, n: usize) -> f32 {
+        self.samples[(self.head + N - 1 - n) % N]
+    }
+
+    fn range(&self, count: usize) -> (f32, f32) {
+        (0..count)
+            .map(|i| self.nth_newest(i))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), v| {
+                (lo.min(v), hi.max(v))
+            })
+    }
+
+    /// Maps a sample onto a bar level in `1..=8`.
+    fn level(sample: f32, min: f32, max: f32) -> u8 {
+        let span = max - min;
+        if span <= 0.0 || !span.is_finite() {
+            return 1;
+        }
+        let scaled = (sample - min) / span * 7.0;
+        1 + (scaled + 0.5).clamp(0.0, 7.0) as u8
+    }
+}
+
+/// Draws the chart across the top row of its rectangle, right-aligned like
+/// [`render`](Sparkline::render).
+#[cfg(feature = "buffer")]
+impl<const N: usize> Widget for Sparkline<N> {
+    fn setup(&mut self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        for (location, glyph) in BAR_GLYPHS.iter().enumerate() {
+            lcd.create_custom_chars(location as u8, glyph)?;
+        }
+        self.glyphs_loaded = true;
+        Ok(())
+    }
+
+    fn render(&mut self, canvas: &mut Canvas) -> anyhow::Result<()> {
+        let width = (canvas.width() as usize).min(N);
+        let shown = self.len.min(width);
+        let (min, max) = self.range(shown);
+        for i in 0..shown {
+            let level = Self::level(self.nth_newest(i), min, max);
+            canvas.set((width - 1 - i) as u8, 0, level - 1);
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> Default for Sparkline<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
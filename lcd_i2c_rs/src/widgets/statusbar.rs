@@ -0,0 +1,223 @@
+use crate::Lcd;
+
+/// A named place in a [`StatusBar`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusSlot {
+    /// The network state.
+    Net,
+    /// The battery level.
+    Battery,
+    /// An alarm or notification bell.
+    Alarm,
+    /// An application-defined slot.
+    Custom(u8),
+}
+
+/// What a [`StatusBar`] cell shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Icon {
+    /// Nothing.
+    #[default]
+    Blank,
+    /// A character from the ROM, e.g. `b'!'` or the full block `0xff`.
+    Code(u8),
+    /// A custom glyph, loaded into CGRAM by the bar.
+    Glyph([u8; 8]),
+}
+
+/// One cell of the bar.
+#[derive(Clone, Copy)]
+struct Cell {
+    slot: Option<StatusSlot>,
+    icon: Icon,
+    dirty: bool,
+}
+
+/// A row of `N` one-cell icons in the top right corner, such as the network, battery and
+/// alarm indicators.
+///
+/// Widgets claim a [`StatusSlot`] each, taking the free cells from the right, and set its
+/// [`Icon`]. The bar shares out a range of CGRAM slots among the custom glyphs shown, loading
+/// each distinct glyph once and reusing slots whose glyph is no longer shown, and redraws only
+/// the cells that changed.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut bar: StatusBar<3> = StatusBar::new().with_cgram(5, 3);
+/// bar.claim(StatusSlot::Net)?;
+/// bar.claim(StatusSlot::Battery)?;
+/// bar.set(StatusSlot::Net, Icon::Glyph(WIFI_GLYPHS[2]))?;
+/// bar.set(StatusSlot::Battery, Icon::Glyph(BATTERY_GLYPHS[level]))?;
+/// bar.render(&mut lcd)?;
+/// ```
+pub struct StatusBar<const N: usize> {
+    cells: [Cell; N],
+    first_cgram: u8,
+    cgram_count: u8,
+    loaded: [Option<[u8; 8]>; 8],
+}
+
+impl<const N: usize> StatusBar<N> {
+    /// Creates an empty bar that may use all eight CGRAM slots.
+    pub const fn new() -> Self {
+        Self {
+            cells: [Cell {
+                slot: None,
+                icon: Icon::Blank,
+                dirty: true,
+            }; N],
+            first_cgram: 0,
+            cgram_count: 8,
+            loaded: [None; 8],
+        }
+    }
+
+    /// Limits the bar to `count` CGRAM slots starting at `first`, leaving the others to the
+    /// application.
+    pub const fn with_cgram(mut self, first: u8, count: u8) -> Self {
+        let first = if first > 7 { 7 } else { first };
+        self.first_cgram = first;
+        self.cgram_count = if count > 8 - first { 8 - first } else { count };
+        self
+    }
+
+    /// Takes the rightmost free cell for a slot.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - The slot to claim.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the slot is claimed.
+    /// * `Err(anyhow::Error)` - If the slot is already claimed or every cell is taken.
+    pub fn claim(&mut self, slot: StatusSlot) -> anyhow::Result<()> {
+        if self.position(slot).is_some() {
+            return Err(anyhow::anyhow!("Status slot already claimed"));
+        }
+        let Some(cell) = self.cells.iter_mut().rev().find(|cell| cell.slot.is_none()) else {
+            return Err(anyhow::anyhow!("Status bar full"));
+        };
+        *cell = Cell {
+            slot: Some(slot),
+            icon: Icon::Blank,
+            dirty: true,
+        };
+        Ok(())
+    }
+
+    /// Frees the cell of a slot, blanking it.
+    pub fn release(&mut self, slot: StatusSlot) {
+        if let Some(index) = self.position(slot) {
+            self.cells[index] = Cell {
+                slot: None,
+                icon: Icon::Blank,
+                dirty: true,
+            };
+        }
+    }
+
+    /// Sets the icon of a slot, shown on the next render.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - The claimed slot.
+    /// * `icon` - The icon.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the icon is set.
+    /// * `Err(anyhow::Error)` - If the slot is not claimed.
+    pub fn set(&mut self, slot: StatusSlot, icon: Icon) -> anyhow::Result<()> {
+        let index = self
+            .position(slot)
+            .ok_or_else(|| anyhow::anyhow!("Status slot not claimed"))?;
+        let cell = &mut self.cells[index];
+        if cell.icon != icon {
+            cell.icon = icon;
+            cell.dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Returns the icon of a slot, if it is claimed.
+    pub fn icon(&self, slot: StatusSlot) -> Option<Icon> {
+        self.position(slot).map(|index| self.cells[index].icon)
+    }
+
+    /// Draws the cells that changed since the last render, in the last `N` columns of the top
+    /// row, loading the glyphs they need.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the bar is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the bar is wider than the display, more distinct glyphs are shown than the bar has CGRAM slots, or there is an error while sending data.
+    pub fn render(&mut self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        if N > lcd.cols() as usize {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+        let col = lcd.cols() - N as u8;
+        for index in 0..N {
+            let cell = self.cells[index];
+            if !cell.dirty {
+                continue;
+            }
+            let code = match cell.icon {
+                Icon::Blank => b' ',
+                Icon::Code(code) => code,
+                Icon::Glyph(glyph) => self.load(lcd, &glyph)?,
+            };
+            lcd.set_cursor(col + index as u8, 0)?;
+            lcd.write_byte(code)?;
+            self.cells[index].dirty = false;
+        }
+        Ok(())
+    }
+
+    /// Forces the next render to redraw every cell and reload every glyph, for example after
+    /// the display was cleared or the CGRAM slots were reused.
+    pub fn invalidate(&mut self) {
+        self.loaded = [None; 8];
+        for cell in &mut self.cells {
+            cell.dirty = true;
+        }
+    }
+
+    fn position(&self, slot: StatusSlot) -> Option<usize> {
+        self.cells.iter().position(|cell| cell.slot == Some(slot))
+    }
+
+    /// Returns the CGRAM slot holding `glyph`, loading it into a slot no cell shows if needed.
+    fn load(&mut self, lcd: &mut Lcd, glyph: &[u8; 8]) -> anyhow::Result<u8> {
+        let mut pool = self.first_cgram..self.first_cgram + self.cgram_count;
+        if let Some(slot) = pool
+            .clone()
+            .find(|&slot| self.loaded[slot as usize] == Some(*glyph))
+        {
+            return Ok(slot);
+        }
+        let in_use = |loaded: &Option<[u8; 8]>| {
+            self.cells.iter().any(|cell| match (cell.icon, loaded) {
+                (Icon::Glyph(shown), Some(loaded)) => shown == *loaded,
+                _ => false,
+            })
+        };
+        let free = pool
+            .find(|&slot| !in_use(&self.loaded[slot as usize]))
+            .ok_or_else(|| anyhow::anyhow!("No free CGRAM slot for the status bar"))?;
+        lcd.create_custom_chars(free, glyph)?;
+        self.loaded[free as usize] = Some(*glyph);
+        Ok(free)
+    }
+}
+
+impl<const N: usize> Default for StatusBar<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
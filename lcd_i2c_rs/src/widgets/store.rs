@@ -0,0 +1,133 @@
+use esp_idf_hal::sys::{self, EspError};
+
+/// The longest NVS namespace or key, in bytes.
+const MAX_KEY: usize = 15;
+
+/// Where a [`SettingsScreen`](super::SettingsScreen) keeps its values between boots.
+///
+/// Every value is stored as a `u32` under a short key. [`NvsStore`] keeps them in the ESP32's
+/// non-volatile storage; implement this trait to keep them elsewhere, e.g. in an EEPROM or in
+/// memory for host tests.
+pub trait SettingsStore {
+    /// Reads a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key, at most 15 bytes.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Option<u32>)` - The value, or `None` if nothing is stored under the key.
+    /// * `Err(anyhow::Error)` - If the storage cannot be read.
+    fn load(&mut self, key: &str) -> anyhow::Result<Option<u32>>;
+
+    /// Writes a value, to be kept once [`commit`](Self::commit) returns.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key, at most 15 bytes.
+    /// * `value` - The value.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the value is written.
+    /// * `Err(anyhow::Error)` - If the storage cannot be written.
+    fn store(&mut self, key: &str, value: u32) -> anyhow::Result<()>;
+
+    /// Makes the values written so far survive a reboot.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the values are committed.
+    /// * `Err(anyhow::Error)` - If the storage cannot be written.
+    fn commit(&mut self) -> anyhow::Result<()>;
+}
+
+/// A [`SettingsStore`] in one namespace of the default NVS partition.
+///
+/// Settings of different screens, or of other parts of the firmware, stay apart as long as
+/// each uses its own namespace.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut store = NvsStore::new("thermostat")?;
+/// let screen = SettingsScreen::new(settings(&mut config)).with_store(&mut store, 1)?;
+/// ```
+pub struct NvsStore {
+    handle: sys::nvs_handle_t,
+}
+
+impl NvsStore {
+    /// Opens a namespace for reading and writing, initializing the NVS partition first if
+    /// nothing else has.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace, at most 15 bytes.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(NvsStore)` - The open namespace.
+    /// * `Err(anyhow::Error)` - If the namespace is too long, or the partition cannot be initialized or opened, e.g. because it is full or was written by a newer ESP-IDF and must be erased.
+    pub fn new(namespace: &str) -> anyhow::Result<Self> {
+        let name = c_key(namespace)?;
+        let mut handle = 0;
+        // SAFETY: `name` is nul-terminated and `handle` outlives the calls.
+        unsafe {
+            EspError::convert(sys::nvs_flash_init())?;
+            EspError::convert(sys::nvs_open(
+                name.as_ptr().cast(),
+                sys::nvs_open_mode_t_NVS_READWRITE,
+                &mut handle,
+            ))?;
+        }
+        Ok(Self { handle })
+    }
+}
+
+impl SettingsStore for NvsStore {
+    fn load(&mut self, key: &str) -> anyhow::Result<Option<u32>> {
+        let key = c_key(key)?;
+        let mut value = 0;
+        // SAFETY: the handle is open, `key` is nul-terminated and `value` outlives the call.
+        let result = unsafe { sys::nvs_get_u32(self.handle, key.as_ptr().cast(), &mut value) };
+        if result == sys::ESP_ERR_NVS_NOT_FOUND as sys::esp_err_t {
+            return Ok(None);
+        }
+        EspError::convert(result)?;
+        Ok(Some(value))
+    }
+
+    fn store(&mut self, key: &str, value: u32) -> anyhow::Result<()> {
+        let key = c_key(key)?;
+        // SAFETY: the handle is open and `key` is nul-terminated.
+        EspError::convert(unsafe { sys::nvs_set_u32(self.handle, key.as_ptr().cast(), value) })?;
+        Ok(())
+    }
+
+    fn commit(&mut self) -> anyhow::Result<()> {
+        // SAFETY: the handle is open.
+        EspError::convert(unsafe { sys::nvs_commit(self.handle) })?;
+        Ok(())
+    }
+}
+
+impl Drop for NvsStore {
+    fn drop(&mut self) {
+        // SAFETY: the handle was opened in `new` and is closed only here.
+        unsafe { sys::nvs_close(self.handle) };
+    }
+}
+
+/// Copies a namespace or key into a nul-terminated buffer.
+fn c_key(key: &str) -> anyhow::Result<[u8; MAX_KEY + 1]> {
+    if key.len() > MAX_KEY || key.contains('\0') {
+        return Err(anyhow::anyhow!(
+            "NVS key must be at most 15 bytes without nul"
+        ));
+    }
+    let mut buf = [0; MAX_KEY + 1];
+    buf[..key.len()].copy_from_slice(key.as_bytes());
+    Ok(buf)
+}
@@ -0,0 +1,196 @@
+use crate::Lcd;
+
+/// Horizontal alignment of text inside a fixed-width cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    /// Text starts at the left edge of the cell.
+    #[default]
+    Left,
+    /// Text ends at the right edge of the cell.
+    Right,
+}
+
+/// A fixed-column table layout, aimed at 20x4 displays showing several channels of data.
+///
+/// Columns have fixed widths and may be separated by a single separator character. When a
+/// header row is enabled, row 0 holds the column headers and data rows start at row 1.
+/// Cell text longer than its column is truncated and shorter text is padded with spaces, so
+/// updating one cell never disturbs its neighbours. Widths are counted in display cells, see
+/// [`DisplayWidth`](crate::charset::DisplayWidth).
+///
+/// # Example
+///
+/// ```ignore
+/// let table = Table::new([6, 6, 6]).with_separator('|').with_header();
+/// table.draw_headers(&mut lcd, &["Ch1", "Ch2", "Ch3"])?;
+/// table.set_cell(&mut lcd, 0, 1, "12.5V")?;
+/// ```
+pub struct Table<const C: usize> {
+    widths: [u8; C],
+    align: [Align; C],
+    separator: Option<char>,
+    header: bool,
+}
+
+impl<const C: usize> Table<C> {
+    /// Creates a table with the given column widths, no separators and no header row.
+    ///
+    /// # Arguments
+    ///
+    /// * `widths` - The width of each column in characters.
+    pub const fn new(widths: [u8; C]) -> Self {
+        Self {
+            widths,
+            align: [Align::Left; C],
+            separator: None,
+            header: false,
+        }
+    }
+
+    /// Draws `separator` between adjacent columns.
+    pub const fn with_separator(mut self, separator: char) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+
+    /// Reserves row 0 for column headers.
+    pub const fn with_header(mut self) -> Self {
+        self.header = true;
+        self
+    }
+
+    /// Sets the alignment used for a column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `col` is not less than `C`.
+    pub fn with_alignment(mut self, col: usize, align: Align) -> Self {
+        self.align[col] = align;
+        self
+    }
+
+    /// Returns the total width of the table in characters, including separators.
+    ///
+    /// Counted in `u16`, since the column widths together may exceed any display.
+    pub fn width(&self) -> u16 {
+        let separators = if self.separator.is_some() {
+            u16::try_from(C.saturating_sub(1)).unwrap_or(u16::MAX)
+        } else {
+            0
+        };
+        self.widths
+            .iter()
+            .map(|&w| u16::from(w))
+            .fold(separators, u16::saturating_add)
+    }
+
+    /// Returns the display column at which the given table column starts.
+    pub fn column_start(&self, col: usize) -> u16 {
+        let sep = u16::from(self.separator.is_some());
+        self.widths[..col]
+            .iter()
+            .map(|&w| u16::from(w) + sep)
+            .fold(0, u16::saturating_add)
+    }
+
+    /// Writes the header row.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `headers` - The header text of each column.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the headers are successfully written.
+    /// * `Err(anyhow::Error)` - If the table has no header row, does not fit the display,
+    ///   or there is an error while sending data.
+    pub fn draw_headers(&self, lcd: &mut Lcd, headers: &[&str; C]) -> anyhow::Result<()> {
+        if !self.header {
+            return Err(anyhow::anyhow!("Table has no header row"));
+        }
+        self.write_row(lcd, 0, headers)
+    }
+
+    /// Blanks every data row and draws the column separators.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the rows are successfully drawn.
+    /// * `Err(anyhow::Error)` - If the table does not fit the display or there is an error while sending data.
+    pub fn draw_grid(&self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        for row in self.first_data_row()..lcd.rows() {
+            self.write_row(lcd, row, &[""; C])?;
+        }
+        Ok(())
+    }
+
+    /// Updates a single cell, truncating or padding the text to the column width.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `row` - The data row (0-indexed, not counting the header row).
+    /// * `col` - The table column (0-indexed).
+    /// * `text` - The text to show in the cell.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the cell is successfully updated.
+    /// * `Err(anyhow::Error)` - If the cell is out of bounds, the table does not fit the display,
+    ///   or there is an error while sending data.
+    pub fn set_cell(&self, lcd: &mut Lcd, row: u8, col: usize, text: &str) -> anyhow::Result<()> {
+        if col >= C {
+            return Err(anyhow::anyhow!("Column out of bounds"));
+        }
+        self.check_fits(lcd)?;
+        let row = self.first_data_row() + row;
+        // `check_fits` has made sure every column starts on the display.
+        lcd.set_cursor(self.column_start(col) as u8, row)?;
+        self.write_cell(lcd, col, text)
+    }
+
+    fn first_data_row(&self) -> u8 {
+        u8::from(self.header)
+    }
+
+    fn check_fits(&self, lcd: &Lcd) -> anyhow::Result<()> {
+        if self.width() > u16::from(lcd.cols()) {
+            return Err(anyhow::anyhow!("Table is wider than the display"));
+        }
+        Ok(())
+    }
+
+    fn write_row(&self, lcd: &mut Lcd, row: u8, cells: &[&str; C]) -> anyhow::Result<()> {
+        self.check_fits(lcd)?;
+        lcd.set_cursor(0, row)?;
+        for (col, text) in cells.iter().enumerate() {
+            if col > 0 {
+                if let Some(separator) = self.separator {
+                    lcd.print(separator)?;
+                }
+            }
+            self.write_cell(lcd, col, text)?;
+        }
+        Ok(())
+    }
+
+    fn write_cell(&self, lcd: &mut Lcd, col: usize, text: &str) -> anyhow::Result<()> {
+        let width = self.widths[col] as usize;
+        let (text, len) = lcd.charmap().truncate(text, width);
+        let padding = width - len;
+
+        if self.align[col] == Align::Right {
+            for _ in 0..padding {
+                lcd.print(' ')?;
+            }
+        }
+        lcd.print_str(text)?;
+        if self.align[col] == Align::Left {
+            for _ in 0..padding {
+                lcd.print(' ')?;
+            }
+        }
+        Ok(())
+    }
+}
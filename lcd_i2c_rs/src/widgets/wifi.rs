@@ -0,0 +1,203 @@
+use crate::glyphs::{SIGNAL_GLYPHS, WIFI_GLYPHS};
+use crate::{Col, Lcd, Row};
+
+/// The longest SSID allowed by 802.11.
+const MAX_SSID: usize = 32;
+
+/// The connection state shown by a [`WifiStatusWidget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WifiState {
+    /// Not connected to any network.
+    #[default]
+    Disconnected,
+    /// Associating with a network or waiting for an address.
+    Connecting,
+    /// Connected as a station.
+    Connected {
+        /// The received signal strength in dBm.
+        rssi: i8,
+    },
+    /// Running as an access point, typically for provisioning.
+    AccessPoint,
+}
+
+impl WifiState {
+    /// Returns the number of signal bars, 0 to 4, for the state.
+    pub fn bars(self) -> u8 {
+        match self {
+            WifiState::Connected { rssi } => match rssi {
+                -55.. => 4,
+                -66..=-56 => 3,
+                -77..=-67 => 2,
+                -88..=-78 => 1,
+                _ => 0,
+            },
+            _ => 0,
+        }
+    }
+
+    fn icon(self) -> usize {
+        match self {
+            WifiState::Disconnected => 0,
+            WifiState::Connecting => 1,
+            WifiState::Connected { .. } => 2,
+            WifiState::AccessPoint => 3,
+        }
+    }
+}
+
+/// A status corner showing the Wi-Fi state icon, signal bars and the SSID.
+///
+/// The widget takes two CGRAM slots, one for the state icon and one for the signal bars.
+/// Changing the state rewrites the glyphs in those slots, so only the cells that changed are
+/// sent again. The SSID follows the two icon cells and is truncated to the widget's width.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut wifi = WifiStatusWidget::new().with_slots(6, 7);
+/// wifi.set_ssid("workshop")?;
+/// wifi.set_state(WifiState::Connected { rssi: -61 });
+/// wifi.render(&mut lcd, 10, 0, 10)?;
+/// ```
+pub struct WifiStatusWidget {
+    state: WifiState,
+    ssid: [u8; MAX_SSID],
+    ssid_len: usize,
+    icon_slot: u8,
+    bars_slot: u8,
+    loaded: Option<(usize, u8)>,
+    dirty: bool,
+}
+
+impl WifiStatusWidget {
+    /// Creates a disconnected widget with no SSID, using CGRAM slots 0 and 1.
+    pub const fn new() -> Self {
+        Self {
+            state: WifiState::Disconnected,
+            ssid: [0; MAX_SSID],
+            ssid_len: 0,
+            icon_slot: 0,
+            bars_slot: 1,
+            loaded: None,
+            dirty: true,
+        }
+    }
+
+    /// Sets the CGRAM slots used for the state icon and the signal bars.
+    ///
+    /// # Arguments
+    ///
+    /// * `icon_slot` - The slot (0-7) for the state icon.
+    /// * `bars_slot` - The slot (0-7) for the signal bars.
+    pub const fn with_slots(mut self, icon_slot: u8, bars_slot: u8) -> Self {
+        self.icon_slot = icon_slot;
+        self.bars_slot = bars_slot;
+        self
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> WifiState {
+        self.state
+    }
+
+    /// Sets the connection state shown on the next render.
+    pub fn set_state(&mut self, state: WifiState) {
+        self.state = state;
+    }
+
+    /// Sets the network name shown next to the icons.
+    ///
+    /// # Arguments
+    ///
+    /// * `ssid` - The network name.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the name is stored.
+    /// * `Err(anyhow::Error)` - If the name is longer than 32 bytes.
+    pub fn set_ssid(&mut self, ssid: &str) -> anyhow::Result<()> {
+        if ssid.len() > MAX_SSID {
+            return Err(anyhow::anyhow!("SSID too long"));
+        }
+        if ssid.as_bytes() != &self.ssid[..self.ssid_len] {
+            self.ssid[..ssid.len()].copy_from_slice(ssid.as_bytes());
+            self.ssid_len = ssid.len();
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Returns the network name.
+    pub fn ssid(&self) -> &str {
+        // Only whole strings are copied in, see `set_ssid`.
+        core::str::from_utf8(&self.ssid[..self.ssid_len]).unwrap_or("")
+    }
+
+    /// Draws the widget, sending only what changed since the last render at the same position.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on.
+    /// * `col` - The column of the state icon (0-indexed).
+    /// * `row` - The row to draw on (0-indexed).
+    /// * `width` - The number of cells the widget may use, at least 2 for the icons.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the widget is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the widget does not fit on the display, or there is an error while sending data.
+    pub fn render(
+        &mut self,
+        lcd: &mut Lcd,
+        col: impl Into<Col>,
+        row: impl Into<Row>,
+        width: u8,
+    ) -> anyhow::Result<()> {
+        let (col, row) = (col.into().0, row.into().0);
+        if width < 2 || col.saturating_add(width) > lcd.cols() || row >= lcd.rows() {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+
+        let glyphs = (self.state.icon(), self.state.bars());
+        let previous = self.loaded;
+        if previous.map(|(icon, _)| icon) != Some(glyphs.0) {
+            lcd.create_custom_chars(self.icon_slot, &WIFI_GLYPHS[glyphs.0])?;
+        }
+        if previous.map(|(_, bars)| bars) != Some(glyphs.1) {
+            lcd.create_custom_chars(self.bars_slot, &SIGNAL_GLYPHS[glyphs.1 as usize])?;
+        }
+        self.loaded = Some(glyphs);
+
+        if !self.dirty {
+            return Ok(());
+        }
+        lcd.set_cursor(col, row)?;
+        lcd.write_byte(self.icon_slot)?;
+        lcd.write_byte(self.bars_slot)?;
+        let space = width as usize - 2;
+        if space > 0 {
+            let (text, len) = lcd.charmap().truncate(self.ssid(), space - 1);
+            lcd.print(' ')?;
+            lcd.print_str(text)?;
+            for _ in len + 1..space {
+                lcd.print(' ')?;
+            }
+        }
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Forces the next render to redraw every cell and reload both glyphs, for example after
+    /// the display was cleared or the CGRAM slots were reused.
+    pub fn invalidate(&mut self) {
+        self.loaded = None;
+        self.dirty = true;
+    }
+}
+
+impl Default for WifiStatusWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
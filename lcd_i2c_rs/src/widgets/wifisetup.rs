@@ -0,0 +1,387 @@
+use super::field::InputField;
+use super::number::EditState;
+use super::picker::Picker;
+use super::print_line;
+use crate::fmt::{fmt_buf, FmtBuf};
+use crate::input::InputEvent;
+use crate::Lcd;
+
+/// The longest SSID allowed by 802.11.
+const MAX_SSID: usize = 32;
+/// The longest WPA2 passphrase.
+const MAX_PASSWORD: usize = 64;
+
+/// The Wi-Fi driver a [`WifiSetupFlow`] scans and connects with.
+///
+/// Implement it over the station interface of the Wi-Fi stack in use, e.g. `BlockingWifi`
+/// from esp-idf-svc.
+pub trait WifiBackend {
+    /// Scans for networks, blocking until the scan is done.
+    ///
+    /// # Arguments
+    ///
+    /// * `found` - Called once per network with its SSID, its signal strength in dBm and
+    ///   whether it is open, i.e. needs no password.
+    fn scan(&mut self, found: &mut dyn FnMut(&str, i8, bool)) -> anyhow::Result<()>;
+
+    /// Connects to a network, blocking until it is connected or the attempt fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `ssid` - The network to join.
+    /// * `password` - The passphrase, empty for an open network.
+    fn connect(&mut self, ssid: &str, password: &str) -> anyhow::Result<()>;
+}
+
+/// The network and passphrase a [`WifiSetupFlow`] connected with, to be stored for the next
+/// boot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Credentials {
+    ssid: [u8; MAX_SSID],
+    ssid_len: usize,
+    password: [u8; MAX_PASSWORD],
+    password_len: usize,
+}
+
+impl Credentials {
+    /// Returns the SSID.
+    pub fn ssid(&self) -> &str {
+        core::str::from_utf8(&self.ssid[..self.ssid_len]).unwrap_or("")
+    }
+
+    /// Returns the passphrase, empty for an open network.
+    pub fn password(&self) -> &str {
+        core::str::from_utf8(&self.password[..self.password_len]).unwrap_or("")
+    }
+}
+
+/// Where a [`WifiSetupFlow`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SetupStep {
+    /// Scanning for networks.
+    Scanning,
+    /// Showing the networks found to pick from.
+    Picking,
+    /// Taking the passphrase of the picked network.
+    Password,
+    /// Connecting to the picked network.
+    Connecting,
+    /// The scan or the connection failed. Select tries again, Back returns to the list.
+    Failed,
+    /// Connected; the credentials were handed back.
+    Done,
+    /// Setup was abandoned with Back from the network list.
+    Cancelled,
+}
+
+/// One network found by a scan.
+#[derive(Clone, Copy)]
+struct Network {
+    ssid: [u8; MAX_SSID],
+    len: usize,
+    rssi: i8,
+    open: bool,
+}
+
+impl Network {
+    const EMPTY: Self = Self {
+        ssid: [0; MAX_SSID],
+        len: 0,
+        rssi: i8::MIN,
+        open: false,
+    };
+
+    fn ssid(&self) -> &str {
+        core::str::from_utf8(&self.ssid[..self.len]).unwrap_or("")
+    }
+}
+
+/// A ready-made provisioning screen: scans for networks, lists them in a [`Picker`], takes
+/// the passphrase with an [`InputField`], connects, and hands back the [`Credentials`].
+///
+/// Up to `S` networks are listed, strongest first, with hidden networks and repeated SSIDs
+/// left out. Open networks skip the passphrase, which is masked as it is typed unless
+/// [`reveal`](Self::reveal)ed. Scanning and connecting block, so the flow shows what it is
+/// doing before each, and a failure shows the error until Select tries again or Back returns
+/// to the list. Back from the list cancels setup.
+///
+/// Call [`run`](Self::run) from the UI loop with each input event, or `None` to redraw.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut setup: WifiSetupFlow<_, 16> = WifiSetupFlow::new(backend);
+/// let credentials = loop {
+///     if let Some(credentials) = setup.run(&mut lcd, keypad.next_event()?)? {
+///         break credentials;
+///     }
+/// };
+/// nvs.set_str("ssid", credentials.ssid())?;
+/// ```
+pub struct WifiSetupFlow<B: WifiBackend, const S: usize = 16> {
+    backend: B,
+    networks: [Network; S],
+    count: usize,
+    view: (usize, usize, usize),
+    password: InputField<MAX_PASSWORD>,
+    step: SetupStep,
+    drawn: Option<SetupStep>,
+    error: FmtBuf<64>,
+}
+
+impl<B: WifiBackend, const S: usize> WifiSetupFlow<B, S> {
+    /// Creates a flow that starts with a scan.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The Wi-Fi driver to scan and connect with.
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            networks: [Network::EMPTY; S],
+            count: 0,
+            view: (0, 0, 1),
+            password: InputField::new().with_mask(),
+            step: SetupStep::Scanning,
+            drawn: None,
+            error: FmtBuf::new(),
+        }
+    }
+
+    /// Returns where the flow is.
+    pub fn step(&self) -> SetupStep {
+        self.step
+    }
+
+    /// Starts over with a new scan, e.g. to change networks later.
+    pub fn restart(&mut self) {
+        self.step = SetupStep::Scanning;
+    }
+
+    /// Shows or hides again the passphrase being entered, which is masked as it is typed.
+    pub fn reveal(&mut self) {
+        self.password.reveal();
+    }
+
+    /// Returns the backend, e.g. to keep the connection once setup is done.
+    pub fn into_inner(self) -> B {
+        self.backend
+    }
+
+    /// Reacts to an input event, scans or connects if that is the next step, and draws the
+    /// current step across the whole display.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on, at least two rows.
+    /// * `event` - The input event, or `None` to only redraw.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(Credentials))` - If the flow just connected.
+    /// * `Ok(None)` - If setup is still going, or already ended.
+    /// * `Err(anyhow::Error)` - If the display has fewer than two rows or there is an error while sending data. Scan and connection errors are shown instead.
+    pub fn run(
+        &mut self,
+        lcd: &mut Lcd,
+        event: Option<InputEvent>,
+    ) -> anyhow::Result<Option<Credentials>> {
+        if lcd.rows() < 2 {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+        if let Some(event) = event {
+            self.handle(event);
+        }
+        match self.step {
+            SetupStep::Scanning => {
+                self.draw(lcd, "Scanning...", "")?;
+                self.scan();
+                self.run(lcd, None)
+            }
+            SetupStep::Connecting => {
+                let network = self.networks[self.view.0];
+                self.draw(lcd, "Connecting to", network.ssid())?;
+                let password = if network.open {
+                    ""
+                } else {
+                    self.password.text()
+                };
+                match self.backend.connect(network.ssid(), password) {
+                    Ok(()) => {
+                        self.step = SetupStep::Done;
+                        self.draw(lcd, "Connected to", network.ssid())?;
+                        Ok(Some(self.credentials(&network)))
+                    }
+                    Err(err) => {
+                        self.fail(fmt_buf!(64, "{}", err));
+                        self.run(lcd, None)
+                    }
+                }
+            }
+            SetupStep::Picking => {
+                self.clear_once(lcd)?;
+                let names = names(&self.networks);
+                let mut picker = Picker::new(&names[..self.count]);
+                picker.restore(self.view);
+                picker.render(lcd, 0, 0, lcd.rows())?;
+                self.view = picker.view();
+                Ok(None)
+            }
+            SetupStep::Password => {
+                self.clear_once(lcd)?;
+                let ssid = self.networks[self.view.0];
+                print_line(lcd, 0, ssid.ssid())?;
+                self.password.render(lcd, 0, 1, lcd.cols())?;
+                Ok(None)
+            }
+            SetupStep::Failed => {
+                self.clear_once(lcd)?;
+                print_line(lcd, 0, "Failed:")?;
+                print_line(lcd, 1, self.error.as_str())?;
+                Ok(None)
+            }
+            SetupStep::Done | SetupStep::Cancelled => Ok(None),
+        }
+    }
+
+    fn handle(&mut self, event: InputEvent) {
+        match self.step {
+            SetupStep::Picking => {
+                let names = names(&self.networks);
+                let mut picker = Picker::new(&names[..self.count]);
+                picker.restore(self.view);
+                picker.handle(event);
+                self.view = picker.view();
+                match picker.state() {
+                    EditState::Confirmed if self.networks[self.view.0].open => {
+                        self.step = SetupStep::Connecting;
+                    }
+                    EditState::Confirmed => {
+                        self.password = self.new_password("");
+                        self.step = SetupStep::Password;
+                    }
+                    EditState::Cancelled => self.step = SetupStep::Cancelled,
+                    EditState::Editing => {}
+                }
+            }
+            SetupStep::Password => {
+                self.password.handle(event);
+                match self.password.state() {
+                    EditState::Confirmed => self.step = SetupStep::Connecting,
+                    EditState::Cancelled => self.step = SetupStep::Picking,
+                    EditState::Editing => {}
+                }
+            }
+            SetupStep::Failed => match event {
+                // A failed scan has no list to return to, so both scan again.
+                InputEvent::Select | InputEvent::Back if self.count == 0 => {
+                    self.step = SetupStep::Scanning;
+                }
+                InputEvent::Select if self.networks[self.view.0].open => {
+                    self.step = SetupStep::Connecting;
+                }
+                InputEvent::Select => {
+                    self.password = self.new_password(self.password.text());
+                    self.step = SetupStep::Password;
+                }
+                InputEvent::Back => self.step = SetupStep::Picking,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    /// Returns a masked field to enter the passphrase again from `text`, revealed if the
+    /// previous one was.
+    fn new_password(&self, text: &str) -> InputField<MAX_PASSWORD> {
+        let mut field = InputField::new().with_mask();
+        field.set_text(text);
+        if self.password.is_revealed() {
+            field.reveal();
+        }
+        field
+    }
+
+    /// Runs a scan and keeps the `S` strongest distinct networks.
+    fn scan(&mut self) {
+        let (networks, count) = (&mut self.networks, &mut self.count);
+        *count = 0;
+        let result = self.backend.scan(&mut |ssid, rssi, open| {
+            if ssid.is_empty() || ssid.len() > MAX_SSID {
+                return;
+            }
+            if let Some(known) = networks[..*count].iter_mut().find(|n| n.ssid() == ssid) {
+                known.rssi = known.rssi.max(rssi);
+                return;
+            }
+            let mut network = Network {
+                len: ssid.len(),
+                rssi,
+                open,
+                ..Network::EMPTY
+            };
+            network.ssid[..ssid.len()].copy_from_slice(ssid.as_bytes());
+            if *count < S {
+                networks[*count] = network;
+                *count += 1;
+            } else if let Some(weakest) = networks.iter_mut().min_by_key(|n| n.rssi) {
+                if weakest.rssi < rssi {
+                    *weakest = network;
+                }
+            }
+        });
+        self.networks[..self.count].sort_by_key(|network| core::cmp::Reverse(network.rssi));
+        self.view = (0, 0, 1);
+        match result {
+            Err(err) => {
+                self.count = 0;
+                self.fail(fmt_buf!(64, "{}", err));
+            }
+            Ok(()) if self.count == 0 => self.fail(fmt_buf!(64, "No networks found")),
+            Ok(()) => self.step = SetupStep::Picking,
+        }
+    }
+
+    fn fail(&mut self, error: FmtBuf<64>) {
+        self.error = error;
+        self.step = SetupStep::Failed;
+    }
+
+    fn credentials(&self, network: &Network) -> Credentials {
+        let mut credentials = Credentials {
+            ssid: network.ssid,
+            ssid_len: network.len,
+            password: [0; MAX_PASSWORD],
+            password_len: 0,
+        };
+        if !network.open {
+            let password = self.password.text().as_bytes();
+            credentials.password[..password.len()].copy_from_slice(password);
+            credentials.password_len = password.len();
+        }
+        credentials
+    }
+
+    /// Clears the display when the flow moves on to a new step.
+    fn clear_once(&mut self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        if self.drawn != Some(self.step) {
+            self.drawn = Some(self.step);
+            lcd.blink(false)?;
+            lcd.clear()?;
+        }
+        Ok(())
+    }
+
+    /// Draws a status screen of two lines.
+    fn draw(&mut self, lcd: &mut Lcd, title: &str, detail: &str) -> anyhow::Result<()> {
+        self.drawn = Some(self.step);
+        lcd.blink(false)?;
+        print_line(lcd, 0, title)?;
+        print_line(lcd, 1, detail)
+    }
+}
+
+/// Returns the SSIDs of the networks, to list in a [`Picker`].
+fn names<const S: usize>(networks: &[Network; S]) -> [&str; S] {
+    core::array::from_fn(|i| networks[i].ssid())
+}
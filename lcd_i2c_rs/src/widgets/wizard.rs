@@ -0,0 +1,332 @@
+use super::number::{EditState, NumberEditor};
+use super::picker::Picker;
+use super::print_line;
+use crate::input::InputEvent;
+use crate::Lcd;
+#[cfg(feature = "buffer")]
+use crate::{Canvas, Rect, Widget};
+
+/// A check run on the value of a [`WizardStep`] before the wizard moves on.
+///
+/// It gets the value and the results of all steps so far, and returns the message to show if
+/// the value is not acceptable.
+pub type Validation = fn(StepValue, &[Option<StepValue>]) -> Result<(), &'static str>;
+
+/// What a [`WizardStep`] asks for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepInput<'w> {
+    /// Nothing but Select to go on, e.g. once a reference weight is on the scale.
+    Confirm,
+    /// A number, edited with a [`NumberEditor`].
+    Number {
+        /// The smallest value.
+        min: f32,
+        /// The largest value.
+        max: f32,
+        /// How much one step changes the value.
+        step: f32,
+        /// The number of places after the decimal point.
+        decimals: u8,
+        /// The unit shown after the value.
+        unit: &'w str,
+    },
+    /// One of a list of options, picked with a [`Picker`].
+    Choice(&'w [&'w str]),
+}
+
+/// The result of one [`WizardStep`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepValue {
+    /// A [`StepInput::Confirm`] step was confirmed.
+    Confirmed,
+    /// The number entered in a [`StepInput::Number`] step.
+    Number(f32),
+    /// The index of the option picked in a [`StepInput::Choice`] step.
+    Choice(usize),
+}
+
+/// One screen of a [`Wizard`]: a prompt on the top row and the input it expects below.
+#[derive(Debug, Clone, Copy)]
+pub struct WizardStep<'w> {
+    prompt: &'w str,
+    input: StepInput<'w>,
+    validate: Option<Validation>,
+}
+
+impl<'w> WizardStep<'w> {
+    /// Creates a step that accepts any value.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The instruction shown on the top row.
+    /// * `input` - What the step asks for.
+    pub const fn new(prompt: &'w str, input: StepInput<'w>) -> Self {
+        Self {
+            prompt,
+            input,
+            validate: None,
+        }
+    }
+
+    /// Checks the value before moving on, keeping the step with the returned message shown
+    /// if it fails.
+    pub const fn with_validation(mut self, validate: Validation) -> Self {
+        self.validate = Some(validate);
+        self
+    }
+}
+
+/// The input of the current step.
+enum Editor<'w> {
+    Confirm(EditState),
+    Number(NumberEditor<'w>),
+    Choice(Picker<'w>),
+}
+
+/// Guides the user through `N` steps in order, such as calibrating a sensor or a first-boot
+/// setup, collecting one result per step.
+///
+/// Each step shows its prompt on the top row and its input below: Select to go on, a number
+/// or a choice. Select confirms the step and, once its validation passes, moves to the next;
+/// a failed validation shows its message until the next event. Back returns to the previous
+/// step with its earlier result, and from the first step cancels the wizard.
+///
+/// # Example
+///
+/// ```ignore
+/// const STEPS: [WizardStep; 3] = [
+///     WizardStep::new("Empty the scale", StepInput::Confirm),
+///     WizardStep::new("Reference weight", StepInput::Number {
+///         min: 0.1, max: 10.0, step: 0.1, decimals: 1, unit: "kg",
+///     })
+///     .with_validation(|value, _| match value {
+///         StepValue::Number(kg) if kg >= 0.5 => Ok(()),
+///         _ => Err("Use 0.5kg or more"),
+///     }),
+///     WizardStep::new("Units", StepInput::Choice(&["kg", "lb"])),
+/// ];
+/// let mut wizard = Wizard::new(&STEPS);
+/// while wizard.state() == EditState::Editing {
+///     if let Some(event) = keypad.next_event()? {
+///         wizard.handle(event);
+///     }
+///     wizard.render(&mut lcd)?;
+/// }
+/// ```
+pub struct Wizard<'w, const N: usize> {
+    steps: &'w [WizardStep<'w>; N],
+    results: [Option<StepValue>; N],
+    current: usize,
+    editor: Editor<'w>,
+    error: Option<&'static str>,
+    state: EditState,
+    drawn: Option<usize>,
+}
+
+impl<'w, const N: usize> Wizard<'w, N> {
+    /// Creates a wizard at its first step.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - The steps, in order.
+    pub fn new(steps: &'w [WizardStep<'w>; N]) -> Self {
+        let mut wizard = Self {
+            steps,
+            results: [None; N],
+            current: 0,
+            editor: Editor::Confirm(EditState::Editing),
+            error: None,
+            state: EditState::Editing,
+            drawn: None,
+        };
+        wizard.open(0);
+        wizard
+    }
+
+    /// Returns the index of the current step.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Returns the result of each step, `None` for the steps not done yet.
+    pub fn results(&self) -> &[Option<StepValue>; N] {
+        &self.results
+    }
+
+    /// Returns whether the wizard is running, or was finished or cancelled.
+    pub fn state(&self) -> EditState {
+        self.state
+    }
+
+    /// Reacts to an input event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the event was used. Events are ignored once the wizard has ended.
+    pub fn handle(&mut self, event: InputEvent) -> bool {
+        if self.state != EditState::Editing || N == 0 {
+            return false;
+        }
+        let had_error = self.error.take().is_some();
+        let used = match &mut self.editor {
+            Editor::Confirm(state) => match event {
+                InputEvent::Select => {
+                    *state = EditState::Confirmed;
+                    true
+                }
+                InputEvent::Back => {
+                    *state = EditState::Cancelled;
+                    true
+                }
+                _ => false,
+            },
+            Editor::Number(editor) => editor.handle(event),
+            Editor::Choice(picker) => picker.handle(event),
+        };
+        match self.editor_state() {
+            (EditState::Confirmed, value) => self.confirm(value),
+            (EditState::Cancelled, _) if self.current == 0 => self.state = EditState::Cancelled,
+            (EditState::Cancelled, _) => self.open(self.current - 1),
+            _ => {}
+        }
+        used || had_error
+    }
+
+    /// Draws the current step across the whole display.
+    ///
+    /// # Arguments
+    ///
+    /// * `lcd` - The display to draw on, at least two rows.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the step is successfully drawn.
+    /// * `Err(anyhow::Error)` - If the display has fewer than two rows or there is an error while sending data.
+    pub fn render(&mut self, lcd: &mut Lcd) -> anyhow::Result<()> {
+        if lcd.rows() < 2 {
+            return Err(anyhow::anyhow!("Widget does not fit on the display"));
+        }
+        if self.state != EditState::Editing || N == 0 {
+            return lcd.blink(false);
+        }
+        if self.drawn != Some(self.current) || self.error.is_some() {
+            self.drawn = Some(self.current);
+            lcd.blink(false)?;
+            print_line(lcd, 0, self.steps[self.current].prompt)?;
+            for row in 1..lcd.rows() {
+                print_line(lcd, row, "")?;
+            }
+        }
+        if let Some(error) = self.error {
+            // Redraw the input once the error is gone.
+            self.drawn = None;
+            return print_line(lcd, 1, error);
+        }
+        let next = self.next_label();
+        match &mut self.editor {
+            Editor::Confirm(_) => print_line(lcd, 1, next),
+            Editor::Number(editor) => editor.render(lcd, 0, 1),
+            Editor::Choice(picker) => picker.render(lcd, 0, 1, lcd.rows() - 1),
+        }
+    }
+
+    /// Returns the state of the current editor and its value.
+    fn editor_state(&self) -> (EditState, StepValue) {
+        match &self.editor {
+            Editor::Confirm(state) => (*state, StepValue::Confirmed),
+            Editor::Number(editor) => (editor.state(), StepValue::Number(editor.value())),
+            Editor::Choice(picker) => (picker.state(), StepValue::Choice(picker.selected())),
+        }
+    }
+
+    /// Validates the value of the current step and moves on if it passes.
+    fn confirm(&mut self, value: StepValue) {
+        let step = &self.steps[self.current];
+        if let Some(Err(error)) = step.validate.map(|validate| validate(value, &self.results)) {
+            self.error = Some(error);
+            // Reopen the input on the rejected value so it can be corrected.
+            self.results[self.current] = Some(value);
+            self.open(self.current);
+            return;
+        }
+        self.results[self.current] = Some(value);
+        if self.current + 1 == N {
+            self.state = EditState::Confirmed;
+        } else {
+            self.open(self.current + 1);
+        }
+    }
+
+    /// Makes a step current, starting its input from its earlier result if it has one.
+    fn open(&mut self, index: usize) {
+        self.current = index;
+        let Some(step) = self.steps.get(index) else {
+            return;
+        };
+        let previous = self.results[index];
+        self.editor = match step.input {
+            StepInput::Confirm => Editor::Confirm(EditState::Editing),
+            StepInput::Number {
+                min,
+                max,
+                step,
+                decimals,
+                unit,
+            } => {
+                let mut editor = NumberEditor::new(min, max, step)
+                    .with_decimals(decimals)
+                    .with_unit(unit);
+                if let Some(StepValue::Number(value)) = previous {
+                    editor.set_value(value);
+                }
+                Editor::Number(editor)
+            }
+            StepInput::Choice(options) => {
+                let mut picker = Picker::new(options);
+                if let Some(StepValue::Choice(index)) = previous {
+                    picker.select(index);
+                }
+                Editor::Choice(picker)
+            }
+        };
+    }
+
+    /// Returns the hint shown under a step that only needs Select.
+    fn next_label(&self) -> &'static str {
+        match self.current + 1 == N {
+            true => "> Finish",
+            false => "> Next",
+        }
+    }
+}
+
+/// Draws the prompt on the top row of its rectangle and the input, or a validation message,
+/// below it.
+#[cfg(feature = "buffer")]
+impl<const N: usize> Widget for Wizard<'_, N> {
+    fn render(&mut self, canvas: &mut Canvas) -> anyhow::Result<()> {
+        if self.state != EditState::Editing || N == 0 {
+            return Ok(());
+        }
+        canvas.print(0, 0, self.steps[self.current].prompt)?;
+        if let Some(error) = self.error {
+            return canvas.print(0, 1, error);
+        }
+        let next = self.next_label();
+        let rect = Rect::new(0, 1, canvas.width(), canvas.height().saturating_sub(1));
+        let mut below = canvas.sub(rect);
+        match &mut self.editor {
+            Editor::Confirm(_) => below.print(0, 0, next),
+            Editor::Number(editor) => Widget::render(editor, &mut below),
+            Editor::Choice(picker) => Widget::render(picker, &mut below),
+        }
+    }
+
+    fn handle(&mut self, event: InputEvent) -> bool {
+        Wizard::handle(self, event)
+    }
+}
@@ -0,0 +1,188 @@
+//! Line breaking for text that is wider than the display.
+
+use crate::charset::CharMap;
+
+/// How text is broken into lines when it does not fit the display width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrapMode {
+    /// Break at the last column, even in the middle of a word.
+    Char,
+    /// Break at spaces and after hyphens; words longer than a line are broken at the last column.
+    #[default]
+    Word,
+    /// Like [`WrapMode::Word`], but words longer than a line are broken with a trailing `-`.
+    Hyphenate,
+}
+
+/// One wrapped line of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'t> {
+    /// The text of the line, without any trailing break spaces.
+    pub text: &'t str,
+    /// Whether a `-` should be printed after the text because a long word was split.
+    pub hyphen: bool,
+    /// The number of display cells the line occupies, including the hyphen.
+    pub cells: usize,
+}
+
+impl<'t> Line<'t> {
+    /// Returns the number of display cells the line occupies.
+    pub fn len(&self) -> usize {
+        self.cells
+    }
+
+    /// Returns `true` if the line occupies no cells.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the characters of the line, including the trailing hyphen if any.
+    pub fn chars(&self) -> impl Iterator<Item = char> + 't {
+        self.text.chars().chain(self.hyphen.then_some('-'))
+    }
+}
+
+/// An iterator over the lines of text wrapped to a fixed width.
+///
+/// Explicit `'\n'` characters always start a new line. Leading spaces are dropped on lines
+/// that start because of a wrap, but kept after an explicit newline. Widths are measured in
+/// display cells, see [`DisplayWidth`](crate::charset::DisplayWidth).
+#[derive(Debug, Clone)]
+pub struct WrappedLines<'t> {
+    rest: &'t str,
+    width: usize,
+    mode: WrapMode,
+    continuation: bool,
+    charmap: CharMap<'t>,
+}
+
+impl<'t> WrappedLines<'t> {
+    /// Wraps `text` to lines of at most `width` display cells.
+    pub fn new(text: &'t str, width: u8, mode: WrapMode) -> Self {
+        Self {
+            rest: text,
+            width: width as usize,
+            mode,
+            continuation: false,
+            charmap: CharMap::default(),
+        }
+    }
+
+    /// Measures characters with `charmap`, so characters it skips take no room.
+    pub fn with_charmap(mut self, charmap: CharMap<'t>) -> Self {
+        self.charmap = charmap;
+        self
+    }
+}
+
+impl WrappedLines<'_> {
+    /// Changes the width used for the following lines.
+    pub fn set_width(&mut self, width: u8) {
+        self.width = width as usize;
+    }
+
+    /// Returns `true` if no text is left to wrap.
+    pub fn is_exhausted(&self) -> bool {
+        let rest = if self.continuation && self.mode != WrapMode::Char {
+            self.rest.trim_start_matches(' ')
+        } else {
+            self.rest
+        };
+        rest.is_empty()
+    }
+}
+
+impl<'t> Iterator for WrappedLines<'t> {
+    type Item = Line<'t>;
+
+    fn next(&mut self) -> Option<Line<'t>> {
+        if self.width == 0 {
+            return None;
+        }
+        if self.continuation && self.mode != WrapMode::Char {
+            self.rest = self.rest.trim_start_matches(' ');
+        }
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        // Byte offset of the first character that does not fit, or of a newline before it.
+        let mut end = self.rest.len();
+        let mut cells = 0;
+        for (i, ch) in self.rest.char_indices() {
+            if ch == '\n' {
+                let text = &self.rest[..i];
+                self.rest = &self.rest[i + 1..];
+                self.continuation = false;
+                return Some(Line {
+                    text,
+                    hyphen: false,
+                    cells,
+                });
+            }
+            let width = self.charmap.width(ch);
+            if cells + width > self.width {
+                end = i;
+                break;
+            }
+            cells += width;
+        }
+
+        if end == self.rest.len() {
+            let text = self.rest;
+            self.rest = "";
+            return Some(Line {
+                text,
+                hyphen: false,
+                cells,
+            });
+        }
+
+        self.continuation = true;
+        let (text, hyphen, next) = match self.mode {
+            WrapMode::Char => (&self.rest[..end], false, end),
+            WrapMode::Word | WrapMode::Hyphenate => match self.word_break(end) {
+                Some(at) => (self.rest[..at].trim_end_matches(' '), false, at),
+                None if self.mode == WrapMode::Hyphenate && self.width > 1 => {
+                    let at = self.rest[..end]
+                        .char_indices()
+                        .rev()
+                        .find(|&(_, ch)| self.charmap.width(ch) > 0)
+                        .map_or(end, |(i, _)| i);
+                    (&self.rest[..at], true, at)
+                }
+                None => (&self.rest[..end], false, end),
+            },
+        };
+        self.rest = &self.rest[next..];
+        let cells = self.measure(text) + usize::from(hyphen);
+        Some(Line {
+            text,
+            hyphen,
+            cells,
+        })
+    }
+}
+
+impl WrappedLines<'_> {
+    fn measure(&self, text: &str) -> usize {
+        text.chars().map(|ch| self.charmap.width(ch)).sum()
+    }
+
+    /// Finds the last byte offset at or before `end` where the line may be broken.
+    fn word_break(&self, end: usize) -> Option<usize> {
+        let head = &self.rest[..end];
+        let next_is_space = self.rest[end..].starts_with(' ');
+        if next_is_space {
+            return Some(end);
+        }
+        head.char_indices()
+            .rev()
+            .find_map(|(i, ch)| match ch {
+                ' ' => Some(i),
+                '-' => Some(i + 1),
+                _ => None,
+            })
+            .filter(|&at| at > 0)
+    }
+}